@@ -0,0 +1,161 @@
+use crate::{
+    client::{Client, Image, Rename, Replayed},
+    model::{Account, Freezer, Product, Revision, Summary},
+    owners::Owner,
+    transfer::Commit,
+    utils::Result,
+};
+use async_trait::async_trait;
+use std::fmt::Debug;
+
+/// Backend of the freezers, views hold it as `Arc<dyn FreezerApi>`
+/// so the HTTP [`Client`] can be replaced by an in-memory, caching or recording one
+#[async_trait]
+pub trait FreezerApi: Debug + Send + Sync {
+    async fn login(&self, login: &str) -> Result<Account>;
+
+    /// See [`Client::image`]
+    async fn image(&self, id: &str, etag: Option<&str>) -> Result<Image>;
+
+    async fn freezer(&self, id: &str) -> Result<Freezer>;
+
+    /// `Ok(None)` if the backend refused the update
+    async fn update_freezer(&self, freezer: Freezer) -> Result<Option<Freezer>>;
+
+    /// `Ok(false)` if the backend refused the deletion
+    async fn delete_freezer(&self, id: &str) -> Result<bool>;
+
+    /// See [`Client::rename_freezer`], refused if the backend can't rename
+    async fn rename_freezer(&self, _old: &str, _new: &str) -> Result<Rename> {
+        Ok(Rename::Refused)
+    }
+
+    /// See [`Client::commit_transfer`], unsupported by default
+    async fn commit_transfer(&self, _from: Freezer, _to: Freezer) -> Result<Commit> {
+        Ok(Commit::Unsupported)
+    }
+
+    async fn freezers(&self) -> Result<Vec<String>>;
+
+    async fn freezers_by(&self, limit: Option<usize>, offset: Option<usize>)
+    -> Result<Vec<String>>;
+
+    /// See [`Client::freezers_owned`]
+    async fn freezers_owned(
+        &self,
+        owner: &Owner,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<String>>;
+
+    /// See [`Client::users`], empty if the backend doesn't list them
+    async fn users(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    async fn product(&self, id: &str) -> Result<Option<Product>>;
+
+    /// `Ok(None)` if the backend can't aggregate, see [`crate::stock::crawl`]
+    async fn summary(&self) -> Result<Option<Summary>> {
+        Ok(None)
+    }
+
+    /// See [`Client::freezer_history`], empty if the backend keeps no history
+    async fn freezer_history(&self, _id: &str) -> Result<Vec<Revision>> {
+        Ok(Vec::new())
+    }
+
+    /// Backend serves the local copy because the server is unreachable
+    fn is_offline(&self) -> bool {
+        false
+    }
+
+    /// Count of mutations waiting for [`FreezerApi::replay`]
+    fn pending(&self) -> usize {
+        0
+    }
+
+    async fn replay(&self) -> Result<Vec<Replayed>> {
+        Ok(Vec::new())
+    }
+}
+
+#[async_trait]
+impl FreezerApi for Client {
+    async fn login(&self, login: &str) -> Result<Account> {
+        Self::login(self, login).await
+    }
+
+    async fn image(&self, id: &str, etag: Option<&str>) -> Result<Image> {
+        Self::image(self, id, etag).await
+    }
+
+    async fn freezer(&self, id: &str) -> Result<Freezer> {
+        Self::freezer(self, id).await
+    }
+
+    async fn update_freezer(&self, freezer: Freezer) -> Result<Option<Freezer>> {
+        Self::update_freezer(self, freezer).await
+    }
+
+    async fn delete_freezer(&self, id: &str) -> Result<bool> {
+        Self::delete_freezer(self, id).await
+    }
+
+    async fn rename_freezer(&self, old: &str, new: &str) -> Result<Rename> {
+        Self::rename_freezer(self, old, new).await
+    }
+
+    async fn commit_transfer(&self, from: Freezer, to: Freezer) -> Result<Commit> {
+        Self::commit_transfer(self, from, to).await
+    }
+
+    async fn freezers(&self) -> Result<Vec<String>> {
+        Self::freezers(self).await
+    }
+
+    async fn freezers_by(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<String>> {
+        Self::freezers_by(self, limit, offset).await
+    }
+
+    async fn freezers_owned(
+        &self,
+        owner: &Owner,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<String>> {
+        Self::freezers_owned(self, owner, limit, offset).await
+    }
+
+    async fn users(&self) -> Result<Vec<String>> {
+        Self::users(self).await
+    }
+
+    async fn product(&self, id: &str) -> Result<Option<Product>> {
+        Self::product(self, id).await
+    }
+
+    async fn summary(&self) -> Result<Option<Summary>> {
+        Self::summary(self).await
+    }
+
+    async fn freezer_history(&self, id: &str) -> Result<Vec<Revision>> {
+        Self::freezer_history(self, id).await
+    }
+
+    fn is_offline(&self) -> bool {
+        Self::is_offline(self)
+    }
+
+    fn pending(&self) -> usize {
+        Self::pending(self)
+    }
+
+    async fn replay(&self) -> Result<Vec<Replayed>> {
+        Self::replay(self).await
+    }
+}
@@ -0,0 +1,203 @@
+//! Blocking facade of [`crate::Client`] for callers without an async runtime.
+//!
+//! Every call blocks the current thread on the client's own runtime,
+//! so it must not be used from within an async context.
+
+use crate::{
+    client::{self, Image, Rename, Replayed},
+    model::{Account, Freezer, Product, Revision, Summary},
+    owners::Owner,
+    store::Store,
+    transfer::Commit,
+    utils::Result,
+};
+use std::fmt::{Debug, Formatter};
+use tokio::runtime::{self, Runtime};
+
+/// Blocking [`crate::Client`], every method behaves as the async one of the same name
+pub struct Client {
+    inner: client::Client,
+    runtime: Runtime,
+}
+
+impl Debug for Client {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl Client {
+    /// `api` is the API root, e.g. [`crate::Client::DEFAULT_API`]
+    ///
+    /// # Errors
+    /// If the runtime can't be started
+    pub fn new(api: &str) -> Result<Self> {
+        Ok(Self {
+            inner: client::Client::new(api, reqwest::Client::new()),
+            runtime: runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+        })
+    }
+
+    /// Enables offline mode: fetched data is copied to the `store`
+    /// and mutations are queued there while the server is unreachable
+    #[must_use]
+    pub fn with_store(self, store: Store) -> Self {
+        Self {
+            inner: self.inner.with_store(store),
+            ..self
+        }
+    }
+
+    /// Last request was served by the store because the server is unreachable
+    #[must_use]
+    pub fn is_offline(&self) -> bool {
+        self.inner.is_offline()
+    }
+
+    /// Count of mutations waiting for [`Client::replay`]
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.inner.pending()
+    }
+
+    /// Checks the credentials and tells what the user may do,
+    /// always succeeds while offline - with [`Account::unknown`]
+    ///
+    /// # Errors
+    /// If the server refuses the login or the request fails
+    pub fn login(&self, login: &str) -> Result<Account> {
+        self.runtime.block_on(self.inner.login(login))
+    }
+
+    /// Conditional image request: pass `etag` of the cached image
+    /// to get [`Image::NotModified`] instead of the same bytes
+    ///
+    /// # Errors
+    /// If the request fails, images are not available offline
+    pub fn image(&self, id: &str, etag: Option<&str>) -> Result<Image> {
+        self.runtime.block_on(self.inner.image(id, etag))
+    }
+
+    /// # Errors
+    /// If the freezer does not exist, the request fails
+    /// or the freezer is not in the store while offline
+    pub fn freezer(&self, id: &str) -> Result<Freezer> {
+        self.runtime.block_on(self.inner.freezer(id))
+    }
+
+    /// Returns the freezer as the server stored it, `Ok(None)` if the server refused
+    /// the update (e.g. not enough privileges). While offline the update is queued
+    /// and returned as is
+    ///
+    /// # Errors
+    /// If the request fails
+    pub fn update_freezer(&self, freezer: Freezer) -> Result<Option<Freezer>> {
+        self.runtime.block_on(self.inner.update_freezer(freezer))
+    }
+
+    /// `Ok(false)` if the server refused the deletion. While offline the deletion
+    /// is queued
+    ///
+    /// # Errors
+    /// If the request fails
+    pub fn delete_freezer(&self, id: &str) -> Result<bool> {
+        self.runtime.block_on(self.inner.delete_freezer(id))
+    }
+
+    /// Page of the freezers of `owner`, while offline the stored freezers are filtered
+    ///
+    /// # Errors
+    /// If the request fails or the response is malformed
+    pub fn freezers_owned(
+        &self,
+        owner: &Owner,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<String>> {
+        self.runtime
+            .block_on(self.inner.freezers_owned(owner, limit, offset))
+    }
+
+    /// Logins of the server users, empty if the server doesn't list them
+    ///
+    /// # Errors
+    /// If the request fails or the response is malformed
+    pub fn users(&self) -> Result<Vec<String>> {
+        self.runtime.block_on(self.inner.users())
+    }
+
+    /// Gives the freezer a new id, its image and history follow it.
+    /// Not queued while offline: the old id may be taken by then
+    ///
+    /// # Errors
+    /// If the new name is empty, the freezer does not exist or the request fails
+    pub fn rename_freezer(&self, old: &str, new: &str) -> Result<Rename> {
+        self.runtime.block_on(self.inner.rename_freezer(old, new))
+    }
+
+    /// Stores both freezers of the transfer at once, see [`crate::transfer::run`].
+    /// [`Commit::Unsupported`] while offline - two queued updates do the job
+    ///
+    /// # Errors
+    /// If either freezer does not exist, the request fails or the response is malformed
+    pub fn commit_transfer(&self, from: Freezer, to: Freezer) -> Result<Commit> {
+        self.runtime.block_on(self.inner.commit_transfer(from, to))
+    }
+
+    /// Ids of all freezers
+    ///
+    /// # Errors
+    /// If the request fails or the response is malformed
+    pub fn freezers(&self) -> Result<Vec<String>> {
+        self.runtime.block_on(self.inner.freezers())
+    }
+
+    /// Page of [`Client::freezers`]
+    ///
+    /// # Errors
+    /// If the request fails or the response is malformed
+    pub fn freezers_by(
+        &self,
+        limit: impl Into<Option<usize>> + Send + Copy,
+        offset: impl Into<Option<usize>> + Send + Copy,
+    ) -> Result<Vec<String>> {
+        self.runtime.block_on(self.inner.freezers_by(limit, offset))
+    }
+
+    /// `Ok(None)` if the product does not exist
+    ///
+    /// # Errors
+    /// If the request fails or the product is not in the store while offline
+    pub fn product(&self, id: &str) -> Result<Option<Product>> {
+        self.runtime.block_on(self.inner.product(id))
+    }
+
+    /// Aggregate of the server, `Ok(None)` if the server can't aggregate
+    /// (or is unreachable) - see [`crate::stock::crawl`]
+    ///
+    /// # Errors
+    /// If the request fails or the response is malformed
+    pub fn summary(&self) -> Result<Option<Summary>> {
+        self.runtime.block_on(self.inner.summary())
+    }
+
+    /// Revisions of the freezer, the oldest first.
+    /// Empty if the server has no history of it
+    ///
+    /// # Errors
+    /// If the request fails, history is not available offline
+    pub fn freezer_history(&self, id: &str) -> Result<Vec<Revision>> {
+        self.runtime.block_on(self.inner.freezer_history(id))
+    }
+
+    /// Replays queued mutations in order, stops (keeping the rest queued)
+    /// once the server is unreachable again
+    ///
+    /// # Errors
+    /// Never for now - failures of the single mutations are reported as [`crate::Outcome::Failed`]
+    pub fn replay(&self) -> Result<Vec<Replayed>> {
+        self.runtime.block_on(self.inner.replay())
+    }
+}
@@ -0,0 +1,633 @@
+use crate::{
+    model::{Account, Freezer, Product, Revision, Summary},
+    owners::Owner,
+    store::{Mutation, Store},
+    transfer::Commit,
+    utils::{Error, Result, error::anyio},
+};
+use bytes::Bytes;
+use json::json;
+
+use reqwest::{Response, StatusCode, header};
+use std::{
+    fmt::{Debug, Formatter},
+    ops::Deref,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+#[cfg(test)]
+mod tests;
+
+macro_rules! api {
+    ($api:expr, $($tt:tt)*) => {
+        format!("{}/{}", $api, format!($($tt)*))
+    };
+}
+
+/// Response of [`Client::image`]
+#[derive(Debug, Clone)]
+pub enum Image {
+    Fresh { etag: Option<String>, bytes: Bytes },
+    NotModified,
+    Missing,
+}
+
+/// Response of [`Client::rename_freezer`]
+#[derive(Debug, Clone)]
+// returned right away, not worth the boxing
+#[allow(clippy::large_enum_variant)]
+pub enum Rename {
+    Renamed(Freezer),
+    /// Another freezer has the name already
+    Taken,
+    /// Server refused the rename (e.g. not enough privileges)
+    Refused,
+}
+
+/// Result of the replayed [`Mutation`]
+#[derive(Debug, Clone)]
+// reported once per replay, not worth the boxing
+#[allow(clippy::large_enum_variant)]
+pub enum Outcome {
+    Applied,
+    /// Freezer was changed on the server since the mutation was made,
+    /// mutation is dropped
+    Conflict {
+        server: Option<Freezer>,
+    },
+    /// Server refused the mutation (e.g. not enough privileges)
+    Rejected,
+    Failed(Error),
+}
+
+/// Entry of the [`Client::replay`] report
+#[derive(Debug, Clone)]
+pub struct Replayed {
+    pub mutation: Mutation,
+    pub outcome: Outcome,
+}
+
+/// HTTP client of the freezers API, see the [crate docs](crate)
+pub struct Client {
+    api: String,
+    inner: reqwest::Client,
+    /// local copy to serve requests while the server is unreachable
+    store: Option<Arc<Store>>,
+    offline: AtomicBool,
+}
+
+impl Debug for Client {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Client {{ api: {}, client: reqwest::Client }}", self.api)
+    }
+}
+
+impl Deref for Client {
+    type Target = reqwest::Client;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl Client {
+    /// API root of the server started locally with default settings
+    pub const DEFAULT_API: &'static str = "http://localhost:1228/api";
+
+    /// `api` is the API root, e.g. [`Client::DEFAULT_API`]
+    #[must_use]
+    pub fn new(api: &str, inner: reqwest::Client) -> Self {
+        Self {
+            api: api.to_owned(),
+            inner,
+            store: None,
+            offline: AtomicBool::new(false),
+        }
+    }
+
+    /// Enables offline mode: fetched data is copied to the `store`
+    /// and mutations are queued there while the server is unreachable
+    #[must_use]
+    pub fn with_store(self, store: Store) -> Self {
+        Self {
+            store: Some(Arc::new(store)),
+            ..self
+        }
+    }
+
+    /// Last request was served by the store because the server is unreachable
+    #[must_use]
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    /// Count of mutations waiting for [`Client::replay`]
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.store.as_deref().map_or(0, Store::pending)
+    }
+
+    /// Server is unreachable and the store can serve the request instead
+    fn unreachable(&self, error: &reqwest::Error) -> bool {
+        let unreachable = self.store.is_some() && (error.is_connect() || error.is_timeout());
+        if unreachable {
+            self.offline.store(true, Ordering::Relaxed);
+        }
+        unreachable
+    }
+
+    /// `Ok(None)` if the request must be served by the store
+    fn online(&self, res: reqwest::Result<Response>) -> Result<Option<Response>> {
+        match res {
+            Ok(res) => {
+                self.offline.store(false, Ordering::Relaxed);
+                Ok(Some(res))
+            }
+            Err(error) if self.unreachable(&error) => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Runs `f` on the blocking pool: the file IO of the store must not stall the runtime.
+    /// `None` without the store
+    async fn on_store<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Store) -> T + Send + 'static,
+    ) -> Option<T> {
+        let store = self.store.clone()?;
+        tokio::task::spawn_blocking(move || f(&store)).await.ok()
+    }
+
+    async fn stored_freezers(&self, limit: Option<usize>, offset: Option<usize>) -> Vec<String> {
+        self.on_store(move |store| store.freezers(limit, offset))
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn cached<T: Send + 'static>(
+        &self,
+        id: &str,
+        f: impl FnOnce(&Store) -> Option<T> + Send + 'static,
+    ) -> Result<T> {
+        self.on_store(f)
+            .await
+            .flatten()
+            .ok_or_else(|| anyio!("`{id}` is not available offline"))
+    }
+
+    async fn store(&self, f: impl FnOnce(&Store) + Send + 'static) {
+        self.on_store(f).await;
+    }
+
+    /// Checks the credentials and tells what the user may do,
+    /// always succeeds while offline - with [`Account::unknown`]
+    ///
+    /// # Errors
+    /// If the server refuses the login or the request fails
+    pub async fn login(&self, login: &str) -> Result<Account> {
+        let res = self
+            .get(api!(self.api, "auth"))
+            .json(&json!(
+                {
+                    "login": login,
+                }
+            ))
+            .send()
+            .await;
+        match self.online(res)? {
+            Some(res) => {
+                let bytes = res.error_for_status()?.bytes().await?;
+                // older servers answer with the login only (or nothing at all)
+                Ok(json::from_slice(&bytes).unwrap_or_else(|_| Account::unknown(login)))
+            }
+            None => Ok(Account::unknown(login)),
+        }
+    }
+
+    /// Conditional image request: pass `etag` of the cached image
+    /// to get [`Image::NotModified`] instead of the same bytes
+    ///
+    /// # Errors
+    /// If the request fails, images are not available offline
+    pub async fn image(&self, id: &str, etag: Option<&str>) -> Result<Image> {
+        let mut request = self.get(api!(self.api, "freezers/{id}/image"));
+        if let Some(etag) = etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+
+        let res = request.send().await?;
+        match res.status() {
+            StatusCode::NOT_MODIFIED => Ok(Image::NotModified),
+            StatusCode::NOT_FOUND => Ok(Image::Missing),
+            _ => {
+                let res = res.error_for_status()?;
+                let etag = res
+                    .headers()
+                    .get(header::ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(ToOwned::to_owned);
+                Ok(Image::Fresh {
+                    etag,
+                    bytes: res.bytes().await?,
+                })
+            }
+        }
+    }
+
+    /// # Errors
+    /// If the freezer does not exist, the request fails
+    /// or the freezer is not in the store while offline
+    pub async fn freezer(&self, id: &str) -> Result<Freezer> {
+        let res = self.get(api!(self.api, "freezers/{id}")).send().await;
+        if let Some(res) = self.online(res)? {
+            let freezer: Freezer = res.json().await?;
+            let stored = freezer.clone();
+            self.store(move |store| store.put_freezer(&stored)).await;
+            Ok(freezer)
+        } else {
+            let owned = id.to_owned();
+            self.cached(id, move |store| store.freezer(&owned)).await
+        }
+    }
+
+    async fn remote_update(&self, freezer: &Freezer) -> reqwest::Result<Option<Freezer>> {
+        let res = self
+            .post(api!(self.api, "freezers/update"))
+            .json(freezer)
+            .send()
+            .await?;
+        if res.status().is_success() {
+            Ok(Some(res.json().await?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the freezer as the server stored it, `Ok(None)` if the server refused
+    /// the update (e.g. not enough privileges). While offline the update is queued
+    /// and returned as is
+    ///
+    /// # Errors
+    /// If the request fails
+    pub async fn update_freezer(&self, freezer: Freezer) -> Result<Option<Freezer>> {
+        match self.remote_update(&freezer).await {
+            Ok(updated) => {
+                self.offline.store(false, Ordering::Relaxed);
+                if let Some(stored) = updated.clone() {
+                    self.store(move |store| store.put_freezer(&stored)).await;
+                }
+                Ok(updated)
+            }
+            Err(error) if self.unreachable(&error) => {
+                let queued = freezer.clone();
+                self.store(move |store| {
+                    let base = store.freezer(&queued.name);
+                    store.queue(Mutation::Update {
+                        base,
+                        freezer: queued,
+                    });
+                })
+                .await;
+                Ok(Some(freezer))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// `Ok(false)` if the server refused the deletion. While offline the deletion
+    /// is queued
+    ///
+    /// # Errors
+    /// If the request fails
+    pub async fn delete_freezer(&self, id: &str) -> Result<bool> {
+        let res = self.delete(api!(self.api, "freezers/{id}")).send().await;
+        let owned = id.to_owned();
+        let Some(res) = self.online(res)? else {
+            self.store(move |store| {
+                let base = store.freezer(&owned);
+                store.queue(Mutation::Delete { base, id: owned });
+            })
+            .await;
+            return Ok(true);
+        };
+
+        let is_deleted = res.status().is_success();
+        if is_deleted {
+            self.store(move |store| store.remove_freezer(&owned)).await;
+        }
+        Ok(is_deleted)
+    }
+
+    /// Gives the freezer a new id, its image and history follow it.
+    /// Not queued while offline: the old id may be taken by then
+    ///
+    /// # Errors
+    /// If the new name is empty, the freezer does not exist or the request fails
+    pub async fn rename_freezer(&self, old: &str, new: &str) -> Result<Rename> {
+        if new.trim().is_empty() {
+            return Err(anyio!("new name of `{old}` is empty"));
+        }
+
+        let res = self
+            .post(api!(self.api, "freezers/{old}/rename"))
+            .json(&json!(
+                {
+                    "name": new,
+                }
+            ))
+            .send()
+            .await;
+        let Some(res) = self.online(res)? else {
+            return Err(anyio!("`{old}` cannot be renamed offline"));
+        };
+
+        match res.status() {
+            StatusCode::CONFLICT => Ok(Rename::Taken),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Ok(Rename::Refused),
+            StatusCode::NOT_FOUND => Err(anyio!("`{old}` does not exist")),
+            _ => {
+                let freezer: Freezer = res.error_for_status()?.json().await?;
+                let (old, stored) = (old.to_owned(), freezer.clone());
+                self.store(move |store| {
+                    store.remove_freezer(&old);
+                    store.put_freezer(&stored);
+                })
+                .await;
+                Ok(Rename::Renamed(freezer))
+            }
+        }
+    }
+
+    /// Stores both freezers of the transfer at once, see [`crate::transfer::run`].
+    /// [`Commit::Unsupported`] while offline - two queued updates do the job
+    ///
+    /// # Errors
+    /// If either freezer does not exist, the request fails or the response is malformed
+    pub async fn commit_transfer(&self, from: Freezer, to: Freezer) -> Result<Commit> {
+        let res = self
+            .post(api!(self.api, "freezers/transfer"))
+            .json(&json!(
+                {
+                    "from": from,
+                    "to": to,
+                }
+            ))
+            .send()
+            .await;
+        let Some(res) = self.online(res)? else {
+            return Ok(Commit::Unsupported);
+        };
+
+        match res.status() {
+            StatusCode::NOT_FOUND
+            | StatusCode::METHOD_NOT_ALLOWED
+            | StatusCode::NOT_IMPLEMENTED => Ok(Commit::Unsupported),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Ok(Commit::Refused),
+            StatusCode::UNPROCESSABLE_ENTITY => {
+                Err(anyio!("`{}` or `{}` does not exist", from.name, to.name))
+            }
+            _ => {
+                #[derive(serde::Deserialize)]
+                struct Committed {
+                    from: Freezer,
+                    to: Freezer,
+                }
+                let Committed { from, to } = res.error_for_status()?.json().await?;
+                let stored = (from.clone(), to.clone());
+                self.store(move |store| {
+                    store.put_freezer(&stored.0);
+                    store.put_freezer(&stored.1);
+                })
+                .await;
+                Ok(Commit::Committed { from, to })
+            }
+        }
+    }
+
+    /// Ids of all freezers
+    ///
+    /// # Errors
+    /// If the request fails or the response is malformed
+    pub async fn freezers(&self) -> Result<Vec<String>> {
+        let res = self.get(api!(self.api, "freezers")).send().await;
+        match self.online(res)? {
+            Some(res) => {
+                let freezers: Vec<String> = res.json().await?;
+                let stored = freezers.clone();
+                self.store(move |store| store.put_freezers(&stored)).await;
+                Ok(freezers)
+            }
+            None => Ok(self.stored_freezers(None, None).await),
+        }
+    }
+
+    /// `Ok(None)` if the product does not exist
+    ///
+    /// # Errors
+    /// If the request fails or the product is not in the store while offline
+    pub async fn product(&self, id: &str) -> Result<Option<Product>> {
+        match self.online(self.get(api!(self.api, "products/{id}")).send().await) {
+            Ok(Some(res)) => {
+                if res.status() == StatusCode::NOT_FOUND {
+                    Ok(None)
+                } else {
+                    let product: Product = res.json().await?;
+                    let stored = product.clone();
+                    self.store(move |store| store.put_product(&stored)).await;
+                    Ok(Some(product))
+                }
+            }
+            Ok(None) => {
+                let owned = id.to_owned();
+                self.cached(id, move |store| store.product(&owned))
+                    .await
+                    .map(Some)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Page of [`Client::freezers`]
+    ///
+    /// # Errors
+    /// If the request fails or the response is malformed
+    pub async fn freezers_by(
+        &self,
+        limit: impl Into<Option<usize>> + Send + Copy,
+        offset: impl Into<Option<usize>> + Send + Copy,
+    ) -> Result<Vec<String>> {
+        let res = self
+            .get(api!(self.api, "freezers"))
+            .query(&json!(
+                {
+                    "limit": limit.into(),
+                    "offset": offset.into(),
+                }
+            ))
+            .send()
+            .await;
+        match self.online(res)? {
+            Some(res) => {
+                let freezers: Vec<String> = res.json().await?;
+                let stored = freezers.clone();
+                self.store(move |store| store.put_freezers(&stored)).await;
+                Ok(freezers)
+            }
+            None => Ok(self.stored_freezers(limit.into(), offset.into()).await),
+        }
+    }
+
+    /// Page of the freezers of `owner`, while offline the stored freezers are filtered
+    ///
+    /// # Errors
+    /// If the request fails or the response is malformed
+    pub async fn freezers_owned(
+        &self,
+        owner: &Owner,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<String>> {
+        let mut query = vec![match owner {
+            Owner::Nobody => ("unowned", "true".to_owned()),
+            Owner::User(user) => ("owner", user.clone()),
+        }];
+        query.extend(limit.map(|limit| ("limit", limit.to_string())));
+        query.extend(offset.map(|offset| ("offset", offset.to_string())));
+
+        let res = self
+            .get(api!(self.api, "freezers"))
+            .query(&query)
+            .send()
+            .await;
+        if let Some(res) = self.online(res)? {
+            let freezers: Vec<String> = res.error_for_status()?.json().await?;
+            let stored = freezers.clone();
+            self.store(move |store| store.put_freezers(&stored)).await;
+            Ok(freezers)
+        } else {
+            let owner = owner.clone();
+            Ok(self
+                .on_store(move |store| {
+                    store
+                        .freezers(None, None)
+                        .into_iter()
+                        .filter(|id| {
+                            store
+                                .freezer(id)
+                                .is_some_and(|freezer| owner.matches(freezer.owner.as_deref()))
+                        })
+                        .skip(offset.unwrap_or_default())
+                        .take(limit.unwrap_or(usize::MAX))
+                        .collect()
+                })
+                .await
+                .unwrap_or_default())
+        }
+    }
+
+    /// Logins of the server users, empty if the server doesn't list them
+    ///
+    /// # Errors
+    /// If the request fails or the response is malformed
+    pub async fn users(&self) -> Result<Vec<String>> {
+        let res = self.get(api!(self.api, "users")).send().await;
+        match self.online(res)? {
+            Some(res) if res.status() == StatusCode::NOT_FOUND => Ok(Vec::new()),
+            Some(res) => Ok(res.error_for_status()?.json().await?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Aggregate of the server, `Ok(None)` if the server can't aggregate
+    /// (or is unreachable) - see [`crate::stock::crawl`]
+    ///
+    /// # Errors
+    /// If the request fails or the response is malformed
+    pub async fn summary(&self) -> Result<Option<Summary>> {
+        let res = self.get(api!(self.api, "summary")).send().await;
+        match self.online(res)? {
+            Some(res) if res.status() == StatusCode::NOT_FOUND => Ok(None),
+            Some(res) => Ok(Some(res.error_for_status()?.json().await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Revisions of the freezer, the oldest first.
+    /// Empty if the server has no history of it
+    ///
+    /// # Errors
+    /// If the request fails, history is not available offline
+    pub async fn freezer_history(&self, id: &str) -> Result<Vec<Revision>> {
+        let res = self
+            .get(api!(self.api, "freezers/{id}/history"))
+            .send()
+            .await;
+        match self.online(res)? {
+            Some(res) if res.status() == StatusCode::NOT_FOUND => Ok(Vec::new()),
+            Some(res) => Ok(res.error_for_status()?.json().await?),
+            None => Err(anyio!("history of `{id}` is not available offline")),
+        }
+    }
+
+    async fn remote_freezer(&self, id: &str) -> reqwest::Result<Option<Freezer>> {
+        let res = self.get(api!(self.api, "freezers/{id}")).send().await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            Ok(None)
+        } else {
+            res.error_for_status()?.json().await.map(Some)
+        }
+    }
+
+    async fn replay_one(&self, mutation: &Mutation) -> reqwest::Result<Outcome> {
+        let server = self.remote_freezer(mutation.id()).await?;
+        if server.as_ref() != mutation.base() {
+            return Ok(Outcome::Conflict { server });
+        }
+
+        let is_applied = match mutation {
+            Mutation::Update { freezer, .. } => self.remote_update(freezer).await?.is_some(),
+            Mutation::Delete { id, .. } => self
+                .delete(api!(self.api, "freezers/{id}"))
+                .send()
+                .await?
+                .status()
+                .is_success(),
+        };
+        Ok(if is_applied {
+            Outcome::Applied
+        } else {
+            Outcome::Rejected
+        })
+    }
+
+    /// Replays queued mutations in order, stops (keeping the rest queued)
+    /// once the server is unreachable again
+    ///
+    /// # Errors
+    /// Never for now - failures of the single mutations are reported as [`Outcome::Failed`]
+    pub async fn replay(&self) -> Result<Vec<Replayed>> {
+        let mut report = Vec::new();
+        let mut outbox = self
+            .on_store(Store::take_outbox)
+            .await
+            .unwrap_or_default()
+            .into_iter();
+        while let Some(mutation) = outbox.next() {
+            let outcome = match self.replay_one(&mutation).await {
+                Ok(outcome) => outcome,
+                Err(error) if self.unreachable(&error) => {
+                    let unapplied = std::iter::once(mutation).chain(outbox).collect();
+                    self.store(move |store| store.restore(unapplied)).await;
+                    break;
+                }
+                Err(error) => Outcome::Failed(error.into()),
+            };
+            self.offline.store(false, Ordering::Relaxed);
+            report.push(Replayed { mutation, outcome });
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,268 @@
+use super::{Client, Image, Outcome, Rename};
+use crate::{
+    model::{Account, Freezer, Permission},
+    store::Store,
+    utils::path::temp_dir,
+};
+use mock_server::{Fault, MockServer, PIXEL, Route, Seed};
+
+async fn setup() -> (MockServer, Client) {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let client = Client::new(&server.api(), reqwest::Client::new());
+    (server, client)
+}
+
+#[tokio::test]
+async fn login() {
+    let (_server, client) = setup().await;
+
+    let admin = client.login("admin").await.unwrap();
+    assert_eq!(admin.roles, ["admin"]);
+    assert!(admin.can(Permission::Update) && admin.can(Permission::Delete));
+
+    let guest = client.login("guest").await.unwrap();
+    assert!(guest.is_read_only());
+    assert!(!guest.can(Permission::Update));
+
+    assert!(client.login("stranger").await.is_err());
+}
+
+#[tokio::test]
+async fn login_without_roles() {
+    let mut seed = Seed::demo();
+    seed.roles.clear();
+    let server = MockServer::start(seed).await.unwrap();
+    let client = Client::new(&server.api(), reqwest::Client::new());
+
+    let admin = client.login("admin").await.unwrap();
+    assert_eq!(admin, Account::unknown("admin"));
+    assert!(admin.can(Permission::Delete) && !admin.is_read_only());
+}
+
+#[tokio::test]
+async fn freezers() {
+    let (server, client) = setup().await;
+
+    assert_eq!(
+        client.freezers().await.unwrap(),
+        ["garage", "kitchen", "office"]
+    );
+    assert_eq!(
+        client.freezers_by(2, 0).await.unwrap(),
+        ["garage", "kitchen"]
+    );
+    assert_eq!(client.freezers_by(2, 2).await.unwrap(), ["office"]);
+    assert!(client.freezers_by(2, 3).await.unwrap().is_empty());
+
+    server.inject(Route::Freezers, Fault::Malformed);
+    assert!(client.freezers().await.is_err());
+}
+
+#[tokio::test]
+async fn freezer() {
+    let (server, client) = setup().await;
+
+    let freezer = client.freezer("kitchen").await.unwrap();
+    assert_eq!(freezer.name, "kitchen");
+    assert_eq!(freezer.model.year, 2015);
+    assert_eq!(freezer.owner.as_deref(), Some("admin"));
+    assert_eq!(freezer.products["ice-cream"], 10);
+
+    assert!(client.freezer("nowhere").await.is_err());
+
+    server.inject(Route::Freezer, Fault::Status(500));
+    assert!(client.freezer("kitchen").await.is_err());
+}
+
+#[tokio::test]
+async fn image() {
+    let (server, client) = setup().await;
+
+    let Image::Fresh { etag, bytes } = client.image("kitchen", None).await.unwrap() else {
+        panic!("expected fresh image");
+    };
+    assert_eq!(bytes.as_ref(), PIXEL);
+
+    let etag = etag.expect("mock server sends `ETag`");
+    assert!(matches!(
+        client.image("kitchen", Some(&etag)).await.unwrap(),
+        Image::NotModified
+    ));
+
+    server.put_image("kitchen", b"other".to_vec());
+    assert!(matches!(
+        client.image("kitchen", Some(&etag)).await.unwrap(),
+        Image::Fresh { .. }
+    ));
+
+    assert!(matches!(
+        client.image("garage", None).await.unwrap(),
+        Image::Missing
+    ));
+
+    server.inject(Route::Image, Fault::Status(500));
+    assert!(client.image("kitchen", None).await.is_err());
+}
+
+#[tokio::test]
+async fn update_freezer() {
+    let (server, client) = setup().await;
+
+    let mut freezer = client.freezer("garage").await.unwrap();
+    freezer.owner = Some("guest".into());
+    freezer.products.insert("berries".into(), 7);
+
+    let updated = client.update_freezer(freezer.clone()).await.unwrap();
+    assert_eq!(updated, Some(freezer.clone()));
+    assert_eq!(server.freezer("garage").unwrap()["products"]["berries"], 7);
+
+    server.inject(Route::Update, Fault::Status(403));
+    assert_eq!(client.update_freezer(freezer).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn delete_freezer() {
+    let (server, client) = setup().await;
+
+    assert!(client.delete_freezer("office").await.unwrap());
+    assert!(server.freezer("office").is_none());
+    assert!(!client.delete_freezer("office").await.unwrap());
+
+    server.inject(Route::Delete, Fault::Status(403));
+    assert!(!client.delete_freezer("kitchen").await.unwrap());
+    assert!(server.freezer("kitchen").is_some());
+}
+
+#[tokio::test]
+async fn rename_freezer() {
+    let (server, client) = setup().await;
+
+    let Rename::Renamed(freezer) = client.rename_freezer("kitchen", "pantry").await.unwrap() else {
+        panic!("freezer is renamed");
+    };
+    assert_eq!(freezer.name, "pantry");
+    assert!(server.freezer("kitchen").is_none());
+    assert!(matches!(
+        client.image("pantry", None).await.unwrap(),
+        Image::Fresh { .. }
+    ));
+    assert_eq!(client.freezer_history("pantry").await.unwrap().len(), 2);
+
+    assert!(matches!(
+        client.rename_freezer("pantry", "garage").await.unwrap(),
+        Rename::Taken
+    ));
+    assert!(client.rename_freezer("kitchen", "attic").await.is_err());
+    assert!(client.rename_freezer("pantry", " ").await.is_err());
+
+    server.inject(Route::Rename, Fault::Status(403));
+    assert!(matches!(
+        client.rename_freezer("pantry", "attic").await.unwrap(),
+        Rename::Refused
+    ));
+}
+
+#[tokio::test]
+async fn product() {
+    let (server, client) = setup().await;
+
+    let product = client.product("pelmeni").await.unwrap().unwrap();
+    assert_eq!((product.name.as_str(), product.default), ("pelmeni", 5));
+    assert!(client.product("caviar").await.unwrap().is_none());
+
+    server.inject(Route::Product, Fault::Malformed);
+    assert!(client.product("pelmeni").await.is_err());
+}
+
+#[tokio::test]
+async fn unreachable() {
+    let (server, client) = setup().await;
+    server.stop().await;
+
+    assert!(client.login("admin").await.is_err());
+    assert!(client.freezers().await.is_err());
+    assert!(!client.is_offline());
+}
+
+#[tokio::test]
+async fn offline() {
+    let (server, client) = setup().await;
+    let dir = temp_dir();
+    let client = client.with_store(Store::at(dir.clone()).unwrap());
+
+    // populate the local copy
+    client.freezers().await.unwrap();
+    let kitchen = client.freezer("kitchen").await.unwrap();
+    client.freezer("office").await.unwrap();
+    client.product("pelmeni").await.unwrap();
+
+    let addr = server.addr();
+    server.stop().await;
+
+    assert!(client.login("admin").await.is_ok());
+    assert!(client.is_offline());
+    assert_eq!(
+        client.freezers_by(2, 1).await.unwrap(),
+        ["kitchen", "office"]
+    );
+    assert_eq!(client.freezer("kitchen").await.unwrap(), kitchen);
+    assert!(client.freezer("garage").await.is_err());
+    assert_eq!(client.product("pelmeni").await.unwrap().unwrap().default, 5);
+
+    let mut changed = kitchen.clone();
+    changed.products.insert("pelmeni".into(), 0);
+    assert_eq!(
+        client.update_freezer(changed.clone()).await.unwrap(),
+        Some(changed.clone())
+    );
+    assert!(client.delete_freezer("office").await.unwrap());
+    assert_eq!(client.pending(), 2);
+    assert_eq!(Store::at(dir).unwrap().pending(), 2);
+    assert_eq!(client.freezer("kitchen").await.unwrap(), changed);
+
+    // still unreachable - nothing is lost
+    assert!(client.replay().await.unwrap().is_empty());
+    assert_eq!(client.pending(), 2);
+
+    let server = MockServer::bind(addr, Seed::demo()).await.unwrap();
+    let report = client.replay().await.unwrap();
+    assert_eq!(report.len(), 2);
+    assert!(
+        report
+            .iter()
+            .all(|replayed| matches!(replayed.outcome, Outcome::Applied))
+    );
+    assert_eq!(client.pending(), 0);
+    assert!(!client.is_offline());
+    assert_eq!(server.freezer("kitchen").unwrap()["products"]["pelmeni"], 0);
+    assert!(server.freezer("office").is_none());
+}
+
+#[tokio::test]
+async fn offline_conflict() {
+    let (server, client) = setup().await;
+    let client = client.with_store(Store::at(temp_dir()).unwrap());
+
+    let garage = client.freezer("garage").await.unwrap();
+    let addr = server.addr();
+    server.stop().await;
+
+    let mut changed = garage.clone();
+    changed.owner = Some("admin".into());
+    client.update_freezer(changed).await.unwrap();
+
+    let server = MockServer::bind(addr, Seed::demo()).await.unwrap();
+    let mut theirs = server.freezer("garage").unwrap();
+    theirs["owner"] = "guest".into();
+    server.put_freezer(theirs);
+
+    let report = client.replay().await.unwrap();
+    assert!(matches!(
+        &report[..],
+        [replayed] if matches!(
+            &replayed.outcome,
+            Outcome::Conflict { server: Some(Freezer { owner: Some(owner), .. }) } if owner == "guest"
+        )
+    ));
+    assert_eq!(server.freezer("garage").unwrap()["owner"], "guest");
+}
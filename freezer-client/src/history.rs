@@ -0,0 +1,60 @@
+//! Audit trail of a freezer: revisions recorded by the server
+//! with the changes each of them made, see [`Client::freezer_history`].
+//!
+//! [`Client::freezer_history`]: crate::Client::freezer_history
+
+use crate::{
+    inventory::Change,
+    model::{Freezer, Revision},
+};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub revision: Revision,
+    /// Lines of [`Change::diff`] against the previous revision
+    pub diff: Vec<String>,
+}
+
+impl Entry {
+    /// The freezer to restore, `None` if the revision is a deletion
+    #[must_use]
+    pub const fn freezer(&self) -> Option<&Freezer> {
+        self.revision.freezer.as_ref()
+    }
+}
+
+/// Pairs revisions (the oldest first, as the server returns them) with their diffs,
+/// entries go the newest first
+#[must_use]
+pub fn entries(revisions: Vec<Revision>) -> Vec<Entry> {
+    let mut previous: Option<Freezer> = None;
+    let mut entries = Vec::with_capacity(revisions.len());
+
+    for revision in revisions {
+        let diff = match (previous.take(), &revision.freezer) {
+            (_, None) => vec!["deleted".to_owned()],
+            (None, Some(new)) => Change::Create(new.clone()).diff(),
+            (Some(old), Some(new)) => {
+                let mut diff = Change::Update {
+                    old,
+                    new: new.clone(),
+                }
+                .diff();
+                // the header names the freezer, it's known from the context
+                diff.remove(0);
+                for line in &mut diff {
+                    *line = line.trim_start().to_owned();
+                }
+                diff
+            }
+        };
+        previous.clone_from(&revision.freezer);
+        entries.push(Entry { revision, diff });
+    }
+
+    entries.reverse();
+    entries
+}
@@ -0,0 +1,38 @@
+use super::entries;
+use crate::client::Client;
+use mock_server::{MockServer, Seed};
+
+#[tokio::test]
+async fn history() {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let client = Client::new(&server.api(), reqwest::Client::new());
+    client.login("admin").await.unwrap();
+
+    let mut kitchen = client.freezer("kitchen").await.unwrap();
+    kitchen.products.insert("pelmeni".into(), 5);
+    kitchen.owner = Some("guest".into());
+    client.update_freezer(kitchen).await.unwrap();
+    assert!(client.delete_freezer("kitchen").await.unwrap());
+
+    let revisions = client.freezer_history("kitchen").await.unwrap();
+    assert_eq!(revisions.len(), 3);
+    assert!(revisions.windows(2).all(|pair| pair[0].at <= pair[1].at));
+
+    let entries = entries(revisions);
+    let users: Vec<_> = entries
+        .iter()
+        .map(|entry| entry.revision.user.as_deref())
+        .collect();
+    // seeded data has no author
+    assert_eq!(users, [Some("admin"), Some("admin"), None]);
+
+    assert_eq!(entries[0].diff, ["deleted"]);
+    assert!(entries[0].freezer().is_none());
+    assert_eq!(
+        entries[1].diff,
+        ["~ owner: admin -> guest", "~ pelmeni: 3 -> 5"]
+    );
+    assert!(entries[2].diff[0].starts_with("+ `kitchen`"));
+
+    assert!(client.freezer_history("none").await.unwrap().is_empty());
+}
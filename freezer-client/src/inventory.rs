@@ -0,0 +1,443 @@
+//! Dump and load of whole inventories as flat tables:
+//! one [`Row`] per freezer/product pair, a freezer without products takes
+//! a single row with empty `product` and `amount`.
+//!
+//! Import is split in two steps: [`plan`] validates the rows against the server
+//! and returns the diff to show, then every [`Change`] is [`apply`]ed.
+//!
+//! Rows carry the amounts only: imported freezers keep the batches of the server ones.
+
+use crate::{
+    api::FreezerApi,
+    model::{Details, Freezer, Model},
+    utils::{Error, Result, error::anyio},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet, hash_map},
+    path::Path,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Freezers requested per page by [`export`]
+pub const PAGE: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+impl Format {
+    /// Guesses the format by the file extension
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Row {
+    pub freezer: String,
+    pub model: String,
+    pub year: usize,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub capacity: Option<usize>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<i32>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub product: Option<String>,
+    #[serde(default)]
+    pub amount: Option<usize>,
+}
+
+/// Rows with their numbers starting from 1
+pub type Numbered = Vec<(usize, Row)>;
+
+/// Rows sorted by freezers then products
+#[must_use]
+pub fn rows(freezers: &[Freezer]) -> Vec<Row> {
+    let mut freezers: Vec<_> = freezers.iter().collect();
+    freezers.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut rows = Vec::new();
+    for freezer in freezers {
+        let row = |product: Option<(&String, &usize)>| Row {
+            freezer: freezer.name.clone(),
+            model: freezer.model.name.clone(),
+            year: freezer.model.year,
+            owner: freezer.owner.clone(),
+            capacity: freezer.details.capacity,
+            location: freezer.details.location.clone(),
+            temperature: freezer.details.temperature,
+            notes: freezer.details.notes.clone(),
+            product: product.map(|(name, _)| name.clone()),
+            amount: product.map(|(_, amount)| *amount),
+        };
+
+        let mut products: Vec<_> = freezer.products.iter().collect();
+        products.sort();
+        if products.is_empty() {
+            rows.push(row(None));
+        }
+        rows.extend(products.into_iter().map(|product| row(Some(product))));
+    }
+    rows
+}
+
+/// # Errors
+/// If a row can't be serialized
+pub fn write(format: Format, rows: &[Row]) -> Result<Vec<u8>> {
+    match format {
+        Format::Json => Ok(json::to_vec_pretty(rows)?),
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for row in rows {
+                writer.serialize(row)?;
+            }
+            writer.into_inner().map_err(|error| anyio!("{error}"))
+        }
+    }
+}
+
+/// Parses rows numbered from 1, malformed ones are reported apart
+///
+/// # Errors
+/// If the whole input is malformed, e.g. JSON is not an array
+pub fn read(format: Format, bytes: &[u8]) -> Result<(Numbered, Vec<Error>)> {
+    let parsed: Vec<Result<Row>> = match format {
+        Format::Json => json::from_slice::<Vec<json::Value>>(bytes)?
+            .into_iter()
+            .map(|value| Ok(json::from_value(value)?))
+            .collect(),
+        Format::Csv => csv::Reader::from_reader(bytes)
+            .deserialize()
+            .map(|row| Ok(row?))
+            .collect(),
+    };
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for (index, row) in (1..).zip(parsed) {
+        match row {
+            Ok(row) => rows.push((index, row)),
+            Err(error) => errors.push(anyio!("row {index}: {error}")),
+        }
+    }
+    Ok((rows, errors))
+}
+
+/// Collects rows into freezers, rows that contradict the previous ones are reported
+#[must_use]
+pub fn freezers(rows: Numbered) -> (Vec<Freezer>, Vec<Error>) {
+    let mut freezers: Vec<Freezer> = Vec::new();
+    // index in `freezers` and the first row of the freezer
+    let mut known = HashMap::new();
+    let mut errors = Vec::new();
+
+    for (index, row) in rows {
+        let Row {
+            freezer: name,
+            model,
+            year,
+            owner,
+            capacity,
+            location,
+            temperature,
+            notes,
+            product,
+            amount,
+        } = row;
+
+        if name.is_empty() {
+            errors.push(anyio!("row {index}: empty freezer id"));
+            continue;
+        }
+        let product = match (product, amount) {
+            (Some(product), _) if product.is_empty() => {
+                errors.push(anyio!("row {index}: empty product id"));
+                continue;
+            }
+            (Some(product), Some(amount)) => Some((product, amount)),
+            (None, None) => None,
+            _ => {
+                errors.push(anyio!("row {index}: product and amount go together"));
+                continue;
+            }
+        };
+
+        let text = |text: Option<String>| text.filter(|text| !text.is_empty());
+        let owner = text(owner);
+        let details = Details {
+            capacity,
+            location: text(location),
+            temperature,
+            notes: text(notes),
+        };
+        let model = Model { name: model, year };
+        let at = match known.entry(name.clone()) {
+            hash_map::Entry::Occupied(entry) => {
+                let &(at, first): &(usize, usize) = entry.get();
+                let freezer = &freezers[at];
+                if freezer.model != model || freezer.owner != owner || freezer.details != details {
+                    errors.push(anyio!(
+                        "row {index}: model, owner or details of `{name}` differs from row {first}"
+                    ));
+                    continue;
+                }
+                at
+            }
+            hash_map::Entry::Vacant(entry) => {
+                freezers.push(Freezer {
+                    name: name.clone(),
+                    model,
+                    owner,
+                    details,
+                    products: HashMap::new(),
+                    batches: HashMap::new(),
+                });
+                entry.insert((freezers.len() - 1, index)).0
+            }
+        };
+
+        if let Some((product, amount)) = product {
+            match freezers[at].products.entry(product) {
+                hash_map::Entry::Occupied(entry) => {
+                    errors.push(anyio!(
+                        "row {index}: `{}` is repeated in `{name}`",
+                        entry.key()
+                    ));
+                }
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(amount);
+                }
+            }
+        }
+    }
+    (freezers, errors)
+}
+
+/// [`read`] followed by [`freezers`]
+///
+/// # Errors
+/// If the whole input is malformed
+pub fn parse(format: Format, bytes: &[u8]) -> Result<(Vec<Freezer>, Vec<Error>)> {
+    let (rows, mut errors) = read(format, bytes)?;
+    let (freezers, invalid) = freezers(rows);
+    errors.extend(invalid);
+    Ok((freezers, errors))
+}
+
+/// Ids of every freezer, pages are requested by [`PAGE`] until a short page
+/// or a page without new ids - a server may ignore the paging
+///
+/// # Errors
+/// If any request fails
+pub async fn ids(api: &dyn FreezerApi) -> Result<Vec<String>> {
+    let mut ids = Vec::new();
+    let mut seen = HashSet::new();
+    loop {
+        let page = api.freezers_by(Some(PAGE), Some(ids.len())).await?;
+        let is_last = page.len() < PAGE;
+        let known = ids.len();
+        ids.extend(page.into_iter().filter(|id| seen.insert(id.clone())));
+        if is_last || ids.len() == known {
+            return Ok(ids);
+        }
+    }
+}
+
+/// Every freezer of the server, see [`ids`]
+///
+/// # Errors
+/// If any request fails
+pub async fn export(api: &dyn FreezerApi) -> Result<Vec<Freezer>> {
+    let ids = ids(api).await?;
+    let mut freezers = Vec::with_capacity(ids.len());
+    for id in ids {
+        freezers.push(api.freezer(&id).await?);
+    }
+    Ok(freezers)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+// a change per imported freezer, not worth the boxing
+#[allow(clippy::large_enum_variant)]
+pub enum Change {
+    Create(Freezer),
+    /// Imported freezer replaces the server one, products missing in the import are removed
+    Update {
+        old: Freezer,
+        new: Freezer,
+    },
+}
+
+impl Change {
+    /// Freezer as it will be stored
+    #[must_use]
+    pub const fn freezer(&self) -> &Freezer {
+        match self {
+            Self::Create(freezer) | Self::Update { new: freezer, .. } => freezer,
+        }
+    }
+
+    /// Human readable diff, a line per changed field or product
+    #[must_use]
+    pub fn diff(&self) -> Vec<String> {
+        fn owner(owner: Option<&str>) -> &str {
+            owner.unwrap_or("nobody")
+        }
+
+        fn details(old: &Details, new: &Details) -> Vec<String> {
+            old.fields()
+                .into_iter()
+                .zip(new.fields())
+                .filter(|((_, old), (_, new))| old != new)
+                .map(|((label, old), (_, new))| match (old, new) {
+                    (None, Some(new)) => format!("  + {label}: {new}"),
+                    (Some(old), None) => format!("  - {label}: {old}"),
+                    (old, new) => format!(
+                        "  ~ {label}: {} -> {}",
+                        old.unwrap_or_default(),
+                        new.unwrap_or_default()
+                    ),
+                })
+                .collect()
+        }
+
+        let mut lines = Vec::new();
+        match self {
+            Self::Create(new) => {
+                lines.push(format!(
+                    "+ `{}`: {} ({}), owner {}",
+                    new.name,
+                    new.model.name,
+                    new.model.year,
+                    owner(new.owner.as_deref())
+                ));
+                lines.extend(details(&Details::default(), &new.details));
+                let mut products: Vec<_> = new.products.iter().collect();
+                products.sort();
+                lines.extend(
+                    products
+                        .into_iter()
+                        .map(|(name, amount)| format!("  + {name}: {amount}")),
+                );
+            }
+            Self::Update { old, new } => {
+                lines.push(format!("~ `{}`", new.name));
+                if old.model != new.model {
+                    lines.push(format!(
+                        "  ~ model: {} ({}) -> {} ({})",
+                        old.model.name, old.model.year, new.model.name, new.model.year
+                    ));
+                }
+                if old.owner != new.owner {
+                    lines.push(format!(
+                        "  ~ owner: {} -> {}",
+                        owner(old.owner.as_deref()),
+                        owner(new.owner.as_deref())
+                    ));
+                }
+                lines.extend(details(&old.details, &new.details));
+
+                let mut products: Vec<_> = old.products.keys().chain(new.products.keys()).collect();
+                products.sort();
+                products.dedup();
+                for name in products {
+                    match (old.products.get(name), new.products.get(name)) {
+                        (Some(old), Some(new)) if old != new => {
+                            lines.push(format!("  ~ {name}: {old} -> {new}"));
+                        }
+                        (None, Some(new)) => lines.push(format!("  + {name}: {new}")),
+                        (Some(old), None) => lines.push(format!("  - {name}: {old}")),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// Result of the dry run, nothing is changed yet
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+    /// Count of imported freezers equal to the server ones
+    pub unchanged: usize,
+    /// Freezers that can't be imported, e.g. with unknown products
+    pub errors: Vec<Error>,
+}
+
+/// Dry run of the import: compares `freezers` with the server ones
+/// and checks that their products exist
+///
+/// # Errors
+/// If any request fails
+pub async fn plan(api: &dyn FreezerApi, freezers: Vec<Freezer>) -> Result<Plan> {
+    let existing: HashSet<_> = api.freezers().await?.into_iter().collect();
+    let mut products = HashMap::new();
+    let mut plan = Plan::default();
+
+    for mut new in freezers {
+        let mut unknown = Vec::new();
+        for product in new.products.keys() {
+            if !products.contains_key(product) {
+                let exists = api.product(product).await?.is_some();
+                products.insert(product.clone(), exists);
+            }
+            if !products[product] {
+                unknown.push(product.as_str());
+            }
+        }
+        if !unknown.is_empty() {
+            unknown.sort_unstable();
+            plan.errors.push(anyio!(
+                "`{}` has unknown products: {}",
+                new.name,
+                unknown.join(", ")
+            ));
+            continue;
+        }
+
+        if existing.contains(&new.name) {
+            let old = api.freezer(&new.name).await?;
+            new.batches.clone_from(&old.batches);
+            new.trim_batches();
+            if old == new {
+                plan.unchanged += 1;
+            } else {
+                plan.changes.push(Change::Update { old, new });
+            }
+        } else {
+            plan.changes.push(Change::Create(new));
+        }
+    }
+    Ok(plan)
+}
+
+/// # Errors
+/// If the request fails or the server refuses the change
+pub async fn apply(api: &dyn FreezerApi, change: Change) -> Result<()> {
+    let id = change.freezer().name.clone();
+    let new = match change {
+        Change::Create(new) | Change::Update { new, .. } => new,
+    };
+    match api.update_freezer(new).await? {
+        Some(_) => Ok(()),
+        None => Err(anyio!("unauthorized access: `{id}` is not imported")),
+    }
+}
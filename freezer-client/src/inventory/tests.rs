@@ -0,0 +1,216 @@
+use super::{
+    Change, Format, PAGE, Row, apply, export, freezers, ids, parse, plan, read, rows, write,
+};
+use crate::{client::Client, model::Freezer};
+use mock_server::{Fault, MockServer, Route, Seed};
+use std::path::Path;
+
+async fn setup() -> (MockServer, Client) {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let client = Client::new(&server.api(), reqwest::Client::new());
+    (server, client)
+}
+
+fn row(freezer: &str, product: Option<(&str, usize)>) -> Row {
+    Row {
+        freezer: freezer.to_owned(),
+        model: "Atlant".to_owned(),
+        year: 2015,
+        owner: None,
+        capacity: None,
+        location: None,
+        temperature: None,
+        notes: None,
+        product: product.map(|(name, _)| name.to_owned()),
+        amount: product.map(|(_, amount)| amount),
+    }
+}
+
+#[tokio::test]
+async fn unpaged_server() {
+    let seed = Seed {
+        freezers: (0..PAGE + 5)
+            .map(|index| {
+                json::json!({
+                    "_id": format!("freezer-{index:02}"),
+                    "model": { "name": "Atlant", "year": 2015 },
+                    "products": {},
+                })
+            })
+            .collect(),
+        ..Seed::default()
+    };
+    let server = MockServer::start(seed).await.unwrap();
+    let client = Client::new(&server.api(), reqwest::Client::new());
+    server.inject(Route::Freezers, Fault::Unpaged);
+
+    // every page is the whole inventory
+    assert_eq!(ids(&client).await.unwrap().len(), PAGE + 5);
+    assert_eq!(server.hits(Route::Freezers), 2);
+}
+
+#[test]
+fn format() {
+    assert_eq!(Format::from_path(Path::new("dump.CSV")), Some(Format::Csv));
+    assert_eq!(
+        Format::from_path(Path::new("dir/dump.json")),
+        Some(Format::Json)
+    );
+    assert_eq!(Format::from_path(Path::new("dump.txt")), None);
+    assert_eq!(Format::from_path(Path::new("dump")), None);
+}
+
+#[tokio::test]
+async fn roundtrip() {
+    let (_server, client) = setup().await;
+    let exported = export(&client).await.unwrap();
+    assert_eq!(exported.len(), 3);
+
+    let rows = rows(&exported);
+    // kitchen has 2 products, office is empty
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[3], {
+        let mut office = row("office", None);
+        office.model = "Bosch".into();
+        office.year = 2020;
+        office.owner = Some("guest".into());
+        office
+    });
+
+    // rows carry no batches
+    let counts: Vec<_> = exported
+        .iter()
+        .cloned()
+        .map(|mut freezer| {
+            freezer.batches.clear();
+            freezer
+        })
+        .collect();
+    assert_ne!(counts, exported);
+
+    for format in [Format::Json, Format::Csv] {
+        let bytes = write(format, &rows).unwrap();
+        let (mut imported, errors) = parse(format, &bytes).unwrap();
+        assert!(errors.is_empty(), "{format:?}: {errors:?}");
+
+        imported.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(imported, counts, "{format:?}");
+
+        // batches of the server are kept
+        let plan = plan(&client, imported).await.unwrap();
+        assert!(plan.changes.is_empty(), "{format:?}: {:?}", plan.changes);
+        assert_eq!(plan.unchanged, 3);
+    }
+}
+
+#[test]
+fn malformed_rows() {
+    let csv = "freezer,model,year,owner,product,amount\n\
+               kitchen,Atlant,2015,,pelmeni,3\n\
+               kitchen,Atlant,many,,berries,1\n\
+               garage,Indesit,2008,,,\n";
+    let (rows, errors) = read(Format::Csv, csv.as_bytes()).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].0, 3);
+    assert!(errors[0].to_string().starts_with("row 2:"), "{errors:?}");
+
+    assert!(read(Format::Json, br#"{"freezer": "kitchen"}"#).is_err());
+    let (rows, errors) = read(Format::Json, br#"[{"freezer": "kitchen"}]"#).unwrap();
+    assert!(rows.is_empty());
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn contradicting_rows() {
+    let mut other = row("kitchen", Some(("berries", 1)));
+    other.year = 2020;
+
+    let (freezers, errors) = freezers(vec![
+        (1, row("kitchen", Some(("pelmeni", 3)))),
+        (2, row("kitchen", Some(("pelmeni", 4)))),
+        (3, other),
+        (4, row("", None)),
+        (5, row("garage", Some(("", 1)))),
+        (
+            6,
+            Row {
+                amount: None,
+                ..row("garage", Some(("pelmeni", 1)))
+            },
+        ),
+    ]);
+    assert!(matches!(
+        &freezers[..],
+        [Freezer { name, products, .. }] if name == "kitchen" && products["pelmeni"] == 3
+    ));
+
+    let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(errors.len(), 5, "{errors:?}");
+    assert!(errors[0].contains("repeated"));
+    assert!(errors[1].contains("differs from row 1"));
+}
+
+#[tokio::test]
+async fn dry_run_and_apply() {
+    let (server, client) = setup().await;
+
+    let mut kitchen = client.freezer("kitchen").await.unwrap();
+    kitchen.products.remove("ice-cream");
+    kitchen.products.insert("berries".into(), 2);
+    let garage = client.freezer("garage").await.unwrap();
+    let mut attic = garage.clone();
+    attic.name = "attic".into();
+    let mut cellar = garage.clone();
+    cellar.name = "cellar".into();
+    cellar.products.insert("caviar".into(), 1);
+
+    let plan = plan(
+        &client,
+        vec![kitchen.clone(), garage, attic.clone(), cellar],
+    )
+    .await
+    .unwrap();
+    assert_eq!(plan.unchanged, 1);
+    assert_eq!(plan.errors.len(), 1);
+    assert!(plan.errors[0].to_string().contains("caviar"));
+
+    let [update @ Change::Update { .. }, create @ Change::Create(_)] = &plan.changes[..] else {
+        panic!("unexpected plan: {:?}", plan.changes);
+    };
+    assert_eq!(
+        update.diff(),
+        ["~ `kitchen`", "  + berries: 2", "  - ice-cream: 10"]
+    );
+    assert_eq!(create.freezer(), &attic);
+
+    // dry run changes nothing
+    assert!(server.freezer("attic").is_none());
+
+    for change in plan.changes {
+        apply(&client, change).await.unwrap();
+    }
+    assert_eq!(client.freezer("kitchen").await.unwrap(), kitchen);
+    assert_eq!(client.freezer("attic").await.unwrap(), attic);
+}
+
+#[tokio::test]
+async fn details_diff() {
+    let (_server, client) = setup().await;
+
+    let old = client.freezer("kitchen").await.unwrap();
+    assert_eq!(old.details.capacity, Some(40));
+    let mut new = old.clone();
+    new.details.capacity = Some(50);
+    new.details.location = None;
+    new.details.notes = Some("defrost in May".into());
+
+    assert_eq!(
+        Change::Update { old, new }.diff(),
+        [
+            "~ `kitchen`",
+            "  ~ capacity: 40 units -> 50 units",
+            "  - location: kitchen, by the window",
+            "  + notes: defrost in May",
+        ]
+    );
+}
@@ -0,0 +1,47 @@
+#![deny(clippy::all, clippy::nursery, clippy::perf, clippy::pedantic)]
+
+//! Bindings of the freezers API.
+//!
+//! [`Client`] talks to the server over HTTP, with a [`Store`] attached
+//! it keeps working while the server is unreachable: reads are served from
+//! the local copy and mutations are queued until [`Client::replay`].
+//! Consumers that want to swap the backend (e.g. for tests) depend on the
+//! [`FreezerApi`] trait instead.
+//!
+//! ```no_run
+//! # async fn run() -> freezer_client::Result<()> {
+//! use freezer_client::Client;
+//!
+//! let client = Client::new(Client::DEFAULT_API, reqwest::Client::new());
+//! client.login("admin").await?;
+//! for id in client.freezers().await? {
+//!     println!("{id}: {:?}", client.freezer(&id).await?.products);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Enable the `blocking` feature for [`blocking::Client`] - the same API
+//! without an async runtime.
+
+pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod client;
+pub mod history;
+pub mod inventory;
+pub mod model;
+pub mod owners;
+pub mod stock;
+pub mod store;
+pub mod transfer;
+pub mod utils;
+
+pub use api::FreezerApi;
+pub use client::{Client, Image, Outcome, Rename, Replayed};
+pub use model::{
+    Account, Batch, Details, Expiry, Freezer, Model, Permission, Product, Revision, Summary,
+};
+pub use store::{Mutation, Store};
+pub use transfer::{Commit, Transfer};
+pub use utils::{Error, Result};
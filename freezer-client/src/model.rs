@@ -0,0 +1,224 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+#[cfg(test)]
+mod tests;
+
+/// Model of the freezer hardware
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Model {
+    pub name: String,
+    pub year: usize,
+}
+
+/// Freezer with its contents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Freezer {
+    /// Unique id of the freezer
+    #[serde(rename = "_id")]
+    pub name: String,
+
+    pub model: Model,
+    pub owner: Option<String>,
+    #[serde(flatten)]
+    pub details: Details,
+    /// Amounts by product ids
+    pub products: HashMap<String, usize>,
+    /// Dated units of the products by product ids, the rest of the amount is undated.
+    /// Absent in the plain count format
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub batches: HashMap<String, Vec<Batch>>,
+}
+
+/// Optional facts about the freezer, the server may omit any of them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Details {
+    /// Units of products that fit in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Target temperature in °C
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl Details {
+    /// Labels and values of the fields, unset ones are `None`
+    #[must_use]
+    pub fn fields(&self) -> [(&'static str, Option<String>); 4] {
+        [
+            (
+                "capacity",
+                self.capacity.map(|units| format!("{units} units")),
+            ),
+            ("location", self.location.clone()),
+            (
+                "temperature",
+                self.temperature.map(|degrees| format!("{degrees} °C")),
+            ),
+            ("notes", self.notes.clone()),
+        ]
+    }
+}
+
+impl Freezer {
+    /// Units of all products
+    #[must_use]
+    pub fn units(&self) -> usize {
+        self.products.values().sum()
+    }
+
+    /// Drops batches beyond the amounts, see [`Batch::trim`]
+    pub fn trim_batches(&mut self) {
+        let products = &self.products;
+        self.batches.retain(|product, batches| {
+            Batch::trim(batches, products.get(product).copied().unwrap_or_default());
+            !batches.is_empty()
+        });
+    }
+}
+
+/// Units of a product frozen at once
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Batch {
+    pub quantity: usize,
+    #[serde(default)]
+    pub frozen: Option<NaiveDate>,
+    #[serde(default)]
+    pub best_before: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Expiry {
+    /// Also batches without the best-before date
+    Fresh,
+    /// Expires within [`Batch::SOON`] days
+    Soon,
+    Expired,
+}
+
+impl Batch {
+    /// Days before the best-before date when the batch should be used
+    pub const SOON: i64 = 7;
+
+    #[must_use]
+    pub fn expiry(&self, today: NaiveDate) -> Expiry {
+        match self.best_before.map(|date| (date - today).num_days()) {
+            Some(days) if days < 0 => Expiry::Expired,
+            Some(days) if days <= Self::SOON => Expiry::Soon,
+            _ => Expiry::Fresh,
+        }
+    }
+
+    /// Sorts to use the expiring first, undated batches go last
+    pub fn sort(batches: &mut [Self]) {
+        batches.sort_by_key(|batch| (batch.best_before.is_none(), batch.best_before, batch.frozen));
+    }
+
+    /// Keeps at most `units` in `batches` using up the expiring first, they stay sorted
+    pub fn trim(batches: &mut Vec<Self>, units: usize) {
+        Self::sort(batches);
+        let total: usize = batches.iter().map(|batch| batch.quantity).sum();
+        let mut excess = total.saturating_sub(units);
+        for batch in batches.iter_mut() {
+            let used = excess.min(batch.quantity);
+            batch.quantity -= used;
+            excess -= used;
+        }
+        batches.retain(|batch| batch.quantity > 0);
+    }
+}
+
+/// Version of a freezer recorded by the server on every change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revision {
+    pub at: DateTime<Utc>,
+    /// Login of the author, `None` if unknown (e.g. seeded data)
+    #[serde(default)]
+    pub user: Option<String>,
+    /// The freezer after the change, `None` if it was deleted
+    pub freezer: Option<Freezer>,
+}
+
+/// Units per product and per freezer across the whole inventory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Summary {
+    pub products: HashMap<String, usize>,
+    pub freezers: HashMap<String, usize>,
+}
+
+impl Summary {
+    #[must_use]
+    pub fn of<'a>(freezers: impl IntoIterator<Item = &'a Freezer>) -> Self {
+        let mut summary = Self::default();
+        for freezer in freezers {
+            for (product, amount) in &freezer.products {
+                *summary.products.entry(product.clone()).or_default() += amount;
+            }
+            summary
+                .freezers
+                .insert(freezer.name.clone(), freezer.units());
+        }
+        summary
+    }
+}
+
+/// What the user may change, granted by the roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    /// Edits of the freezers: contents, details, owners, names and transfers
+    Update,
+    Delete,
+}
+
+/// The logged in user as the server sees it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Account {
+    pub login: String,
+    /// Names of the roles, e.g. `admin`
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// `None` if the server doesn't tell (older servers, offline login):
+    /// everything is allowed then and refused changes are reported afterwards
+    #[serde(default)]
+    pub permissions: Option<BTreeSet<Permission>>,
+}
+
+impl Account {
+    /// Account of the server that doesn't tell the roles
+    #[must_use]
+    pub fn unknown(login: &str) -> Self {
+        Self {
+            login: login.to_owned(),
+            roles: Vec::new(),
+            permissions: None,
+        }
+    }
+
+    #[must_use]
+    pub fn can(&self, permission: Permission) -> bool {
+        self.permissions
+            .as_ref()
+            .map_or(true, |permissions| permissions.contains(&permission))
+    }
+
+    /// Nothing can be changed
+    #[must_use]
+    pub fn is_read_only(&self) -> bool {
+        self.permissions.as_ref().is_some_and(BTreeSet::is_empty)
+    }
+}
+
+/// Product known to the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Product {
+    #[serde(rename = "_id")]
+    pub name: String,
+    /// Amount put into the freezer when the product is added
+    pub default: usize,
+}
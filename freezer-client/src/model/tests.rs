@@ -0,0 +1,65 @@
+use super::{Batch, Expiry, Freezer};
+use chrono::NaiveDate;
+
+fn date(day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(2022, 11, day)
+}
+
+fn batch(quantity: usize, best_before: Option<NaiveDate>) -> Batch {
+    Batch {
+        quantity,
+        frozen: None,
+        best_before,
+    }
+}
+
+#[test]
+fn plain_count_format() {
+    let plain = json::json!({
+        "_id": "garage",
+        "model": { "name": "Indesit", "year": 2008 },
+        "owner": null,
+        "products": { "pelmeni": 1 },
+    });
+    let freezer: Freezer = json::from_value(plain.clone()).unwrap();
+    assert!(freezer.batches.is_empty());
+    // servers that don't know batches get the same document back
+    assert_eq!(json::to_value(&freezer).unwrap(), plain);
+
+    let dated = json::json!({
+        "_id": "garage",
+        "model": { "name": "Indesit", "year": 2008 },
+        "owner": null,
+        "products": { "pelmeni": 1 },
+        "batches": { "pelmeni": [{ "quantity": 1, "best_before": "2022-11-20" }] },
+    });
+    let freezer: Freezer = json::from_value(dated).unwrap();
+    assert_eq!(freezer.batches["pelmeni"], [batch(1, date(20))]);
+}
+
+#[test]
+fn expiry() {
+    let today = date(10).unwrap();
+    assert_eq!(batch(1, None).expiry(today), Expiry::Fresh);
+    assert_eq!(batch(1, date(18)).expiry(today), Expiry::Fresh);
+    assert_eq!(batch(1, date(17)).expiry(today), Expiry::Soon);
+    assert_eq!(batch(1, date(10)).expiry(today), Expiry::Soon);
+    assert_eq!(batch(1, date(9)).expiry(today), Expiry::Expired);
+}
+
+#[test]
+fn expiring_are_used_first() {
+    let mut batches = vec![batch(2, None), batch(3, date(20)), batch(1, date(5))];
+
+    Batch::trim(&mut batches, 10);
+    assert_eq!(
+        batches,
+        [batch(1, date(5)), batch(3, date(20)), batch(2, None)]
+    );
+
+    Batch::trim(&mut batches, 4);
+    assert_eq!(batches, [batch(2, date(20)), batch(2, None)]);
+
+    Batch::trim(&mut batches, 0);
+    assert!(batches.is_empty());
+}
@@ -0,0 +1,105 @@
+//! Owners of the freezers: who a freezer can be assigned to and bulk reassignment.
+//!
+//! Candidates are the users of the server merged with the owners seen so far -
+//! older servers don't list their users.
+
+use crate::{api::FreezerApi, utils::Result};
+use futures::{StreamExt, stream};
+use std::{
+    collections::BTreeSet,
+    fmt::{Display, Formatter},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Owner filter of [`Client::freezers_owned`](crate::Client::freezers_owned)
+/// and the assignee of [`reassign`]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Owner {
+    /// Freezers without an owner
+    Nobody,
+    User(String),
+}
+
+impl Owner {
+    #[must_use]
+    pub fn matches(&self, owner: Option<&str>) -> bool {
+        match self {
+            Self::Nobody => owner.is_none(),
+            Self::User(user) => owner == Some(user.as_str()),
+        }
+    }
+
+    /// Value of [`Freezer::owner`](crate::Freezer::owner)
+    #[must_use]
+    pub fn login(&self) -> Option<&str> {
+        match self {
+            Self::Nobody => None,
+            Self::User(user) => Some(user),
+        }
+    }
+}
+
+impl From<Option<String>> for Owner {
+    fn from(owner: Option<String>) -> Self {
+        owner.map_or(Self::Nobody, Self::User)
+    }
+}
+
+impl Display for Owner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Nobody => write!(f, "nobody"),
+            Self::User(user) => write!(f, "{user}"),
+        }
+    }
+}
+
+/// Users of the server and the owners seen so far, sorted and deduplicated
+#[must_use]
+pub fn candidates(
+    users: impl IntoIterator<Item = String>,
+    seen: impl IntoIterator<Item = String>,
+) -> Vec<String> {
+    users
+        .into_iter()
+        .chain(seen)
+        .filter(|owner| !owner.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Assigns the freezer to `owner`, it's left as is if `owner` has it already
+///
+/// # Errors
+/// If the freezer can't be fetched or updated; `Ok(false)` if the server
+/// refused the update
+pub async fn assign(api: &dyn FreezerApi, id: &str, owner: &Owner) -> Result<bool> {
+    let mut freezer = api.freezer(id).await?;
+    if owner.matches(freezer.owner.as_deref()) {
+        return Ok(true);
+    }
+    freezer.owner = owner.login().map(ToOwned::to_owned);
+    Ok(api.update_freezer(freezer).await?.is_some())
+}
+
+/// [`assign`] every freezer to `owner`, at most `parallel` freezers at once
+///
+/// Returns the result per freezer in no particular order
+pub async fn reassign(
+    api: &dyn FreezerApi,
+    ids: Vec<String>,
+    owner: &Owner,
+    parallel: usize,
+) -> Vec<(String, Result<bool>)> {
+    stream::iter(ids)
+        .map(|id| async move {
+            let reassigned = assign(api, &id, owner).await;
+            (id, reassigned)
+        })
+        .buffer_unordered(parallel.max(1))
+        .collect()
+        .await
+}
@@ -0,0 +1,50 @@
+use super::{Owner, candidates, reassign};
+use crate::client::Client;
+use mock_server::{Fault, MockServer, Route, Seed};
+
+#[test]
+fn candidates_are_merged() {
+    let users = vec!["guest".to_owned(), "admin".to_owned()];
+    let seen = vec![String::new(), "cook".to_owned(), "admin".to_owned()];
+    assert_eq!(candidates(users, seen), ["admin", "cook", "guest"]);
+}
+
+#[tokio::test]
+async fn owned_freezers() {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let client = Client::new(&server.api(), reqwest::Client::new());
+
+    assert_eq!(client.users().await.unwrap(), ["admin", "guest"]);
+    let owned = client
+        .freezers_owned(&Owner::User("admin".into()), None, None)
+        .await
+        .unwrap();
+    assert_eq!(owned, ["kitchen"]);
+    let unowned = client
+        .freezers_owned(&Owner::Nobody, None, None)
+        .await
+        .unwrap();
+    assert_eq!(unowned, ["garage"]);
+
+    server.inject(Route::Users, Fault::Status(404));
+    assert!(client.users().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn bulk_reassign() {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let client = Client::new(&server.api(), reqwest::Client::new());
+
+    let ids = vec!["kitchen".to_owned(), "garage".to_owned()];
+    let mut results = reassign(&client, ids, &Owner::User("guest".into()), 2).await;
+    results.sort_by(|(a, _), (b, _)| a.cmp(b));
+    assert!(matches!(&results[..], [(_, Ok(true)), (_, Ok(true))]));
+    assert_eq!(server.freezer("kitchen").unwrap()["owner"], "guest");
+    assert_eq!(server.freezer("garage").unwrap()["owner"], "guest");
+
+    server.inject(Route::Update, Fault::Status(403));
+    let results = reassign(&client, vec!["office".into()], &Owner::Nobody, 2).await;
+    assert!(matches!(&results[..], [(_, Ok(false))]));
+    let results = reassign(&client, vec!["none".into()], &Owner::Nobody, 2).await;
+    assert!(matches!(&results[..], [(_, Err(_))]));
+}
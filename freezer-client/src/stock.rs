@@ -0,0 +1,249 @@
+//! Stock across the whole inventory: units per product compared with
+//! [`Product::default`] and freezers by their fill.
+//!
+//! [`Thresholds`] are kept on the client side: the server knows nothing about them.
+
+use crate::{
+    api::FreezerApi,
+    inventory,
+    model::{Freezer, Product, Summary},
+    utils::{Error, Result},
+};
+use futures::{StreamExt, TryStreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stock {
+    pub product: String,
+    pub units: usize,
+    /// `None` if the product is unknown to the server
+    pub default: Option<usize>,
+}
+
+impl Stock {
+    /// Fewer units than [`Product::default`]
+    #[must_use]
+    pub fn is_low(&self) -> bool {
+        self.default.is_some_and(|default| self.units < default)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// Sorted by products
+    pub stock: Vec<Stock>,
+    /// Units per freezer, the fullest go first
+    pub freezers: Vec<(String, usize)>,
+    /// Server can't aggregate - every freezer was fetched
+    pub crawled: bool,
+}
+
+/// Every freezer of the server in no particular order,
+/// at most `parallel` freezers are requested at once
+///
+/// # Errors
+/// If any request fails
+pub async fn freezers(api: &dyn FreezerApi, parallel: usize) -> Result<Vec<Freezer>> {
+    stream::iter(inventory::ids(api).await?)
+        .map(|id| async move { api.freezer(&id).await })
+        .buffer_unordered(parallel.max(1))
+        .try_collect()
+        .await
+}
+
+/// Aggregates every freezer on the client side, see [`freezers`]
+///
+/// # Errors
+/// If any request fails
+pub async fn crawl(api: &dyn FreezerApi, parallel: usize) -> Result<Summary> {
+    Ok(Summary::of(&freezers(api, parallel).await?))
+}
+
+/// Uses the aggregate of the server if it's available, otherwise [`crawl`]s.
+/// Products are requested with the same parallelism
+///
+/// # Errors
+/// If any request fails
+pub async fn report(api: &dyn FreezerApi, parallel: usize) -> Result<Report> {
+    let (summary, crawled) = match api.summary().await? {
+        Some(summary) => (summary, false),
+        None => (crawl(api, parallel).await?, true),
+    };
+    let Summary { products, freezers } = summary;
+
+    let mut stock: Vec<_> = stream::iter(products)
+        .map(|(product, units)| async move {
+            let default = api
+                .product(&product)
+                .await?
+                .map(|Product { default, .. }| default);
+            Ok::<_, Error>(Stock {
+                product,
+                units,
+                default,
+            })
+        })
+        .buffer_unordered(parallel.max(1))
+        .try_collect()
+        .await?;
+    stock.sort_by(|a, b| a.product.cmp(&b.product));
+
+    let mut freezers: Vec<_> = freezers.into_iter().collect();
+    freezers.sort_by(|(a, a_units), (b, b_units)| b_units.cmp(a_units).then_with(|| a.cmp(b)));
+
+    Ok(Report {
+        stock,
+        freezers,
+        crawled,
+    })
+}
+
+/// Stock of a product in a freezer compared with its threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Enough,
+    /// Fewer units than the threshold
+    Low,
+    /// Nothing left of a product with a threshold
+    Out,
+}
+
+/// Minimum units to keep per product, a freezer may override them.
+/// Products without a threshold never alert
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Thresholds {
+    #[serde(default)]
+    pub products: HashMap<String, usize>,
+    /// Overrides by freezer ids, then by product ids
+    #[serde(default)]
+    pub freezers: HashMap<String, HashMap<String, usize>>,
+}
+
+impl Thresholds {
+    /// `thresholds.json` in the platform config directory
+    #[must_use]
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("freezers-client").join("thresholds.json"))
+    }
+
+    /// Missing file means no thresholds
+    ///
+    /// # Errors
+    /// If the file is not readable or malformed
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(json::from_slice(&bytes)?),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// # Errors
+    /// If the file or its directory is not writable
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Override of the freezer if any, otherwise the threshold of the product
+    #[must_use]
+    pub fn minimum(&self, freezer: &str, product: &str) -> Option<usize> {
+        self.freezers
+            .get(freezer)
+            .and_then(|overrides| overrides.get(product))
+            .or_else(|| self.products.get(product))
+            .copied()
+    }
+
+    /// Sets the threshold of the product, or its override in `freezer`.
+    /// `None` removes it
+    pub fn set(&mut self, freezer: Option<&str>, product: &str, minimum: Option<usize>) {
+        let thresholds = match freezer {
+            Some(freezer) => self.freezers.entry(freezer.to_owned()).or_default(),
+            None => &mut self.products,
+        };
+        match minimum {
+            Some(minimum) => {
+                thresholds.insert(product.to_owned(), minimum);
+            }
+            None => {
+                thresholds.remove(product);
+            }
+        }
+        self.freezers.retain(|_, overrides| !overrides.is_empty());
+    }
+
+    #[must_use]
+    pub fn level(&self, freezer: &str, product: &str, units: usize) -> Level {
+        match self.minimum(freezer, product) {
+            Some(minimum) if units < minimum => {
+                if units == 0 {
+                    Level::Out
+                } else {
+                    Level::Low
+                }
+            }
+            _ => Level::Enough,
+        }
+    }
+
+    /// Products below their thresholds, the emptiest go first
+    #[must_use]
+    pub fn alerts<'a>(&self, freezers: impl IntoIterator<Item = &'a Freezer>) -> Vec<Alert> {
+        let mut alerts: Vec<_> = freezers
+            .into_iter()
+            .flat_map(|freezer| {
+                freezer.products.iter().filter_map(|(product, &units)| {
+                    let level = self.level(&freezer.name, product, units);
+                    (level != Level::Enough).then(|| Alert {
+                        freezer: freezer.name.clone(),
+                        product: product.clone(),
+                        units,
+                        minimum: self.minimum(&freezer.name, product).unwrap_or_default(),
+                        level,
+                    })
+                })
+            })
+            .collect();
+        alerts.sort_by(|a, b| {
+            b.level
+                .cmp(&a.level)
+                .then_with(|| a.freezer.cmp(&b.freezer))
+                .then_with(|| a.product.cmp(&b.product))
+        });
+        alerts
+    }
+}
+
+/// Product of a freezer below its threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    pub freezer: String,
+    pub product: String,
+    pub units: usize,
+    pub minimum: usize,
+    pub level: Level,
+}
+
+/// [`Thresholds::alerts`] of every freezer, see [`freezers`]
+///
+/// # Errors
+/// If any request fails
+pub async fn scan(
+    api: &dyn FreezerApi,
+    thresholds: &Thresholds,
+    parallel: usize,
+) -> Result<Vec<Alert>> {
+    Ok(thresholds.alerts(&freezers(api, parallel).await?))
+}
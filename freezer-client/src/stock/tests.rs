@@ -0,0 +1,218 @@
+use super::{Alert, Level, Stock, Thresholds, crawl, report, scan};
+use crate::{
+    api::FreezerApi,
+    client::{Client, Image},
+    model::{Account, Freezer, Product},
+    owners::Owner,
+    utils::Result,
+};
+use async_trait::async_trait;
+use mock_server::{Fault, MockServer, Route, Seed};
+use std::{
+    env,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+async fn setup() -> (MockServer, Client) {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let client = Client::new(&server.api(), reqwest::Client::new());
+    (server, client)
+}
+
+#[tokio::test]
+async fn aggregate() {
+    let (server, client) = setup().await;
+
+    let report = report(&client, 4).await.unwrap();
+    assert!(!report.crawled);
+    assert_eq!(server.hits(Route::Freezer), 0);
+
+    assert_eq!(
+        report.stock,
+        [
+            Stock {
+                product: "ice-cream".into(),
+                units: 10,
+                default: Some(4)
+            },
+            Stock {
+                product: "pelmeni".into(),
+                units: 4,
+                default: Some(5)
+            },
+        ]
+    );
+    assert!(report.stock[1].is_low());
+    assert_eq!(
+        report.freezers,
+        [
+            ("kitchen".to_owned(), 13),
+            ("garage".to_owned(), 1),
+            ("office".to_owned(), 0)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn crawl_fallback() {
+    let (server, client) = setup().await;
+    let aggregated = report(&client, 4).await.unwrap();
+
+    server.inject(Route::Summary, Fault::Status(404));
+    let crawled = report(&client, 4).await.unwrap();
+    assert!(crawled.crawled);
+    assert_eq!(server.hits(Route::Freezer), 3);
+    assert_eq!(crawled.stock, aggregated.stock);
+    assert_eq!(crawled.freezers, aggregated.freezers);
+
+    server.inject(Route::Summary, Fault::Status(500));
+    assert!(report(&client, 4).await.is_err());
+}
+
+/// Counts requests of freezers in flight
+#[derive(Debug)]
+struct Gauge {
+    inner: Client,
+    in_flight: AtomicUsize,
+    max: AtomicUsize,
+}
+
+#[async_trait]
+impl FreezerApi for Gauge {
+    async fn login(&self, login: &str) -> Result<Account> {
+        self.inner.login(login).await
+    }
+
+    async fn image(&self, id: &str, etag: Option<&str>) -> Result<Image> {
+        self.inner.image(id, etag).await
+    }
+
+    async fn freezer(&self, id: &str) -> Result<Freezer> {
+        let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let freezer = self.inner.freezer(id).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        freezer
+    }
+
+    async fn update_freezer(&self, freezer: Freezer) -> Result<Option<Freezer>> {
+        self.inner.update_freezer(freezer).await
+    }
+
+    async fn delete_freezer(&self, id: &str) -> Result<bool> {
+        self.inner.delete_freezer(id).await
+    }
+
+    async fn freezers(&self) -> Result<Vec<String>> {
+        self.inner.freezers().await
+    }
+
+    async fn freezers_by(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<String>> {
+        self.inner.freezers_by(limit, offset).await
+    }
+
+    async fn freezers_owned(
+        &self,
+        owner: &Owner,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<String>> {
+        self.inner.freezers_owned(owner, limit, offset).await
+    }
+
+    async fn product(&self, id: &str) -> Result<Option<Product>> {
+        self.inner.product(id).await
+    }
+}
+
+#[tokio::test]
+async fn bounded_parallelism() {
+    let mut seed = Seed::demo();
+    seed.freezers = (0..40)
+        .map(|i| {
+            json::json!({
+                "_id": format!("freezer-{i:02}"),
+                "model": { "name": "Atlant", "year": 2015 },
+                "owner": null,
+                "products": { "pelmeni": 1 },
+            })
+        })
+        .collect();
+    let server = MockServer::start(seed).await.unwrap();
+    let gauge = Gauge {
+        inner: Client::new(&server.api(), reqwest::Client::new()),
+        in_flight: AtomicUsize::new(0),
+        max: AtomicUsize::new(0),
+    };
+
+    let summary = crawl(&gauge, 3).await.unwrap();
+    assert_eq!(summary.freezers.len(), 40);
+    assert_eq!(summary.products["pelmeni"], 40);
+    assert_eq!(gauge.max.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn thresholds() {
+    let mut thresholds = Thresholds::default();
+    assert_eq!(thresholds.level("kitchen", "pelmeni", 0), Level::Enough);
+
+    thresholds.set(None, "pelmeni", Some(3));
+    assert_eq!(thresholds.level("kitchen", "pelmeni", 3), Level::Enough);
+    assert_eq!(thresholds.level("kitchen", "pelmeni", 2), Level::Low);
+    assert_eq!(thresholds.level("kitchen", "pelmeni", 0), Level::Out);
+
+    // the freezer overrides the product
+    thresholds.set(Some("garage"), "pelmeni", Some(1));
+    assert_eq!(thresholds.minimum("garage", "pelmeni"), Some(1));
+    assert_eq!(thresholds.level("garage", "pelmeni", 2), Level::Enough);
+    assert_eq!(thresholds.minimum("kitchen", "pelmeni"), Some(3));
+
+    thresholds.set(Some("garage"), "pelmeni", None);
+    assert!(thresholds.freezers.is_empty());
+    assert_eq!(thresholds.minimum("garage", "pelmeni"), Some(3));
+}
+
+#[test]
+fn thresholds_file() {
+    let path = env::temp_dir()
+        .join(format!("freezers-client-thresholds-{}", std::process::id()))
+        .join("thresholds.json");
+    assert_eq!(Thresholds::load(&path).unwrap(), Thresholds::default());
+
+    let mut thresholds = Thresholds::default();
+    thresholds.set(None, "pelmeni", Some(3));
+    thresholds.set(Some("garage"), "berries", Some(1));
+    thresholds.save(&path).unwrap();
+    assert_eq!(Thresholds::load(&path).unwrap(), thresholds);
+
+    std::fs::write(&path, "{ oops").unwrap();
+    assert!(Thresholds::load(&path).is_err());
+}
+
+#[tokio::test]
+async fn alerts() {
+    let (_server, client) = setup().await;
+
+    let mut thresholds = Thresholds::default();
+    thresholds.set(None, "pelmeni", Some(2));
+    thresholds.set(None, "ice-cream", Some(5));
+    thresholds.set(Some("kitchen"), "pelmeni", Some(5));
+
+    let alert = |freezer: &str, units, minimum| Alert {
+        freezer: freezer.into(),
+        product: "pelmeni".into(),
+        units,
+        minimum,
+        level: Level::Low,
+    };
+    assert_eq!(
+        scan(&client, &thresholds, 2).await.unwrap(),
+        [alert("garage", 1, 2), alert("kitchen", 3, 5)]
+    );
+}
@@ -0,0 +1,261 @@
+use crate::{
+    model::{Freezer, Product},
+    utils::{Result, error::anyio, path},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+use tracing::debug;
+
+#[cfg(test)]
+mod tests;
+
+/// Mutation made while the server was unreachable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+// stored as JSON in the outbox, boxing would change nothing there
+#[allow(clippy::large_enum_variant)]
+pub enum Mutation {
+    Update {
+        /// last version seen from the server, `None` if freezer is new
+        base: Option<Freezer>,
+        freezer: Freezer,
+    },
+    Delete {
+        base: Option<Freezer>,
+        id: String,
+    },
+}
+
+impl Mutation {
+    /// Id of the mutated freezer
+    #[must_use]
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Update { freezer, .. } => &freezer.name,
+            Self::Delete { id, .. } => id,
+        }
+    }
+
+    #[must_use]
+    pub const fn base(&self) -> Option<&Freezer> {
+        match self {
+            Self::Update { base, .. } | Self::Delete { base, .. } => base.as_ref(),
+        }
+    }
+}
+
+/// Local copy of everything fetched from the server (per host) and the outbox
+/// of mutations that wait for connectivity.
+///
+/// Layout:
+/// ```text
+/// freezers.json         - ids in the server order
+/// freezers/{id}.json
+/// products/{id}.json
+/// outbox.json
+/// ```
+#[derive(Debug)]
+pub struct Store {
+    dir: PathBuf,
+    /// serializes read-modify-write of `freezers.json` and `outbox.json`
+    lock: Mutex<()>,
+    /// Length of `outbox.json`, kept in memory to be polled cheaply
+    pending: AtomicUsize,
+}
+
+impl Store {
+    /// Opens the store of the `api` host in the platform data directory
+    ///
+    /// # Errors
+    /// If there is no data directory or it is not writable
+    pub fn open(api: &str) -> Result<Self> {
+        let root = dirs::data_local_dir()
+            .ok_or_else(|| anyio!("no local data directory"))?
+            .join("freezers-client");
+        Self::open_in(&root, api)
+    }
+
+    /// Opens the store of the `api` host in `root`, next to the stores of the other hosts
+    ///
+    /// # Errors
+    /// If `root` is not writable
+    pub fn open_in(root: &Path, api: &str) -> Result<Self> {
+        Self::at(root.join(path::escape(api)))
+    }
+
+    /// Opens the store in `dir`
+    ///
+    /// # Errors
+    /// If `dir` is not writable
+    pub fn at(dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(dir.join("freezers"))?;
+        fs::create_dir_all(dir.join("products"))?;
+        let store = Self {
+            dir,
+            lock: Mutex::new(()),
+            pending: AtomicUsize::new(0),
+        };
+        store
+            .pending
+            .store(store.outbox_unlocked().len(), Ordering::Relaxed);
+        Ok(store)
+    }
+
+    fn read<T: DeserializeOwned>(path: &Path) -> Option<T> {
+        let bytes = fs::read(path).ok()?;
+        json::from_slice(&bytes)
+            .map_err(|error| debug!("`{}` is corrupted: `{error}`", path.display()))
+            .ok()
+    }
+
+    fn write<T: Serialize>(path: &Path, value: &T) {
+        let res = json::to_vec_pretty(value)
+            .map_err(Into::into)
+            .and_then(|bytes| fs::write(path, bytes));
+        if let Err(error) = res {
+            debug!("`{}` is not stored: `{error}`", path.display());
+        }
+    }
+
+    fn entry(&self, kind: &str, id: &str) -> PathBuf {
+        self.dir
+            .join(kind)
+            .join(format!("{}.json", path::escape(id)))
+    }
+
+    fn locked<T>(&self, f: impl FnOnce() -> T) -> T {
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        f()
+    }
+
+    fn ids(&self) -> Vec<String> {
+        Self::read(&self.dir.join("freezers.json")).unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn freezers(&self, limit: Option<usize>, offset: Option<usize>) -> Vec<String> {
+        let ids = self.locked(|| self.ids());
+        ids.into_iter()
+            .skip(offset.unwrap_or_default())
+            .take(limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Remembers ids of the fetched page keeping the server order
+    pub fn put_freezers(&self, page: &[String]) {
+        self.locked(|| {
+            let mut ids = self.ids();
+            let new: Vec<_> = page
+                .iter()
+                .filter(|id| !ids.contains(id))
+                .cloned()
+                .collect();
+            ids.extend(new);
+            Self::write(&self.dir.join("freezers.json"), &ids);
+        });
+    }
+
+    #[must_use]
+    pub fn freezer(&self, id: &str) -> Option<Freezer> {
+        Self::read(&self.entry("freezers", id))
+    }
+
+    pub fn put_freezer(&self, freezer: &Freezer) {
+        self.put_freezers(std::slice::from_ref(&freezer.name));
+        Self::write(&self.entry("freezers", &freezer.name), freezer);
+    }
+
+    pub fn remove_freezer(&self, id: &str) {
+        self.locked(|| {
+            let mut ids = self.ids();
+            ids.retain(|known| known != id);
+            Self::write(&self.dir.join("freezers.json"), &ids);
+        });
+        let _ = fs::remove_file(self.entry("freezers", id));
+    }
+
+    #[must_use]
+    pub fn product(&self, id: &str) -> Option<Product> {
+        Self::read(&self.entry("products", id))
+    }
+
+    pub fn put_product(&self, product: &Product) {
+        Self::write(&self.entry("products", &product.name), product);
+    }
+
+    fn outbox_unlocked(&self) -> Vec<Mutation> {
+        Self::read(&self.dir.join("outbox.json")).unwrap_or_default()
+    }
+
+    fn write_outbox(&self, outbox: &[Mutation]) {
+        Self::write(&self.dir.join("outbox.json"), &outbox);
+        self.pending.store(outbox.len(), Ordering::Relaxed);
+    }
+
+    /// Queued mutations in order
+    #[must_use]
+    pub fn outbox(&self) -> Vec<Mutation> {
+        self.locked(|| self.outbox_unlocked())
+    }
+
+    /// Count of the queued mutations without reading the outbox
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Queues the mutation and applies it to the local copy.
+    /// Mutations of the same freezer are merged, so the earliest `base` is kept
+    /// to detect conflicts on replay
+    pub fn queue(&self, mutation: Mutation) {
+        match &mutation {
+            Mutation::Update { freezer, .. } => self.put_freezer(freezer),
+            Mutation::Delete { id, .. } => self.remove_freezer(id),
+        }
+
+        self.locked(|| {
+            let mut outbox = self.outbox_unlocked();
+            let mutation = match outbox
+                .iter()
+                .position(|queued| queued.id() == mutation.id())
+            {
+                Some(index) => {
+                    let base = outbox.remove(index).base().cloned();
+                    match mutation {
+                        Mutation::Update { freezer, .. } => Mutation::Update { base, freezer },
+                        Mutation::Delete { id, .. } => Mutation::Delete { base, id },
+                    }
+                }
+                None => mutation,
+            };
+            outbox.push(mutation);
+            self.write_outbox(&outbox);
+        });
+    }
+
+    /// Takes all queued mutations, unapplied ones must be returned by [`Store::restore`]
+    pub fn take_outbox(&self) -> Vec<Mutation> {
+        self.locked(|| {
+            let outbox = self.outbox_unlocked();
+            self.write_outbox(&[]);
+            outbox
+        })
+    }
+
+    /// Puts unapplied mutations back before the ones queued during replay
+    pub fn restore(&self, mut mutations: Vec<Mutation>) {
+        if mutations.is_empty() {
+            return;
+        }
+        self.locked(|| {
+            mutations.extend(self.outbox_unlocked());
+            self.write_outbox(&mutations);
+        });
+    }
+}
@@ -0,0 +1,94 @@
+use super::{Mutation, Store};
+use crate::{
+    model::{Details, Freezer, Model},
+    utils::path::temp_dir,
+};
+use std::collections::HashMap;
+
+fn freezer(name: &str, pelmeni: usize) -> Freezer {
+    Freezer {
+        name: name.to_owned(),
+        model: Model {
+            name: "Atlant".to_owned(),
+            year: 2015,
+        },
+        owner: None,
+        details: Details::default(),
+        products: HashMap::from([("pelmeni".to_owned(), pelmeni)]),
+        batches: HashMap::new(),
+    }
+}
+
+fn update(base: Option<Freezer>, freezer: Freezer) -> Mutation {
+    Mutation::Update { base, freezer }
+}
+
+fn ids(outbox: &[Mutation]) -> Vec<&str> {
+    outbox.iter().map(Mutation::id).collect()
+}
+
+#[test]
+fn merge_keeps_earliest_base() {
+    let store = Store::at(temp_dir()).unwrap();
+    let server = freezer("kitchen", 1);
+
+    store.queue(update(Some(server.clone()), freezer("kitchen", 2)));
+    store.queue(update(Some(freezer("kitchen", 2)), freezer("kitchen", 3)));
+    store.queue(update(None, freezer("garage", 1)));
+    assert_eq!(store.pending(), 2);
+    assert_eq!(store.freezer("kitchen"), Some(freezer("kitchen", 3)));
+
+    let outbox = store.outbox();
+    assert_eq!(ids(&outbox), ["kitchen", "garage"]);
+    assert!(matches!(&outbox[0], Mutation::Update { base, freezer }
+        if base.as_ref() == Some(&server) && freezer.products["pelmeni"] == 3));
+
+    // the deletion replaces the update, still against the server version
+    store.queue(Mutation::Delete {
+        base: Some(freezer("kitchen", 3)),
+        id: "kitchen".to_owned(),
+    });
+    let outbox = store.outbox();
+    assert_eq!(ids(&outbox), ["garage", "kitchen"]);
+    assert!(matches!(&outbox[1], Mutation::Delete { base, .. } if base.as_ref() == Some(&server)));
+    assert_eq!(store.freezer("kitchen"), None);
+    assert_eq!(store.pending(), 2);
+}
+
+#[test]
+fn take_then_restore() {
+    let store = Store::at(temp_dir()).unwrap();
+    store.queue(update(None, freezer("kitchen", 1)));
+    store.queue(update(None, freezer("garage", 1)));
+
+    let taken = store.take_outbox();
+    assert_eq!(ids(&taken), ["kitchen", "garage"]);
+    assert_eq!(store.pending(), 0);
+    assert!(store.outbox().is_empty());
+
+    // queued while replaying, the unapplied ones go before it
+    store.queue(update(None, freezer("cellar", 1)));
+    store.restore(taken[1..].to_vec());
+    assert_eq!(ids(&store.outbox()), ["garage", "cellar"]);
+    assert_eq!(store.pending(), 2);
+
+    // nothing to restore
+    store.restore(Vec::new());
+    assert_eq!(store.pending(), 2);
+}
+
+#[test]
+fn pending_count_is_kept() {
+    let dir = temp_dir();
+    let store = Store::at(dir.clone()).unwrap();
+    assert_eq!(store.pending(), 0);
+
+    store.queue(update(None, freezer("kitchen", 1)));
+    store.queue(update(None, freezer("kitchen", 2)));
+    store.queue(update(None, freezer("garage", 1)));
+    assert_eq!(store.pending(), store.outbox().len());
+    assert_eq!(store.pending(), 2);
+
+    // the count of the reopened store is read from the outbox once
+    assert_eq!(Store::at(dir).unwrap().pending(), 2);
+}
@@ -0,0 +1,176 @@
+//! Moving units of a product from one freezer to another.
+//!
+//! Servers with [`Client::commit_transfer`](crate::Client::commit_transfer) update both
+//! freezers at once. Others get two updates: the source first and the target second,
+//! the source is restored if the target fails.
+
+use crate::{
+    api::FreezerApi,
+    model::{Batch, Freezer},
+    utils::{Error, Result, error::anyio},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[cfg(test)]
+mod tests;
+
+/// Units of the product to move between the freezers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transfer {
+    pub from: String,
+    pub to: String,
+    pub product: String,
+    pub amount: usize,
+}
+
+/// Response of [`Client::commit_transfer`](crate::Client::commit_transfer)
+#[derive(Debug, Clone)]
+// returned right away, not worth the boxing
+#[allow(clippy::large_enum_variant)]
+pub enum Commit {
+    /// Both freezers as the server stored them
+    Committed { from: Freezer, to: Freezer },
+    /// Server refused the transfer (e.g. not enough privileges)
+    Refused,
+    /// Server has no transactional transfers or is unreachable
+    Unsupported,
+}
+
+/// Result of [`run`]
+#[derive(Debug, Clone)]
+// reported once per transfer, not worth the boxing
+#[allow(clippy::large_enum_variant)]
+pub enum Outcome {
+    /// Both freezers are updated, `atomic` if by a single request
+    Moved {
+        from: Freezer,
+        to: Freezer,
+        atomic: bool,
+    },
+    /// Server refused the transfer, nothing is changed
+    Refused,
+    /// Target is not updated, the source is restored
+    RolledBack(Error),
+    /// Neither the target is updated nor the source is restored:
+    /// the units are gone from the source, `source` is its state before the transfer
+    Broken { error: Error, source: Freezer },
+}
+
+impl Display for Transfer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            from,
+            to,
+            product,
+            amount,
+        } = self;
+        write!(f, "{amount} of `{product}` from `{from}` to `{to}`")
+    }
+}
+
+impl Transfer {
+    /// Moves the units between the local copies.
+    ///
+    /// Undated units go first, then the expiring batches - the ones
+    /// [`Freezer::trim_batches`] would drop
+    ///
+    /// # Errors
+    /// If the freezers are the same, the amount is zero or the source has less
+    pub fn apply(&self, from: &mut Freezer, to: &mut Freezer) -> Result<()> {
+        let Self {
+            product, amount, ..
+        } = self;
+        if from.name == to.name {
+            return Err(anyio!("`{product}` can't be moved within `{}`", from.name));
+        }
+        if *amount == 0 {
+            return Err(anyio!("nothing to move"));
+        }
+        let available = from.products.get(product).copied().unwrap_or_default();
+        if available < *amount {
+            return Err(anyio!(
+                "`{}` has only {available} of `{product}`",
+                from.name
+            ));
+        }
+
+        let mut moved = Vec::new();
+        if let Some(batches) = from.batches.get_mut(product) {
+            Batch::sort(batches);
+            let dated: usize = batches.iter().map(|batch| batch.quantity).sum();
+            let mut left = amount.saturating_sub(available - dated);
+            for batch in batches.iter_mut() {
+                let used = left.min(batch.quantity);
+                if used > 0 {
+                    moved.push(Batch {
+                        quantity: used,
+                        ..batch.clone()
+                    });
+                    batch.quantity -= used;
+                    left -= used;
+                }
+            }
+            batches.retain(|batch| batch.quantity > 0);
+            if batches.is_empty() {
+                from.batches.remove(product);
+            }
+        }
+
+        if available == *amount {
+            from.products.remove(product);
+        } else {
+            from.products.insert(product.clone(), available - amount);
+        }
+        *to.products.entry(product.clone()).or_default() += amount;
+        if !moved.is_empty() {
+            to.batches.entry(product.clone()).or_default().extend(moved);
+        }
+        Ok(())
+    }
+}
+
+/// Moves the units by the transactional endpoint if the server has it,
+/// by two updates otherwise
+///
+/// # Errors
+/// If the freezers can't be fetched, the transfer can't be applied (see
+/// [`Transfer::apply`]) or nothing is changed because the first request fails
+pub async fn run(api: &dyn FreezerApi, transfer: &Transfer) -> Result<Outcome> {
+    let source = api.freezer(&transfer.from).await?;
+    let target = api.freezer(&transfer.to).await?;
+    let (mut from, mut to) = (source.clone(), target);
+    transfer.apply(&mut from, &mut to)?;
+
+    match api.commit_transfer(from.clone(), to.clone()).await? {
+        Commit::Committed { from, to } => {
+            return Ok(Outcome::Moved {
+                from,
+                to,
+                atomic: true,
+            });
+        }
+        Commit::Refused => return Ok(Outcome::Refused),
+        Commit::Unsupported => {}
+    }
+
+    let Some(from) = api.update_freezer(from).await? else {
+        return Ok(Outcome::Refused);
+    };
+    let error = match api.update_freezer(to).await {
+        Ok(Some(to)) => {
+            return Ok(Outcome::Moved {
+                from,
+                to,
+                atomic: false,
+            });
+        }
+        Ok(None) => anyio!("unauthorized access: `{}` is not updated", transfer.to),
+        Err(error) => error,
+    };
+
+    match api.update_freezer(source.clone()).await {
+        Ok(Some(_)) => Ok(Outcome::RolledBack(error)),
+        Ok(None) | Err(_) => Ok(Outcome::Broken { error, source }),
+    }
+}
@@ -0,0 +1,197 @@
+use super::{Outcome, Transfer, run};
+use crate::{
+    api::FreezerApi,
+    client::{Client, Image},
+    model::{Account, Batch, Freezer, Product},
+    owners::Owner,
+    utils::{Result, error::anyio},
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use mock_server::{Fault, MockServer, Route, Seed};
+
+fn transfer(from: &str, to: &str, product: &str, amount: usize) -> Transfer {
+    Transfer {
+        from: from.to_owned(),
+        to: to.to_owned(),
+        product: product.to_owned(),
+        amount,
+    }
+}
+
+fn units(server: &MockServer, id: &str, product: &str) -> Option<u64> {
+    server.freezer(id).unwrap()["products"][product].as_u64()
+}
+
+#[test]
+fn undated_units_go_first() {
+    let date = |day| NaiveDate::from_ymd_opt(2023, 4, day);
+    let batch = |quantity, day| Batch {
+        quantity,
+        frozen: None,
+        best_before: date(day),
+    };
+    let mut from: Freezer = json::from_value(json::json!({
+        "_id": "kitchen",
+        "model": { "name": "Atlant", "year": 2015 },
+        "owner": null,
+        "products": { "pelmeni": 5 },
+        "batches": { "pelmeni": [batch(2, 20), batch(1, 10)] },
+    }))
+    .unwrap();
+    let mut to = Freezer {
+        name: "garage".into(),
+        ..from.clone()
+    };
+    to.products.clear();
+    to.batches.clear();
+
+    transfer("kitchen", "garage", "pelmeni", 3)
+        .apply(&mut from, &mut to)
+        .unwrap();
+    assert_eq!(from.products["pelmeni"], 2);
+    assert_eq!(from.batches["pelmeni"], [batch(2, 20)]);
+    assert_eq!(to.products["pelmeni"], 3);
+    assert_eq!(to.batches["pelmeni"], [batch(1, 10)]);
+
+    transfer("kitchen", "garage", "pelmeni", 2)
+        .apply(&mut from, &mut to)
+        .unwrap();
+    assert!(from.products.is_empty() && from.batches.is_empty());
+    assert_eq!(to.products["pelmeni"], 5);
+
+    let error = transfer("kitchen", "garage", "pelmeni", 1).apply(&mut from, &mut to);
+    assert_eq!(
+        error.unwrap_err().to_string(),
+        "`kitchen` has only 0 of `pelmeni`"
+    );
+    assert!(
+        transfer("garage", "garage", "pelmeni", 1)
+            .apply(&mut to.clone(), &mut to)
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn atomic_and_two_step() {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let client = Client::new(&server.api(), reqwest::Client::new());
+
+    let outcome = run(&client, &transfer("kitchen", "garage", "pelmeni", 2))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, Outcome::Moved { atomic: true, .. }));
+    assert_eq!(units(&server, "kitchen", "pelmeni"), Some(1));
+    assert_eq!(units(&server, "garage", "pelmeni"), Some(3));
+    assert_eq!(server.hits(Route::Update), 0);
+
+    server.inject(Route::Transfer, Fault::Status(404));
+    let outcome = run(&client, &transfer("kitchen", "office", "ice-cream", 4))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, Outcome::Moved { atomic: false, .. }));
+    assert_eq!(units(&server, "kitchen", "ice-cream"), Some(6));
+    assert_eq!(units(&server, "office", "ice-cream"), Some(4));
+    assert_eq!(server.hits(Route::Update), 2);
+
+    server.inject(Route::Update, Fault::Status(403));
+    let outcome = run(&client, &transfer("kitchen", "office", "ice-cream", 1))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, Outcome::Refused));
+    assert_eq!(units(&server, "kitchen", "ice-cream"), Some(6));
+
+    assert!(
+        run(&client, &transfer("kitchen", "office", "pelmeni", 5))
+            .await
+            .is_err()
+    );
+}
+
+/// Fails the updates of the `failing` freezer, and the restore of the source
+/// (back to 3 `pelmeni`) if `restore_fails`
+#[derive(Debug)]
+struct Flaky {
+    inner: Client,
+    failing: String,
+    restore_fails: bool,
+}
+
+#[async_trait]
+impl FreezerApi for Flaky {
+    async fn login(&self, login: &str) -> Result<Account> {
+        self.inner.login(login).await
+    }
+
+    async fn image(&self, id: &str, etag: Option<&str>) -> Result<Image> {
+        self.inner.image(id, etag).await
+    }
+
+    async fn freezer(&self, id: &str) -> Result<Freezer> {
+        self.inner.freezer(id).await
+    }
+
+    async fn update_freezer(&self, freezer: Freezer) -> Result<Option<Freezer>> {
+        let restore = freezer.products.get("pelmeni") == Some(&3);
+        if freezer.name == self.failing || (restore && self.restore_fails) {
+            return Err(anyio!("connection reset"));
+        }
+        self.inner.update_freezer(freezer).await
+    }
+
+    async fn delete_freezer(&self, id: &str) -> Result<bool> {
+        self.inner.delete_freezer(id).await
+    }
+
+    async fn freezers(&self) -> Result<Vec<String>> {
+        self.inner.freezers().await
+    }
+
+    async fn freezers_by(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<String>> {
+        self.inner.freezers_by(limit, offset).await
+    }
+
+    async fn freezers_owned(
+        &self,
+        owner: &Owner,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<String>> {
+        self.inner.freezers_owned(owner, limit, offset).await
+    }
+
+    async fn product(&self, id: &str) -> Result<Option<Product>> {
+        self.inner.product(id).await
+    }
+}
+
+#[tokio::test]
+async fn compensation() {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let mut flaky = Flaky {
+        inner: Client::new(&server.api(), reqwest::Client::new()),
+        failing: "garage".into(),
+        restore_fails: false,
+    };
+    let pelmeni = transfer("kitchen", "garage", "pelmeni", 1);
+
+    // the default backend has no transactional transfers
+    let outcome = run(&flaky, &pelmeni).await.unwrap();
+    assert!(
+        matches!(&outcome, Outcome::RolledBack(error) if error.to_string() == "connection reset")
+    );
+    assert_eq!(units(&server, "kitchen", "pelmeni"), Some(3));
+    assert_eq!(units(&server, "garage", "pelmeni"), Some(1));
+
+    flaky.restore_fails = true;
+    let outcome = run(&flaky, &pelmeni).await.unwrap();
+    let Outcome::Broken { source, .. } = outcome else {
+        panic!("source is not restored: {outcome:?}");
+    };
+    assert_eq!(source.products["pelmeni"], 3);
+    assert_eq!(units(&server, "kitchen", "pelmeni"), Some(2));
+}
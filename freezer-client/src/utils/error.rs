@@ -5,6 +5,7 @@ use std::{
 };
 use tracing::debug;
 
+/// Any failure of the client, cheap to clone to pass it around messages
 #[derive(Debug, Clone)]
 pub struct Error {
     inner: Arc<anyhow::Error>,
@@ -29,6 +30,7 @@ impl Display for Error {
     }
 }
 
+/// Ad hoc [`Error`] from the format string
 #[macro_export]
 macro_rules! anyio {
     ($($tt:tt)*) => {
@@ -44,4 +46,5 @@ pub use anyio;
 //     }
 // }
 
+/// `Result` with [`Error`] by default
 pub type Result<T, E = Error> = std::result::Result<T, E>;
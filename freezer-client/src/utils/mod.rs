@@ -1,5 +1,4 @@
 pub mod error;
-pub mod macros;
+pub mod path;
 
 pub use error::{Error, Result};
-pub use macros::*;
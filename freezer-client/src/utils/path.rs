@@ -0,0 +1,34 @@
+use std::fmt::Write;
+
+#[cfg(test)]
+mod tests;
+
+/// Fresh directory per test - they run concurrently
+#[cfg(test)]
+pub fn temp_dir() -> std::path::PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "freezers-client-test-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Ids and hosts are arbitrary strings - escape everything except `[a-zA-Z0-9-]`
+/// to use them as file names. Escapes are of the fixed width, so the names never collide
+#[must_use]
+pub fn escape(id: &str) -> String {
+    id.chars()
+        .fold(String::with_capacity(id.len()), |mut stem, char| {
+            if char.is_ascii_alphanumeric() || char == '-' {
+                stem.push(char);
+            } else {
+                let _ = write!(stem, "_{:06x}", u32::from(char));
+            }
+            stem
+        })
+}
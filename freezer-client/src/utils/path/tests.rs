@@ -0,0 +1,16 @@
+use super::escape;
+
+#[test]
+fn escaped_names() {
+    assert_eq!(escape("kitchen-2"), "kitchen-2");
+    assert_eq!(escape("a b"), "a_000020b");
+    assert_eq!(escape("http://host"), "http_00003a_00002f_00002fhost");
+}
+
+#[test]
+fn escapes_never_collide() {
+    // `_{:x}` gave `_200` for both
+    assert_ne!(escape(" 0"), escape("\u{200}"));
+    assert_ne!(escape("_"), escape("_5f"));
+    assert_eq!(escape("\u{10ffff}"), "_10ffff");
+}
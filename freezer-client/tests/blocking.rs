@@ -0,0 +1,25 @@
+#![cfg(feature = "blocking")]
+
+use freezer_client::blocking::Client;
+use mock_server::{Fault, MockServer, Route, Seed};
+
+#[test]
+fn blocking() {
+    // the server needs a runtime of its own, the client blocks on another one
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockServer::start(Seed::demo())).unwrap();
+    let client = Client::new(&server.api()).unwrap();
+
+    client.login("admin").unwrap();
+    assert_eq!(client.freezers_by(2, 0).unwrap(), ["garage", "kitchen"]);
+
+    let mut garage = client.freezer("garage").unwrap();
+    garage.products.insert("berries".into(), 7);
+    assert_eq!(client.update_freezer(garage.clone()).unwrap(), Some(garage));
+    assert!(client.delete_freezer("office").unwrap());
+    assert!(client.product("caviar").unwrap().is_none());
+    assert!(client.summary().unwrap().is_some());
+
+    server.inject(Route::Freezers, Fault::Malformed);
+    assert!(client.freezers().is_err());
+}
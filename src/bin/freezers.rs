@@ -0,0 +1,308 @@
+#![deny(clippy::all, clippy::nursery, clippy::perf, clippy::pedantic)]
+
+//! Command line companion of the GUI for scripted and bulk actions
+
+use bytesize::ByteSize;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use freezer_client::{
+    client::{Client, Image},
+    history,
+    model::Freezer,
+    utils::{Result, error::anyio},
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, process::ExitCode};
+
+#[cfg(test)]
+#[path = "freezers/tests.rs"]
+mod tests;
+
+#[derive(Debug, Parser)]
+#[command(name = "freezers", about = "Freezers from the command line")]
+struct Cli {
+    /// API root, the one of the last `login` by default
+    #[arg(long, env = "FREEZERS_API", global = true)]
+    api: Option<String>,
+
+    #[arg(long, value_enum, default_value_t = Format::Table, global = true)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Checks the login and remembers it for the next commands
+    Login {
+        login: String,
+    },
+    /// Ids of the freezers
+    List {
+        #[arg(long)]
+        limit: Option<usize>,
+        #[arg(long)]
+        offset: Option<usize>,
+    },
+    Show {
+        id: String,
+    },
+    /// Sets amounts of the products
+    Update {
+        id: String,
+        /// `product=N`, can be repeated
+        #[arg(long = "set", value_name = "PRODUCT=N", value_parser = assignment, required = true)]
+        set: Vec<(String, usize)>,
+    },
+    Delete {
+        id: String,
+    },
+    /// Changes of the freezer, the newest first
+    History {
+        id: String,
+    },
+    /// Downloads the image of the freezer
+    Image {
+        id: String,
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+}
+
+fn assignment(arg: &str) -> Result<(String, usize), String> {
+    let (product, amount) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected `product=N`, got `{arg}`"))?;
+    if product.is_empty() {
+        return Err(format!("no product in `{arg}`"));
+    }
+    let amount = amount
+        .parse()
+        .map_err(|error| format!("invalid amount `{amount}`: {error}"))?;
+    Ok((product.to_owned(), amount))
+}
+
+/// Remembered by `login`, every other command logs in with it
+#[derive(Debug, Serialize, Deserialize)]
+struct Session {
+    api: String,
+    login: String,
+}
+
+impl Session {
+    fn path() -> Result<PathBuf> {
+        Ok(dirs::config_dir()
+            .ok_or_else(|| anyio!("no config directory"))?
+            .join("freezers-client")
+            .join("session.json"))
+    }
+
+    fn load() -> Result<Self> {
+        let bytes = fs::read(Self::path()?)
+            .map_err(|_| anyio!("not logged in - run `freezers login <LOGIN>` first"))?;
+        Ok(json::from_slice(&bytes)?)
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(fs::write(path, json::to_vec_pretty(self)?)?)
+    }
+}
+
+/// Columns are aligned by the widest cell
+fn table<const N: usize>(head: [&str; N], rows: &[[String; N]]) -> String {
+    let mut widths = head.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &mut dyn Iterator<Item = &str>| {
+        let cells: Vec<_> = cells
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        cells.join("  ").trim_end().to_owned()
+    };
+
+    std::iter::once(line(&mut head.into_iter()))
+        .chain(
+            rows.iter()
+                .map(|row| line(&mut row.iter().map(String::as_str))),
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn print(format: Format, value: &impl Serialize, table: impl FnOnce() -> String) -> Result<()> {
+    match format {
+        Format::Table => println!("{}", table()),
+        Format::Json => println!("{}", json::to_string_pretty(value)?),
+    }
+    Ok(())
+}
+
+fn freezer_table(freezer: &Freezer) -> String {
+    let mut products: Vec<_> = freezer.products.iter().collect();
+    products.sort();
+
+    let fields = table(
+        ["FIELD", "VALUE"],
+        &[
+            ["name".into(), freezer.name.clone()],
+            [
+                "model".into(),
+                format!("{} ({})", freezer.model.name, freezer.model.year),
+            ],
+            ["owner".into(), freezer.owner.clone().unwrap_or_default()],
+        ]
+        .into_iter()
+        .chain(
+            freezer
+                .details
+                .fields()
+                .map(|(label, value)| [label.into(), value.unwrap_or_default()]),
+        )
+        .collect::<Vec<_>>(),
+    );
+    let products: Vec<_> = products
+        .into_iter()
+        .map(|(name, amount)| [name.clone(), amount.to_string()])
+        .collect();
+    let products = table(["PRODUCT", "AMOUNT"], &products);
+    if freezer.batches.is_empty() {
+        return format!("{fields}\n\n{products}");
+    }
+
+    let date = |date: Option<NaiveDate>| date.map(|date| date.to_string()).unwrap_or_default();
+    let mut batches: Vec<_> = freezer.batches.iter().collect();
+    batches.sort_by(|a, b| a.0.cmp(b.0));
+    let batches: Vec<_> = batches
+        .into_iter()
+        .flat_map(|(name, batches)| {
+            batches.iter().map(move |batch| {
+                [
+                    name.clone(),
+                    batch.quantity.to_string(),
+                    date(batch.frozen),
+                    date(batch.best_before),
+                ]
+            })
+        })
+        .collect();
+    let batches = table(["PRODUCT", "QUANTITY", "FROZEN", "BEST BEFORE"], &batches);
+    format!("{fields}\n\n{products}\n\n{batches}")
+}
+
+async fn run(
+    Cli {
+        api,
+        format,
+        command,
+    }: Cli,
+) -> Result<()> {
+    let inner = reqwest::Client::new();
+
+    if let Command::Login { login } = command {
+        let api = api.unwrap_or_else(|| Client::DEFAULT_API.to_owned());
+        Client::new(&api, inner).login(&login).await?;
+
+        let session = Session { api, login };
+        session.save()?;
+        return print(format, &session, || {
+            format!("logged in as `{}` at {}", session.login, session.api)
+        });
+    }
+
+    let session = Session::load()?;
+    let client = Client::new(api.as_deref().unwrap_or(&session.api), inner);
+    client.login(&session.login).await?;
+
+    match command {
+        Command::Login { .. } => unreachable!("handled above"),
+        Command::List { limit, offset } => {
+            let freezers = client.freezers_by(limit, offset).await?;
+            print(format, &freezers, || {
+                let rows: Vec<_> = freezers.iter().map(|id| [id.clone()]).collect();
+                table(["ID"], &rows)
+            })
+        }
+        Command::Show { id } => {
+            let freezer = client.freezer(&id).await?;
+            print(format, &freezer, || freezer_table(&freezer))
+        }
+        Command::Update { id, set } => {
+            let mut freezer = client.freezer(&id).await?;
+            freezer.products.extend(set);
+            freezer.trim_batches();
+
+            let freezer = client
+                .update_freezer(freezer)
+                .await?
+                .ok_or_else(|| anyio!("unauthorized access: `{id}` is not updated"))?;
+            print(format, &freezer, || freezer_table(&freezer))
+        }
+        Command::Delete { id } => {
+            if !client.delete_freezer(&id).await? {
+                return Err(anyio!("unauthorized access: `{id}` is not deleted"));
+            }
+            print(format, &json::json!({ "deleted": &id }), || {
+                format!("deleted `{id}`")
+            })
+        }
+        Command::History { id } => {
+            let revisions = client.freezer_history(&id).await?;
+            print(format, &revisions, || {
+                let rows: Vec<_> = history::entries(revisions.clone())
+                    .into_iter()
+                    .map(|entry| {
+                        [
+                            entry.revision.at.to_rfc3339(),
+                            entry.revision.user.unwrap_or_else(|| "unknown".to_owned()),
+                            entry.diff.join("; "),
+                        ]
+                    })
+                    .collect();
+                table(["AT", "USER", "CHANGES"], &rows)
+            })
+        }
+        Command::Image { id, output } => {
+            let bytes = match client.image(&id, None).await? {
+                Image::Fresh { bytes, .. } => bytes,
+                Image::Missing | Image::NotModified => {
+                    return Err(anyio!("`{id}` has no image"));
+                }
+            };
+            fs::write(&output, &bytes)?;
+
+            let size = bytes.len();
+            print(
+                format,
+                &json::json!({ "path": &output, "bytes": size }),
+                || format!("saved {} to {}", ByteSize(size as u64), output.display()),
+            )
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use super::{Cli, Command, assignment, freezer_table, table};
+use clap::Parser;
+use freezer_client::model::{Details, Freezer, Model};
+use std::collections::HashMap;
+
+#[test]
+fn assignments() {
+    assert_eq!(assignment("pelmeni=3"), Ok(("pelmeni".to_owned(), 3)));
+    assert_eq!(assignment("ice cream=0"), Ok(("ice cream".to_owned(), 0)));
+
+    assert!(
+        assignment("pelmeni")
+            .unwrap_err()
+            .contains("expected `product=N`")
+    );
+    assert!(
+        assignment("pelmeni=three")
+            .unwrap_err()
+            .contains("invalid amount `three`")
+    );
+    assert!(
+        assignment("pelmeni=-1")
+            .unwrap_err()
+            .contains("invalid amount `-1`")
+    );
+    assert!(
+        assignment("pelmeni=")
+            .unwrap_err()
+            .contains("invalid amount ``")
+    );
+    assert_eq!(assignment("=3"), Err("no product in `=3`".to_owned()));
+}
+
+#[test]
+fn set_is_parsed() {
+    let cli = Cli::try_parse_from([
+        "freezers",
+        "update",
+        "garage",
+        "--set",
+        "pelmeni=3",
+        "--set",
+        "berries=7",
+    ])
+    .unwrap();
+    assert!(matches!(cli.command, Command::Update { id, set }
+        if id == "garage" && set == [("pelmeni".to_owned(), 3), ("berries".to_owned(), 7)]));
+
+    for malformed in ["pelmeni", "pelmeni=three", "=3"] {
+        let args = ["freezers", "update", "garage", "--set", malformed];
+        assert!(Cli::try_parse_from(args).is_err(), "{malformed}");
+    }
+    // at least one is required
+    assert!(Cli::try_parse_from(["freezers", "update", "garage"]).is_err());
+}
+
+#[test]
+fn columns_are_aligned() {
+    let rows = [
+        ["pelmeni".to_owned(), "3".to_owned()],
+        ["ice cream".to_owned(), "12".to_owned()],
+        ["щи".to_owned(), String::new()],
+    ];
+    assert_eq!(
+        table(["PRODUCT", "AMOUNT"], &rows),
+        "PRODUCT    AMOUNT\n\
+         pelmeni    3\n\
+         ice cream  12\n\
+         щи"
+    );
+    assert_eq!(table(["ID"], &[]), "ID");
+}
+
+#[test]
+fn freezer_is_tabulated() {
+    let freezer = Freezer {
+        name: "garage".to_owned(),
+        model: Model {
+            name: "Atlant".to_owned(),
+            year: 2015,
+        },
+        owner: Some("admin".to_owned()),
+        details: Details::default(),
+        products: HashMap::from([("pelmeni".to_owned(), 3), ("berries".to_owned(), 7)]),
+        batches: HashMap::new(),
+    };
+    let table = freezer_table(&freezer);
+
+    let (fields, products) = table.split_once("\n\n").unwrap();
+    // empty details are trimmed
+    assert_eq!(
+        fields,
+        "FIELD        VALUE\n\
+         name         garage\n\
+         model        Atlant (2015)\n\
+         owner        admin\n\
+         capacity\n\
+         location\n\
+         temperature\n\
+         notes"
+    );
+    // sorted by name, no batches
+    assert_eq!(products, "PRODUCT  AMOUNT\nberries  7\npelmeni  3");
+}
@@ -0,0 +1,134 @@
+use crate::utils::path;
+use bytes::Bytes;
+use lru::LruCache;
+use std::{
+    fs as sync_fs, io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+use tokio::fs;
+use tracing::debug;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub etag: Option<String>,
+    pub bytes: Bytes,
+}
+
+/// Freezer images keyed by freezer id: in-memory LRU over the on-disk storage.
+/// The memory is locked only while it's accessed, never during the disk IO
+#[derive(Debug)]
+pub struct ImageCache {
+    memory: Mutex<LruCache<String, Entry>>,
+    /// `None` if platform has no cache directory - only memory is used
+    dir: Option<PathBuf>,
+}
+
+/// Memory only, see [`ImageCache::open`]
+impl Default for ImageCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY, None)
+    }
+}
+
+impl ImageCache {
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Cache of the `api` host in `root`, e.g. the platform cache directory,
+    /// freezers of the same name on other hosts have their own images
+    pub fn open(root: &Path, api: &str) -> Self {
+        Self::new(
+            Self::DEFAULT_CAPACITY,
+            Some(root.join(path::escape(api)).join("images")),
+        )
+    }
+
+    pub fn new(capacity: usize, dir: Option<PathBuf>) -> Self {
+        let dir = dir.filter(|dir| match sync_fs::create_dir_all(dir) {
+            Ok(()) => true,
+            Err(error) => {
+                debug!("image cache works in memory only: `{error}`");
+                false
+            }
+        });
+        Self {
+            memory: Mutex::new(LruCache::new(
+                NonZeroUsize::new(capacity.max(1)).expect("capacity is at least one"),
+            )),
+            dir,
+        }
+    }
+
+    fn paths(&self, id: &str) -> Option<(PathBuf, PathBuf)> {
+        let stem = path::escape(id);
+        self.dir.as_ref().map(|dir| {
+            (
+                dir.join(format!("{stem}.bin")),
+                dir.join(format!("{stem}.etag")),
+            )
+        })
+    }
+
+    fn memory(&self) -> MutexGuard<'_, LruCache<String, Entry>> {
+        self.memory.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub async fn get(&self, id: &str) -> Option<Entry> {
+        let cached = self.memory().get(id).cloned();
+        if cached.is_some() {
+            return cached;
+        }
+
+        let (bytes, etag) = self.paths(id)?;
+        let entry = Entry {
+            etag: fs::read_to_string(etag).await.ok(),
+            bytes: fs::read(bytes).await.ok()?.into(),
+        };
+        self.memory().put(id.to_owned(), entry.clone());
+        Some(entry)
+    }
+
+    pub async fn insert(&self, id: &str, entry: Entry) {
+        if let Some((bytes, etag)) = self.paths(id) {
+            let res = match fs::write(bytes, &entry.bytes).await {
+                Ok(()) => match &entry.etag {
+                    Some(tag) => fs::write(&etag, tag).await,
+                    None => remove_file(&etag).await,
+                },
+                Err(error) => Err(error),
+            };
+            if let Err(error) = res {
+                debug!("image `{id}` is not stored on disk: `{error}`");
+            }
+        }
+        self.memory().put(id.to_owned(), entry);
+    }
+
+    pub async fn remove(&self, id: &str) {
+        self.memory().pop(id);
+        if let Some((bytes, etag)) = self.paths(id) {
+            let _ = remove_file(&bytes).await;
+            let _ = remove_file(&etag).await;
+        }
+    }
+
+    /// Keeps the image of the renamed freezer
+    pub async fn rename(&self, old: &str, new: &str) {
+        if let Some(entry) = self.get(old).await {
+            self.insert(new, entry).await;
+            self.remove(old).await;
+        }
+    }
+}
+
+/// Missing file is removed already
+async fn remove_file(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path).await {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,77 @@
+use super::{Entry, ImageCache};
+use crate::{tests::temp_dir, utils::path::escape};
+use std::fs;
+
+fn entry(bytes: &'static [u8], etag: Option<&str>) -> Entry {
+    Entry {
+        etag: etag.map(ToOwned::to_owned),
+        bytes: bytes.into(),
+    }
+}
+
+async fn bytes(cache: &ImageCache, id: &str) -> Option<Vec<u8>> {
+    cache.get(id).await.map(|entry| entry.bytes.to_vec())
+}
+
+#[tokio::test]
+async fn least_recent_is_evicted() {
+    let cache = ImageCache::new(2, None);
+    cache.insert("kitchen", entry(b"kitchen", None)).await;
+    cache.insert("garage", entry(b"garage", None)).await;
+
+    // the kitchen is used, so the garage is the least recent one
+    assert!(cache.get("kitchen").await.is_some());
+    cache.insert("office", entry(b"office", None)).await;
+
+    assert_eq!(bytes(&cache, "garage").await, None);
+    assert_eq!(bytes(&cache, "kitchen").await, Some(b"kitchen".to_vec()));
+    assert_eq!(bytes(&cache, "office").await, Some(b"office".to_vec()));
+}
+
+#[tokio::test]
+async fn evicted_is_read_from_disk() {
+    let cache = ImageCache::new(1, Some(temp_dir()));
+    cache
+        .insert("kitchen", entry(b"kitchen", Some("\"1\"")))
+        .await;
+    cache.insert("garage", entry(b"garage", None)).await;
+    assert!(!cache.memory().contains("kitchen"));
+
+    let kitchen = cache.get("kitchen").await.unwrap();
+    assert_eq!(kitchen.bytes, b"kitchen".as_slice());
+    assert_eq!(kitchen.etag.as_deref(), Some("\"1\""));
+    // and takes the place of the garage
+    assert!(cache.memory().contains("kitchen"));
+    assert!(!cache.memory().contains("garage"));
+}
+
+#[tokio::test]
+async fn ids_are_escaped() {
+    let dir = temp_dir();
+    let cache = ImageCache::new(1, Some(dir.clone()));
+    cache
+        .insert("../garage/1", entry(b"garage", Some("\"1\"")))
+        .await;
+
+    let stem = escape("../garage/1");
+    assert!(!stem.contains('/'));
+    assert_eq!(
+        fs::read(dir.join(format!("{stem}.bin"))).unwrap(),
+        b"garage"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join(format!("{stem}.etag"))).unwrap(),
+        "\"1\""
+    );
+    // nothing is written outside of the directory
+    assert!(!dir.join("garage").exists());
+    assert!(!dir.parent().unwrap().join("garage").exists());
+
+    // from the disk, not from the memory
+    cache.insert("kitchen", entry(b"kitchen", None)).await;
+    assert_eq!(bytes(&cache, "../garage/1").await, Some(b"garage".to_vec()));
+
+    cache.remove("../garage/1").await;
+    assert!(!dir.join(format!("{stem}.bin")).exists());
+    assert!(!dir.join(format!("{stem}.etag")).exists());
+}
@@ -7,23 +7,44 @@
 #![feature(box_syntax)]
 #![feature(let_chains)]
 
-mod model;
-// todo: bug in rust plugin - useless mod
-mod client;
-mod utils;
+mod cache;
+mod macros;
+mod task;
+mod theme;
 mod views;
 
+#[cfg(test)]
+mod tests;
+
 use crate::{
     alignment::Horizontal,
-    client::Client,
-    views::{list, preview, List, Log, Preview},
+    api::FreezerApi,
+    cache::ImageCache,
+    client::{Client, Outcome, Replayed},
+    model::Account,
+    stock::Thresholds,
+    store::Store,
+    theme::{Choice, Palette},
+    views::{
+        alerts, bulk, compare, dashboard, exchange, list, mover, preview, Alerts, Bulk, Compare,
+        Dashboard, Exchange, List, Log, Mover, Preview,
+    },
+};
+use freezer_client::{
+    api, client, history, inventory, model, owners, stock, store, transfer, utils,
+};
+use iced::{
+    alignment, executor, time, Application, Command, Element, Length, Renderer, Settings,
+    Subscription,
 };
-use iced::{alignment, executor, Application, Command, Element, Length, Renderer, Settings};
 use iced_aw::{Card, Modal};
-use iced_native::widget::helpers::{button, container, horizontal_rule, row, text, text_input};
-use std::{default::default, sync::Arc};
+use iced_native::widget::helpers::{
+    button, container, horizontal_rule, pick_list, row, text, text_input,
+};
+use std::{default::default, path::PathBuf, sync::Arc, time::Duration};
 use tap::Pipe;
-use utils::Result;
+use task::{Tagged, Task, Ticket, Written};
+use utils::{Result, error::anyio};
 
 pub fn main() -> iced::Result {
     App::run(Settings {
@@ -35,6 +56,7 @@ pub fn main() -> iced::Result {
         },
         default_font: Some(include_bytes!("../fonts/JetBrainsMono-Regular.ttf")),
         default_text_size: 17,
+        flags: Dirs::system(),
         ..default()
     })
 }
@@ -46,21 +68,101 @@ enum Message {
     ClearLog,
     ResetInit,
 
+    Theme(Choice),
+    OnSaveTheme(Tagged<Result<()>>),
+
     HostChanged(String),
     LoginChanged(String),
     OnLogin,
 
-    OnLoginResponse(Result<()>),
+    OnLoginResponse(Tagged<Result<Account>>),
+
+    /// Replay of the offline outbox
+    Sync,
+    OnSync(Tagged<Result<Vec<Replayed>>>),
+    OnUsers(Result<Vec<String>>),
 
     List(list::Message),
     Preview(preview::Message),
+    Exchange(exchange::Message),
+    Bulk(bulk::Message),
+    Mover(mover::Message),
+    Compare(compare::Message),
+    Dashboard(dashboard::Message),
+    Alerts(alerts::Message),
+}
+
+/// Side effect requested by the update, see [`App::perform`]
+#[derive(Debug)]
+enum Effect {
+    Login {
+        client: Arc<dyn FreezerApi>,
+        login: String,
+        ticket: Ticket,
+    },
+    Sync {
+        client: Arc<dyn FreezerApi>,
+        ticket: Ticket,
+    },
+    /// Candidates for the owners of the freezers
+    Users {
+        client: Arc<dyn FreezerApi>,
+    },
+    SaveTheme {
+        themes: theme::Config,
+        ticket: Ticket,
+    },
+    List(list::Effect),
+    Preview(preview::Effect),
+    Exchange(exchange::Effect),
+    Bulk(bulk::Effect),
+    Mover(mover::Effect),
+    Compare(compare::Effect),
+    Dashboard(dashboard::Effect),
+    Alerts(alerts::Effect),
 }
 
 #[derive(Debug)]
 enum State {
     Login,
-    WaitLogin { client: Arc<Client> },
-    Ready { list: List, preview: Preview },
+    WaitLogin { client: Arc<dyn FreezerApi> },
+    Ready {
+        client: Arc<dyn FreezerApi>,
+        /// Logged in user, shown in the title
+        account: Account,
+        list: List,
+        preview: Preview,
+        exchange: Exchange,
+        bulk: Bulk,
+        mover: Mover,
+        compare: Compare,
+        dashboard: Dashboard,
+        alerts: Alerts,
+    },
+}
+
+/// Where the app keeps its files, `None` if the platform has no such directory
+#[derive(Debug, Clone, Default)]
+struct Dirs {
+    /// Offline stores of the hosts, see [`Store::open_in`]
+    data: Option<PathBuf>,
+    /// Images of the hosts, see [`ImageCache::open`]
+    cache: Option<PathBuf>,
+    themes: Option<PathBuf>,
+    thresholds: Option<PathBuf>,
+}
+
+impl Dirs {
+    /// Directories of the platform, e.g. `~/.config/freezers-client`
+    fn system() -> Self {
+        let app = |dir: Option<PathBuf>| dir.map(|dir| dir.join("freezers-client"));
+        Self {
+            data: app(dirs::data_local_dir()),
+            cache: app(dirs::cache_dir()),
+            themes: theme::Config::path(),
+            thresholds: Thresholds::path(),
+        }
+    }
 }
 
 struct App {
@@ -69,6 +171,16 @@ struct App {
     host: String,
     login: String,
     log: Log,
+    login_task: Task,
+    sync_task: Task,
+    save_task: Task,
+
+    dirs: Dirs,
+    themes: theme::Config,
+    /// Of the saves of the themes, the newest one is kept
+    themes_written: Written,
+    /// Of the chosen theme, see [`theme::Config::palette`]
+    palette: Palette,
 }
 
 impl Application for App
@@ -78,25 +190,49 @@ where
     type Executor = executor::Default;
     type Message = Message;
     type Theme = iced::Theme;
-    type Flags = ();
-
-    fn new(_: Self::Flags) -> (Self, Command<Self::Message>) {
-        (
-            Self {
-                state: State::Login,
-                host: Client::DEFAULT_API.to_owned(),
-                login: String::new(),
-                log: default(),
-            },
-            Command::none(),
-        )
+    type Flags = Dirs;
+
+    fn new(dirs: Self::Flags) -> (Self, Command<Self::Message>) {
+        let mut log = Log::default();
+        let themes = match dirs.themes.as_deref().map(theme::Config::load) {
+            Some(Ok(themes)) => themes,
+            Some(Err(error)) => {
+                log.warn(format!("themes are ignored: {error}"));
+                theme::Config::default()
+            }
+            None => theme::Config::default(),
+        };
+        let mut app = Self {
+            state: State::Login,
+            host: Client::DEFAULT_API.to_owned(),
+            login: String::new(),
+            log,
+            login_task: default(),
+            sync_task: default(),
+            save_task: default(),
+            dirs,
+            themes,
+            themes_written: default(),
+            palette: Palette::default(),
+        };
+        app.choose_palette();
+        (app, Command::none())
     }
 
     fn title(&self) -> String {
-        match self.state {
-            State::Login { .. } => "Login",
-            State::WaitLogin { .. } => "Waiting for Login",
-            State::Ready { .. } => "Ready",
+        match &self.state {
+            State::Login { .. } => "Login".to_owned(),
+            State::WaitLogin { .. } => "Waiting for Login".to_owned(),
+            State::Ready {
+                client, account, ..
+            } => {
+                let status = match (client.is_offline(), client.pending()) {
+                    (false, 0) => "Ready".to_owned(),
+                    (false, pending) => format!("Ready - {pending} changes to sync"),
+                    (true, pending) => format!("Offline - {pending} changes to sync"),
+                };
+                format!("{status} - {}", Self::user(account))
+            }
         }
         .pipe(|str| format!("Freezers Client - {}", str))
     }
@@ -104,138 +240,554 @@ where
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
         println!("{:?}", message);
 
+        let effects = self.reduce(message);
+        Command::batch(effects.into_iter().map(|effect| self.perform(effect)))
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        match &self.state {
+            State::Ready {
+                client,
+                preview,
+                alerts,
+                ..
+            } => {
+                // poll connectivity while something waits for it
+                let sync = if client.is_offline() || client.pending() > 0 {
+                    time::every(Duration::from_secs(10)).map(|_| Message::Sync)
+                } else {
+                    Subscription::none()
+                };
+                Subscription::batch([
+                    sync,
+                    List::subscription().map(Message::List),
+                    preview.subscription().map(Message::Preview),
+                    alerts.subscription().map(Message::Alerts),
+                ])
+            }
+            _ => Subscription::none(),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Self::Message, Renderer<Self::Theme>> {
+        let Self { host, login, .. } = self;
+
+        let view = match &self.state {
+            State::Login | State::WaitLogin { .. } => Self::login(host, login),
+            State::Ready {
+                list,
+                preview,
+                compare,
+                alerts,
+                ..
+            } => Self::ready(list, preview, compare, alerts),
+        };
+
+        let content = columee![
+            container(view).height(Length::FillPortion(4)),
+            horizontal_rule(10),
+            container(self.log()).height(Length::Fill)
+        ];
+
+        // at most one dialog over the panes
+        let dialog = || match &self.state {
+            State::Ready {
+                preview,
+                exchange,
+                bulk,
+                mover,
+                dashboard,
+                alerts,
+                ..
+            } => preview
+                .viewer()
+                .map(|viewer| viewer.map(Message::Preview))
+                .or_else(|| exchange.view().map(|view| view.map(Message::Exchange)))
+                .or_else(|| bulk.view().map(|view| view.map(Message::Bulk)))
+                .or_else(|| mover.view().map(|view| view.map(Message::Mover)))
+                .or_else(|| dashboard.view().map(|view| view.map(Message::Dashboard)))
+                .or_else(|| alerts.view().map(|view| view.map(Message::Alerts))),
+            _ => None,
+        };
+
+        Modal::new(
+            matches!(self.state, State::WaitLogin { .. }) || dialog().is_some(),
+            content,
+            move || {
+                if let Some(dialog) = dialog() {
+                    return dialog;
+                }
+                Card::new(
+                    text("Wait please..."),
+                    button(text("Cancel").horizontal_alignment(Horizontal::Center))
+                        .width(Length::Fill)
+                        .on_press(Message::ResetInit),
+                )
+                .max_width(400)
+                //.width(Length::Shrink)
+                .into()
+            },
+        )
+        .into()
+    }
+
+    fn theme(&self) -> Self::Theme {
+        self.palette.theme()
+    }
+}
+
+impl App {
+    fn connect(&mut self) -> Arc<dyn FreezerApi> {
+        let client = Client::new(&self.host, reqwest::Client::new());
+        let store = match &self.dirs.data {
+            Some(root) => Store::open_in(root, &self.host),
+            None => Err(anyio!("no local data directory")),
+        };
+        let client = match store {
+            Ok(store) => client.with_store(store),
+            Err(error) => {
+                self.log.warn(format!("offline mode is disabled: {error}"));
+                client
+            }
+        };
+        Arc::new(client)
+    }
+
+    /// Palette of the chosen theme for the log and the views, the replaced colours are warned
+    fn choose_palette(&mut self) {
+        let (palette, warnings) = self.themes.palette(theme::system());
+        for warning in warnings {
+            self.log.warn(warning);
+        }
+        self.palette = palette;
+        self.state.set_palette(palette);
+    }
+
+    /// Applies the message to the state, side effects are returned to be performed later
+    fn reduce(&mut self, message: Message) -> Vec<Effect> {
         if let Message::ClearLog = &message {
             self.log.clear();
         }
 
+        let message = match message {
+            // themes are chosen in any state
+            Message::Theme(choice) => {
+                self.themes.theme = choice;
+                self.choose_palette();
+                return vec![Effect::SaveTheme {
+                    themes: self.themes.clone(),
+                    ticket: self.save_task.begin(),
+                }];
+            }
+            Message::OnSaveTheme(res) => {
+                if let Some(Err(error)) = self.save_task.accept(res) {
+                    self.log.warn(format!("theme is not saved: {error}"));
+                }
+                return vec![];
+            }
+            message => message,
+        };
+
         match &mut self.state {
             State::Login => match message {
                 Message::HostChanged(new) => {
                     self.host = new;
-                    Command::none()
+                    vec![]
                 }
                 Message::LoginChanged(new) => {
                     self.login = new;
-                    Command::none()
+                    vec![]
                 }
                 Message::OnLogin => {
                     if self.login.is_empty() {
                         self.log.error("Login cannot be empty");
-                        Command::none()
+                        vec![]
                     } else {
-                        let Self { host, login, .. } = self;
-                        let login = login.clone();
-                        let client = Arc::new(Client::new(host, reqwest::Client::new()));
+                        let client = self.connect();
                         self.state = State::WaitLogin {
                             client: client.clone(),
                         };
-                        Command::perform(
-                            async move { client.login(&login).await },
-                            Message::OnLoginResponse,
-                        )
+                        vec![Effect::Login {
+                            client,
+                            login: self.login.clone(),
+                            ticket: self.login_task.begin(),
+                        }]
                     }
                 }
-                _ => Command::none(),
+                _ => vec![],
             },
-            State::WaitLogin { client, .. } => match &message {
+            State::WaitLogin { client, .. } => match message {
                 Message::ResetInit => {
+                    self.login_task.cancel();
                     self.state = State::Login;
-                    Command::none()
+                    vec![]
                 }
-                Message::OnLoginResponse(res) => match res {
-                    Ok(_) => {
-                        let (list, preview, command) = Self::on_login(client);
-                        self.state = State::Ready { list, preview };
-                        command
+                Message::OnLoginResponse(res) => match self.login_task.accept(res) {
+                    // stale or aborted login
+                    None => vec![],
+                    Some(Ok(account)) => {
+                        if client.is_offline() {
+                            self.log
+                                .warn("server is unreachable - browsing the local copy");
+                        }
+                        let path = self.dirs.thresholds.clone();
+                        let thresholds = match path.as_deref().map(Thresholds::load) {
+                            Some(Ok(thresholds)) => thresholds,
+                            Some(Err(error)) => {
+                                self.log.warn(format!("thresholds are ignored: {error}"));
+                                Thresholds::default()
+                            }
+                            None => Thresholds::default(),
+                        };
+
+                        let (list, mut preview, effect) = Self::on_login(client, &account);
+                        preview.set_thresholds(thresholds.clone());
+                        let images = self
+                            .dirs
+                            .cache
+                            .as_deref()
+                            .map_or_else(ImageCache::default, |root| {
+                                ImageCache::open(root, &self.host)
+                            });
+                        preview.set_images(Arc::new(images));
+                        let mut mover = Mover::new(client.clone());
+                        mover.set_account(account.clone());
+                        let mut exchange = Exchange::new(client.clone());
+                        exchange.set_account(account.clone());
+                        let users = Effect::Users {
+                            client: client.clone(),
+                        };
+                        self.state = State::Ready {
+                            client: client.clone(),
+                            account,
+                            list,
+                            preview,
+                            exchange,
+                            bulk: Bulk::new(client.clone()),
+                            mover,
+                            compare: Compare::new(client.clone()),
+                            dashboard: Dashboard::new(client.clone()),
+                            alerts: Alerts::new(client.clone(), path, thresholds),
+                        };
+                        self.state.set_palette(self.palette);
+                        vec![effect, users]
                     }
-                    Err(error) => {
+                    Some(Err(error)) => {
                         self.state = State::Login;
                         self.log.error(error);
-                        Command::none()
+                        vec![]
                     }
                 },
-                _ => Command::none(),
+                _ => vec![],
             },
-            State::Ready { list, preview } => match message {
+            State::Ready {
+                client,
+                account,
+                list,
+                preview,
+                exchange,
+                bulk,
+                mover,
+                compare,
+                dashboard,
+                alerts,
+            } => match message {
+                Message::Sync => {
+                    if self.sync_task.is_running() {
+                        vec![]
+                    } else {
+                        vec![Effect::Sync {
+                            client: client.clone(),
+                            ticket: self.sync_task.begin(),
+                        }]
+                    }
+                }
+                Message::OnSync(res) => {
+                    match self.sync_task.accept(res) {
+                        Some(Ok(report)) => self.report_sync(report),
+                        Some(Err(error)) => self.log.error(error),
+                        None => {}
+                    }
+                    vec![]
+                }
+                Message::OnUsers(res) => {
+                    match res {
+                        Ok(users) => {
+                            preview.add_owners(users);
+                            list.set_owners(preview.owners().to_vec());
+                        }
+                        Err(error) => self
+                            .log
+                            .warn(format!("owners are limited to the seen ones: {error}")),
+                    }
+                    vec![]
+                }
                 Message::List(message) => {
-                    let mut commands = Vec::new();
+                    let mut effects = Vec::new();
 
                     if let list::Message::Ping(list) = &message {
-                        commands.push(
+                        // the preview is shown instead of the comparison
+                        compare.reduce(compare::Message::Close);
+                        effects.extend(
                             preview
-                                .update(preview::Message::FetchRequest(list.clone()))
-                                .map(Message::Preview),
+                                .reduce(preview::Message::FetchRequest(list.clone()))
+                                .into_iter()
+                                .map(Effect::Preview),
                         );
                     }
 
-                    if let list::Message::Error(error) = &message {
-                        self.log.error(error);
+                    match &message {
+                        list::Message::Error(error) => self.log.error(error),
+                        list::Message::Info(info) => self.log.info(info),
+                        list::Message::Bulk { ids, action } => effects.extend(
+                            bulk.reduce(bulk::Message::Open {
+                                ids: ids.clone(),
+                                action: action.clone(),
+                            })
+                            .into_iter()
+                            .map(Effect::Bulk),
+                        ),
+                        _ => {}
                     }
 
-                    commands.push(list.update(message).map(Message::List));
-
-                    Command::batch(commands)
+                    effects.extend(list.reduce(message).into_iter().map(Effect::List));
+                    effects
+                }
+                Message::Preview(preview::Message::ChangeMinimum {
+                    freezer,
+                    product,
+                    minimum,
+                }) => {
+                    let effects = alerts.reduce(alerts::Message::Threshold {
+                        freezer: Some(freezer),
+                        product,
+                        minimum,
+                    });
+                    preview.set_thresholds(alerts.thresholds().clone());
+                    effects.into_iter().map(Effect::Alerts).collect()
                 }
                 Message::Preview(message) => {
-                    if let preview::Message::Error(error) = &message {
+                    let mut effects = Vec::new();
+                    match &message {
+                        preview::Message::Error(error) => self.log.error(error),
+                        preview::Message::Warn(error) => self.log.warn(error),
+                        preview::Message::Renamed { old, new } => effects.extend(
+                            list.reduce(list::Message::Renamed {
+                                old: old.clone(),
+                                new: new.clone(),
+                            })
+                            .into_iter()
+                            .map(Effect::List),
+                        ),
+                        _ => {}
+                    }
+                    let fetched = matches!(message, preview::Message::FetchInfo(_));
+                    effects.extend(preview.reduce(message).into_iter().map(Effect::Preview));
+                    // owners of the fetched freezers are candidates too
+                    if fetched {
+                        list.set_owners(preview.owners().to_vec());
+                    }
+                    effects
+                }
+                Message::Exchange(message) => {
+                    let mut effects = Vec::new();
+                    match &message {
+                        exchange::Message::Error(error) => self.log.error(error),
+                        exchange::Message::Warn(error) => self.log.warn(error),
+                        exchange::Message::Info(info) => self.log.info(info),
+                        exchange::Message::Imported => {
+                            let (new, effect) = List::new(client.clone(), account.clone());
+                            *list = new;
+                            effects.push(Effect::List(effect));
+                        }
+                        _ => {}
+                    }
+                    effects.extend(exchange.reduce(message).into_iter().map(Effect::Exchange));
+                    effects
+                }
+                Message::Bulk(message) => {
+                    let mut effects = Vec::new();
+                    match &message {
+                        bulk::Message::Error(error) => self.log.error(error),
+                        bulk::Message::Warn(error) => self.log.warn(error),
+                        bulk::Message::Info(info) => self.log.info(info),
+                        bulk::Message::Changed => {
+                            effects.extend(
+                                list.reduce(list::Message::Reload)
+                                    .into_iter()
+                                    .map(Effect::List),
+                            );
+                            effects.extend(
+                                compare
+                                    .reduce(compare::Message::Refresh)
+                                    .into_iter()
+                                    .map(Effect::Compare),
+                            );
+                        }
+                        _ => {}
+                    }
+                    effects.extend(bulk.reduce(message).into_iter().map(Effect::Bulk));
+                    effects
+                }
+                Message::Mover(message) => {
+                    let mut effects = Vec::new();
+                    match &message {
+                        mover::Message::Open(_) => mover.set_freezers(list.freezers().to_vec()),
+                        mover::Message::Error(error) => self.log.error(error),
+                        mover::Message::Warn(error) => self.log.warn(error),
+                        mover::Message::Info(info) => self.log.info(info),
+                        // amounts of the open freezers are outdated
+                        mover::Message::Moved { from, to } => {
+                            effects.extend(
+                                compare
+                                    .reduce(compare::Message::Refresh)
+                                    .into_iter()
+                                    .map(Effect::Compare),
+                            );
+                            for id in [from, to] {
+                                effects.extend(
+                                    preview
+                                        .reduce(preview::Message::Refresh(id.clone()))
+                                        .into_iter()
+                                        .map(Effect::Preview),
+                                );
+                            }
+                        }
+                        _ => {}
+                    }
+                    effects.extend(mover.reduce(message).into_iter().map(Effect::Mover));
+                    effects
+                }
+                Message::Compare(message) => {
+                    match &message {
+                        compare::Message::Open { .. } => {
+                            compare.set_freezers(list.freezers().to_vec());
+                        }
+                        compare::Message::Error(error) => self.log.error(error),
+                        _ => {}
+                    }
+                    compare
+                        .reduce(message)
+                        .into_iter()
+                        .map(Effect::Compare)
+                        .collect()
+                }
+                Message::Dashboard(message) => {
+                    if let dashboard::Message::Error(error) = &message {
                         self.log.error(error);
                     }
-                    if let preview::Message::Warn(error) = &message {
-                        self.log.warn(error);
+                    dashboard
+                        .reduce(message)
+                        .map(Effect::Dashboard)
+                        .into_iter()
+                        .collect()
+                }
+                Message::Alerts(message) => {
+                    let mut effects = Vec::new();
+                    match &message {
+                        alerts::Message::Error(error) => self.log.error(error),
+                        alerts::Message::Warn(error) => self.log.warn(error),
+                        alerts::Message::Show(freezer) => effects.extend(
+                            preview
+                                .reduce(preview::Message::FetchRequest(freezer.clone()))
+                                .into_iter()
+                                .map(Effect::Preview),
+                        ),
+                        _ => {}
+                    }
+                    let changed = matches!(
+                        message,
+                        alerts::Message::SetMinimum | alerts::Message::Threshold { .. }
+                    );
+                    effects.extend(alerts.reduce(message).into_iter().map(Effect::Alerts));
+                    if changed {
+                        preview.set_thresholds(alerts.thresholds().clone());
                     }
-                    preview.update(message).map(Message::Preview)
+                    effects
                 }
-                _ => Command::none(),
+                _ => vec![],
             },
         }
     }
 
-    // fn subscription(&self) -> Subscription<Self::Message> {
-    //     time::every(Duration::from_millis(50)).map(|_| Message::Silent)
-    // }
-
-    fn view(&self) -> Element<'_, Self::Message, Renderer<Self::Theme>> {
-        let Self { host, login, .. } = self;
-
-        let view = match &self.state {
-            State::Login | State::WaitLogin { .. } => Self::login(host, login),
-            State::Ready { list, preview } => Self::ready(list, preview),
-        };
-
-        let content = columee![
-            container(view).height(Length::FillPortion(4)),
-            horizontal_rule(10),
-            container(self.log()).height(Length::Fill)
-        ];
-
-        Modal::new(
-            matches!(self.state, State::WaitLogin { .. }),
-            content,
-            || {
-                Card::new(
-                    text("Wait please..."),
-                    button(text("Cancel").horizontal_alignment(Horizontal::Center))
-                        .width(Length::Fill)
-                        .on_press(Message::ResetInit),
-                )
-                .max_width(400)
-                //.width(Length::Shrink)
-                .into()
+    fn perform(&self, effect: Effect) -> Command<Message> {
+        match (effect, &self.state) {
+            (
+                Effect::Login {
+                    client,
+                    login,
+                    ticket,
+                },
+                _,
+            ) => ticket.perform(
+                async move { client.login(&login).await },
+                Message::OnLoginResponse,
+            ),
+            (Effect::Sync { client, ticket }, _) => {
+                ticket.perform(async move { client.replay().await }, Message::OnSync)
+            }
+            (Effect::Users { client }, _) => {
+                Command::perform(async move { client.users().await }, Message::OnUsers)
+            }
+            (Effect::SaveTheme { themes, ticket }, _) => match self.dirs.themes.clone() {
+                Some(path) => ticket.write(
+                    self.themes_written.clone(),
+                    move || themes.save(&path),
+                    Message::OnSaveTheme,
+                ),
+                None => Command::none(),
             },
-        )
-        .into()
-    }
-
-    fn theme(&self) -> Self::Theme {
-        Self::Theme::Dark
+            (Effect::List(effect), State::Ready { list, .. }) => {
+                list.perform(effect).map(Message::List)
+            }
+            (Effect::Preview(effect), State::Ready { preview, .. }) => {
+                preview.perform(effect).map(Message::Preview)
+            }
+            (Effect::Exchange(effect), State::Ready { exchange, .. }) => {
+                exchange.perform(effect).map(Message::Exchange)
+            }
+            (Effect::Bulk(effect), State::Ready { bulk, .. }) => {
+                bulk.perform(effect).map(Message::Bulk)
+            }
+            (Effect::Mover(effect), State::Ready { mover, .. }) => {
+                mover.perform(effect).map(Message::Mover)
+            }
+            (Effect::Compare(effect), State::Ready { compare, .. }) => {
+                compare.perform(effect).map(Message::Compare)
+            }
+            (Effect::Dashboard(effect), State::Ready { dashboard, .. }) => {
+                dashboard.perform(effect).map(Message::Dashboard)
+            }
+            (Effect::Alerts(effect), State::Ready { alerts, .. }) => {
+                alerts.perform(effect).map(Message::Alerts)
+            }
+            // views are gone
+            (
+                Effect::List(_)
+                | Effect::Preview(_)
+                | Effect::Exchange(_)
+                | Effect::Bulk(_)
+                | Effect::Mover(_)
+                | Effect::Compare(_)
+                | Effect::Dashboard(_)
+                | Effect::Alerts(_),
+                _,
+            ) => Command::none(),
+        }
     }
-}
 
-impl App {
     fn log(&self) -> Element<'_, Message, Renderer<iced::Theme>> {
         columee![
-            button("clear").on_press(Message::ClearLog),
-            self.log.view().map(|_| Message::Silent)
+            row![
+                button("clear").on_press(Message::ClearLog),
+                pick_list(
+                    self.themes.choices(),
+                    Some(self.themes.theme.clone()),
+                    Message::Theme
+                ),
+            ]
+            .spacing(5),
+            self.log.view(&self.palette).map(|_| Message::Silent)
         ]
         .into()
     }
@@ -252,22 +804,102 @@ impl App {
     fn ready<'a>(
         list: &'a List,
         preview: &'a Preview,
+        compare: &'a Compare,
+        alerts: &'a Alerts,
     ) -> Element<'a, Message, Renderer<iced::Theme>> {
+        let count = alerts.count();
+        // two selected freezers, or the shown one and the choice
+        let mut selected = list.selected().into_iter();
+        let (left, right) = match (selected.next(), selected.next()) {
+            (Some(left), right @ Some(_)) => (Some(left), right),
+            _ => (preview.shown().map(ToOwned::to_owned), None),
+        };
         row![
-            container(list.view().map(Message::List)).width(Length::Units(400)),
-            container(preview.view().map(Message::Preview))
+            columee![
+                row![
+                    button("dashboard").on_press(Message::Dashboard(dashboard::Message::Open)),
+                    button(text(if count == 0 {
+                        "alerts".to_owned()
+                    } else {
+                        format!("alerts ({count})")
+                    }))
+                    .on_press(Message::Alerts(alerts::Message::Open)),
+                    button("import / export").on_press(Message::Exchange(exchange::Message::Open)),
+                    button("move products").on_press(Message::Mover(mover::Message::Open(
+                        preview.shown().map(ToOwned::to_owned)
+                    ))),
+                    button("compare")
+                        .on_press(Message::Compare(compare::Message::Open { left, right })),
+                ]
+                .spacing(5),
+                list.view().map(Message::List)
+            ]
+            .width(Length::Units(400)),
+            if compare.is_open() {
+                container(compare.view().map(Message::Compare))
+            } else {
+                container(preview.view().map(Message::Preview))
+            }
         ]
         .into()
     }
 
-    fn on_login(client: &Arc<Client>) -> (List, Preview, Command<Message>) {
-        let (list, command1) = List::new(client.clone());
-        let (preview, command2) = Preview::new(client.clone());
-        (
-            list,
+    fn report_sync(&mut self, report: Vec<Replayed>) {
+        for Replayed { mutation, outcome } in report {
+            let id = mutation.id();
+            match outcome {
+                Outcome::Applied => self.log.info(format!("synced `{id}`")),
+                Outcome::Conflict { server: Some(_) } => self.log.warn(format!(
+                    "`{id}` was changed on the server while offline - local changes are dropped"
+                )),
+                Outcome::Conflict { server: None } => self.log.warn(format!(
+                    "`{id}` was deleted on the server while offline - local changes are dropped"
+                )),
+                Outcome::Rejected => self.log.warn(format!(
+                    "unauthorized access - offline changes of `{id}` are rejected"
+                )),
+                Outcome::Failed(error) => self.log.error(format!("`{id}` is not synced: {error}")),
+            }
+        }
+    }
+
+    fn on_login(client: &Arc<dyn FreezerApi>, account: &Account) -> (List, Preview, Effect) {
+        let (list, effect) = List::new(client.clone(), account.clone());
+        let mut preview = Preview::new(client.clone());
+        preview.set_account(account.clone());
+        (list, preview, Effect::List(effect))
+    }
+
+    /// Login with the roles, e.g. `admin (admin)` or `guest (viewer, read-only)`
+    fn user(account: &Account) -> String {
+        let mut roles = account.roles.clone();
+        if account.is_read_only() {
+            roles.push("read-only".to_owned());
+        }
+        if roles.is_empty() {
+            account.login.clone()
+        } else {
+            format!("{} ({})", account.login, roles.join(", "))
+        }
+    }
+}
+
+impl State {
+    /// Views drawn with the palette, see [`App::choose_palette`]
+    fn set_palette(&mut self, palette: Palette) {
+        if let Self::Ready {
             preview,
-            Command::batch([command1.map(Message::List), command2.map(Message::Preview)]),
-        )
+            compare,
+            dashboard,
+            alerts,
+            ..
+        } = self
+        {
+            preview.set_palette(palette);
+            compare.set_palette(palette);
+            dashboard.set_palette(palette);
+            alerts.set_palette(palette);
+        }
     }
 }
 
@@ -0,0 +1,174 @@
+use crate::utils::{Error, Result};
+use futures::future::{AbortHandle, AbortRegistration, Abortable};
+use iced_native::Command;
+use std::{
+    future::Future,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Identifies the request spawned by the [`Task`],
+/// every new request (or cancel) makes previous generation stale.
+/// Unique among all tasks, so the response also tells the task it belongs to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Generation(u64);
+
+impl Generation {
+    fn next() -> Self {
+        static LAST: AtomicU64 = AtomicU64::new(0);
+        Self(LAST.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
+/// Generation of the content of a file written by [`Ticket::write`],
+/// shared by all the writes of that file
+pub type Written = Arc<Mutex<Generation>>;
+
+/// Runs `write` unless a newer generation is written already
+fn write_newest(
+    written: &Mutex<Generation>,
+    generation: Generation,
+    write: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let mut written = written.lock().unwrap_or_else(PoisonError::into_inner);
+    if *written > generation {
+        return Ok(());
+    }
+    write()?;
+    *written = generation;
+    Ok(())
+}
+
+/// Response of the request marked with its [`Generation`]
+#[derive(Debug, Clone)]
+pub struct Tagged<T> {
+    generation: Generation,
+    /// `None` if request was aborted
+    value: Option<T>,
+}
+
+/// Permission to run the request issued by [`Task::begin`],
+/// the request is aborted by the task if it's superseded or cancelled
+#[derive(Debug)]
+pub struct Ticket {
+    generation: Generation,
+    registration: AbortRegistration,
+}
+
+impl Ticket {
+    pub fn perform<T, M>(
+        self,
+        future: impl Future<Output = T> + Send + 'static,
+        f: impl Fn(Tagged<T>) -> M + Send + 'static,
+    ) -> Command<M>
+    where
+        T: Send + 'static,
+    {
+        let Self {
+            generation,
+            registration,
+        } = self;
+        Command::perform(Abortable::new(future, registration), move |value| {
+            f(Tagged {
+                generation,
+                value: value.ok(),
+            })
+        })
+    }
+
+    /// Blocking `write` of a file, skipped if a newer ticket has written the file already:
+    /// the threads may run the writes in any order, the content of the newest one is kept
+    pub fn write<M>(
+        self,
+        written: Written,
+        write: impl FnOnce() -> Result<()> + Send + 'static,
+        f: impl Fn(Tagged<Result<()>>) -> M + Send + 'static,
+    ) -> Command<M> {
+        let generation = self.generation;
+        self.perform(
+            async move {
+                tokio::task::spawn_blocking(move || write_newest(&written, generation, write))
+                    .await
+                    .map_err(Error::from)
+                    .and_then(|res| res)
+            },
+            f,
+        )
+    }
+
+    /// Response as if the request has completed with `value`
+    #[cfg(test)]
+    pub const fn resolve<T>(&self, value: T) -> Tagged<T> {
+        Tagged {
+            generation: self.generation,
+            value: Some(value),
+        }
+    }
+}
+
+/// Handle of at most one in-flight request
+#[derive(Debug, Default)]
+pub struct Task {
+    generation: Generation,
+    abort: Option<AbortHandle>,
+}
+
+impl Task {
+    /// Supersedes the in-flight request (if any) by the new one
+    pub fn begin(&mut self) -> Ticket {
+        self.cancel();
+
+        let (abort, registration) = AbortHandle::new_pair();
+        self.abort = Some(abort);
+        Ticket {
+            generation: self.generation,
+            registration,
+        }
+    }
+
+    /// Spawns `future` and supersedes the in-flight request (if any)
+    pub fn perform<T, M>(
+        &mut self,
+        future: impl Future<Output = T> + Send + 'static,
+        f: impl Fn(Tagged<T>) -> M + Send + 'static,
+    ) -> Command<M>
+    where
+        T: Send + 'static,
+    {
+        self.begin().perform(future, f)
+    }
+
+    /// Aborts the in-flight request: its future is dropped on the next poll,
+    /// so the underlying HTTP request is closed too
+    pub fn cancel(&mut self) {
+        if let Some(abort) = self.abort.take() {
+            abort.abort();
+        }
+        self.generation = Generation::next();
+    }
+
+    pub const fn is_running(&self) -> bool {
+        self.abort.is_some()
+    }
+
+    /// The response belongs to the current request of this task
+    pub fn issued<T>(&self, tagged: &Tagged<T>) -> bool {
+        tagged.generation == self.generation && self.abort.is_some()
+    }
+
+    /// Unwraps the response if it belongs to the current request,
+    /// stale and aborted responses are discarded
+    pub fn accept<T>(&mut self, tagged: Tagged<T>) -> Option<T> {
+        if self.issued(&tagged) {
+            self.abort = None;
+            tagged.value
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use super::{Generation, Task, write_newest};
+use std::sync::Mutex;
+
+#[test]
+fn newest_write_is_kept() {
+    let mut task = Task::default();
+    let older = task.begin().generation;
+    let newer = task.begin().generation;
+    assert!(older < newer);
+
+    let written = Mutex::new(Generation::default());
+    let mut content = "";
+
+    // the newer one runs first, the late older one is skipped
+    write_newest(&written, newer, || {
+        content = "newer";
+        Ok(())
+    })
+    .unwrap();
+    write_newest(&written, older, || {
+        content = "older";
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(content, "newer");
+}
+
+#[test]
+fn writes_in_turn() {
+    let mut task = Task::default();
+    let older = task.begin().generation;
+    let newer = task.begin().generation;
+
+    let written = Mutex::new(Generation::default());
+    let mut writes = Vec::new();
+    for generation in [older, newer] {
+        write_newest(&written, generation, || {
+            writes.push(generation);
+            Ok(())
+        })
+        .unwrap();
+    }
+    assert_eq!(writes, [older, newer]);
+}
@@ -0,0 +1,245 @@
+use super::{
+    Account, App, Choice, Dirs, Effect, Message, Palette, State, alerts, compare, list, preview,
+};
+use crate::utils::error::anyio;
+use iced::Application;
+use std::{
+    env, fs,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Fresh directory per test - they run concurrently
+pub fn temp_dir() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = env::temp_dir().join(format!(
+        "freezers-app-test-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+/// App with its files in a fresh directory
+fn app() -> App {
+    let root = temp_dir();
+    let (mut app, _) = App::new(Dirs {
+        data: Some(root.join("data")),
+        cache: Some(root.join("cache")),
+        themes: Some(root.join("themes.json")),
+        thresholds: Some(root.join("thresholds.json")),
+    });
+    app.host = "http://test".to_owned();
+    app
+}
+
+/// Starts the login, its response is returned to be fed back
+fn login(app: &mut App) -> Effect {
+    app.reduce(Message::LoginChanged("admin".into()));
+    let mut effects = app.reduce(Message::OnLogin);
+    assert_eq!(effects.len(), 1);
+    effects.remove(0)
+}
+
+#[test]
+fn empty_login() {
+    let mut app = app();
+
+    assert!(app.reduce(Message::OnLogin).is_empty());
+    assert!(matches!(app.state, State::Login));
+    assert!(
+        app.log
+            .messages()
+            .any(|message| message.contains("cannot be empty"))
+    );
+}
+
+#[test]
+fn successful_login() {
+    let mut app = app();
+
+    let Effect::Login {
+        login: name,
+        ticket,
+        ..
+    } = login(&mut app)
+    else {
+        panic!("login is requested");
+    };
+    assert_eq!(name, "admin");
+    assert!(matches!(app.state, State::WaitLogin { .. }));
+    // the offline store is in the directory of the test
+    assert!(app.dirs.data.as_ref().unwrap().exists());
+
+    let account = Account {
+        roles: vec!["viewer".into()],
+        permissions: Some([].into()),
+        ..Account::unknown("admin")
+    };
+    let effects = app.reduce(Message::OnLoginResponse(ticket.resolve(Ok(account))));
+    assert!(matches!(app.state, State::Ready { .. }));
+    assert!(app.title().ends_with(" - admin (viewer, read-only)"));
+    assert!(matches!(
+        &effects[..],
+        [
+            Effect::List(list::Effect::FetchPage { offset: 0, .. }),
+            Effect::Users { .. }
+        ]
+    ));
+}
+
+#[test]
+fn reset_login() {
+    let mut app = app();
+
+    let Effect::Login { ticket, .. } = login(&mut app) else {
+        panic!("login is requested");
+    };
+    app.reduce(Message::ResetInit);
+    assert!(matches!(app.state, State::Login));
+
+    // late response of the cancelled login
+    assert!(
+        app.reduce(Message::OnLoginResponse(
+            ticket.resolve(Ok(Account::unknown("admin")))
+        ))
+        .is_empty()
+    );
+    assert!(matches!(app.state, State::Login));
+}
+
+#[test]
+fn ping_fetches_preview() {
+    let mut app = app();
+
+    let Effect::Login { ticket, .. } = login(&mut app) else {
+        panic!("login is requested");
+    };
+    app.reduce(Message::OnLoginResponse(
+        ticket.resolve(Ok(Account::unknown("admin"))),
+    ));
+
+    let effects = app.reduce(Message::List(list::Message::Ping("kitchen".into())));
+    assert_eq!(effects.len(), 2);
+    assert!(
+        effects
+            .iter()
+            .all(|effect| matches!(effect, Effect::Preview(_)))
+    );
+}
+
+#[test]
+fn ping_leaves_comparison() {
+    let mut app = app();
+
+    let Effect::Login { ticket, .. } = login(&mut app) else {
+        panic!("login is requested");
+    };
+    app.reduce(Message::OnLoginResponse(
+        ticket.resolve(Ok(Account::unknown("admin"))),
+    ));
+
+    let effects = app.reduce(Message::Compare(compare::Message::Open {
+        left: Some("kitchen".into()),
+        right: Some("garage".into()),
+    }));
+    assert_eq!(effects.len(), 2);
+    app.reduce(Message::List(list::Message::Ping("kitchen".into())));
+    let State::Ready { compare, .. } = &app.state else {
+        panic!("app is ready");
+    };
+    assert!(!compare.is_open());
+}
+
+#[test]
+fn minimum_of_preview_goes_to_alerts() {
+    let mut app = app();
+
+    let Effect::Login { ticket, .. } = login(&mut app) else {
+        panic!("login is requested");
+    };
+    app.reduce(Message::OnLoginResponse(
+        ticket.resolve(Ok(Account::unknown("admin"))),
+    ));
+
+    let mut change = |minimum| {
+        let effects = app.reduce(Message::Preview(preview::Message::ChangeMinimum {
+            freezer: "kitchen".into(),
+            product: "pelmeni".into(),
+            minimum,
+        }));
+        assert!(matches!(
+            &effects[..],
+            [Effect::Alerts(alerts::Effect::Settle(_))]
+        ));
+        let State::Ready { alerts, .. } = &app.state else {
+            panic!("app is ready");
+        };
+        alerts.thresholds().minimum("kitchen", "pelmeni")
+    };
+    assert_eq!(change(Some(4)), Some(4));
+    // the override is removed, not kept as zero
+    assert_eq!(change(None), None);
+}
+
+#[test]
+fn users_are_owner_candidates() {
+    let mut app = app();
+
+    let Effect::Login { ticket, .. } = login(&mut app) else {
+        panic!("login is requested");
+    };
+    app.reduce(Message::OnLoginResponse(
+        ticket.resolve(Ok(Account::unknown("admin"))),
+    ));
+
+    app.reduce(Message::OnUsers(Ok(vec!["guest".into(), "admin".into()])));
+    let State::Ready { preview, .. } = &app.state else {
+        panic!("app is ready");
+    };
+    assert_eq!(preview.owners(), ["admin", "guest"]);
+}
+
+#[test]
+fn theme_is_chosen_before_login() {
+    let mut app = app();
+
+    let effects = app.reduce(Message::Theme(Choice::Light));
+    assert!(matches!(
+        &effects[..],
+        [Effect::SaveTheme { themes, .. }] if themes.theme == Choice::Light
+    ));
+    assert_eq!(app.palette, Palette::LIGHT);
+    assert!(matches!(app.state, State::Login));
+
+    // the failure of a superseded save is dropped
+    let [Effect::SaveTheme { ticket: older, .. }] = &effects[..] else {
+        unreachable!()
+    };
+    let effects = app.reduce(Message::Theme(Choice::Dark));
+    let failed = || Err(anyio!("disk is full"));
+    app.reduce(Message::OnSaveTheme(older.resolve(failed())));
+    assert!(
+        !app.log
+            .messages()
+            .any(|message| message.contains("not saved"))
+    );
+    let [Effect::SaveTheme { ticket: newer, .. }] = &effects[..] else {
+        panic!("theme is saved");
+    };
+    app.reduce(Message::OnSaveTheme(newer.resolve(failed())));
+    assert!(
+        app.log
+            .messages()
+            .any(|message| message.contains("theme is not saved"))
+    );
+
+    // the palette is gone from the config
+    app.reduce(Message::Theme(Choice::Custom("solarized".into())));
+    assert!(
+        app.log
+            .messages()
+            .any(|message| message.contains("palette `solarized` is not in the config"))
+    );
+}
@@ -0,0 +1,391 @@
+//! Palettes of the app: the built-in light and dark ones and the user-defined ones of
+//! [`Config`]. Colours of the text are checked against the background, see [`Palette::checked`]
+
+use crate::utils::{Result, error::anyio};
+use hex_colors::color_from_hex;
+use iced::{Color, Theme, theme};
+use iced_aw::style::BadgeStyles;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Least contrast of the text with the background, WCAG AA for the normal text
+pub const CONTRAST: f32 = 4.5;
+
+/// Colours of the log levels, checked against the background
+const LEVELS: [&str; 5] = ["trace", "debug", "info", "warn", "error"];
+
+macro_rules! hex {
+    ($($tt:tt)*) => {{
+        let [r, g, b]: [u8; 3] = color_from_hex!($($tt)*);
+        Color::from_rgb(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+        )
+    }};
+}
+
+/// Built-in palette, custom palettes take the missing colours from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Base {
+    Light,
+    #[default]
+    Dark,
+}
+
+impl Base {
+    pub const fn palette(self) -> Palette {
+        match self {
+            Self::Light => Palette::LIGHT,
+            Self::Dark => Palette::DARK,
+        }
+    }
+}
+
+/// Meaning of a badge, its style depends on the palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    Plain,
+    Good,
+    Warning,
+    Danger,
+}
+
+/// Colours of the widgets and of the log levels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub base: Base,
+    pub background: Color,
+    pub text: Color,
+    pub primary: Color,
+    pub success: Color,
+    pub danger: Color,
+    pub trace: Color,
+    pub debug: Color,
+    pub info: Color,
+    /// Also highlights the differences
+    pub warn: Color,
+    /// Also colours the invalid fields
+    pub error: Color,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+impl Palette {
+    pub const LIGHT: Self = Self {
+        base: Base::Light,
+        background: theme::Palette::LIGHT.background,
+        text: theme::Palette::LIGHT.text,
+        primary: theme::Palette::LIGHT.primary,
+        success: theme::Palette::LIGHT.success,
+        danger: theme::Palette::LIGHT.danger,
+        trace: hex!(0x8250df),
+        debug: hex!(0x0550ae),
+        info: hex!(0x116329),
+        warn: hex!(0x7d4e00),
+        error: hex!(0xcf222e),
+    };
+
+    pub const DARK: Self = Self {
+        base: Base::Dark,
+        background: theme::Palette::DARK.background,
+        text: theme::Palette::DARK.text,
+        primary: theme::Palette::DARK.primary,
+        success: theme::Palette::DARK.success,
+        danger: theme::Palette::DARK.danger,
+        trace: hex!(0xc586c0),
+        debug: hex!(0x6cb6ff),
+        info: hex!(0x57c785),
+        warn: hex!(0xe5c07b),
+        error: hex!(0xf47067),
+    };
+
+    /// Theme of the iced widgets
+    pub fn theme(&self) -> Theme {
+        if *self == Self::LIGHT {
+            Theme::Light
+        } else if *self == Self::DARK {
+            Theme::Dark
+        } else {
+            Theme::custom(theme::Palette {
+                background: self.background,
+                text: self.text,
+                primary: self.primary,
+                success: self.success,
+                danger: self.danger,
+            })
+        }
+    }
+
+    /// Badges readable on the background: the light badge is lost on the light one
+    pub const fn badge(&self, tone: Tone) -> BadgeStyles {
+        match (tone, self.base) {
+            (Tone::Plain, Base::Light) => BadgeStyles::Secondary,
+            (Tone::Plain, Base::Dark) => BadgeStyles::Light,
+            (Tone::Good, _) => BadgeStyles::Info,
+            (Tone::Warning, _) => BadgeStyles::Warning,
+            (Tone::Danger, _) => BadgeStyles::Danger,
+        }
+    }
+
+    fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "background" => &mut self.background,
+            "text" => &mut self.text,
+            "primary" => &mut self.primary,
+            "success" => &mut self.success,
+            "danger" => &mut self.danger,
+            "trace" => &mut self.trace,
+            "debug" => &mut self.debug,
+            "info" => &mut self.info,
+            "warn" => &mut self.warn,
+            "error" => &mut self.error,
+            _ => return None,
+        })
+    }
+
+    /// Replaces the colours hard to read on the background: the text becomes black or white,
+    /// the levels are blended with the text. Warnings name the replaced colours
+    pub fn checked(mut self) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let background = self.background;
+
+        if contrast(self.text, background) < CONTRAST {
+            self.text = if contrast(Color::BLACK, background) > contrast(Color::WHITE, background) {
+                Color::BLACK
+            } else {
+                Color::WHITE
+            };
+            warnings.push("`text` is hard to read on the background, it's replaced".to_owned());
+        }
+
+        let text = self.text;
+        for name in LEVELS {
+            let Some(color) = self.color_mut(name) else {
+                continue;
+            };
+            if contrast(*color, background) < CONTRAST {
+                *color = readable(*color, text, background);
+                warnings.push(format!(
+                    "`{name}` is hard to read on the background, it's blended with the text"
+                ));
+            }
+        }
+        (self, warnings)
+    }
+}
+
+/// WCAG contrast ratio, from 1 for the same colours to 21 for black on white.
+/// Transparency is ignored
+pub fn contrast(a: Color, b: Color) -> f32 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn luminance(Color { r, g, b, .. }: Color) -> f32 {
+    let linear = |channel: f32| {
+        if channel <= 0.039_28 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.0722f32.mul_add(linear(b), 0.2126f32.mul_add(linear(r), 0.7152 * linear(g)))
+}
+
+/// `color` blended with `text` just enough to be readable on `background`
+fn readable(color: Color, text: Color, background: Color) -> Color {
+    let mix = |weight: f32| Color {
+        r: (text.r - color.r).mul_add(weight, color.r),
+        g: (text.g - color.g).mul_add(weight, color.g),
+        b: (text.b - color.b).mul_add(weight, color.b),
+        a: color.a,
+    };
+    (1..=10u8)
+        .map(|step| mix(f32::from(step) / 10.0))
+        .find(|mixed| contrast(*mixed, background) >= CONTRAST)
+        .unwrap_or(text)
+}
+
+/// Colour of the config in the format of [`color_from_hex!`]: 6 or 8 hex digits of RGB or RGBA,
+/// optionally prefixed with `#` or `0x`
+///
+/// # Errors
+/// If it's not a colour
+pub fn parse(hex: &str) -> Result<Color> {
+    let digits = hex
+        .strip_prefix('#')
+        .or_else(|| hex.strip_prefix("0x"))
+        .unwrap_or(hex);
+    let value = match u32::from_str_radix(digits, 16) {
+        _ if !digits.bytes().all(|digit| digit.is_ascii_hexdigit()) => None,
+        Ok(value) if digits.len() == 6 => Some((value << 8) | 0xff),
+        Ok(value) if digits.len() == 8 => Some(value),
+        _ => None,
+    };
+    let [r, g, b, a] = value
+        .ok_or_else(|| anyio!("`{hex}` is not a colour, ex: 4c4c4c or #4c4c4cff"))?
+        .to_be_bytes();
+    Ok(Color::from_rgba8(r, g, b, f32::from(a) / 255.0))
+}
+
+/// Theme chosen by the user, a name of the config palettes otherwise
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Choice {
+    Light,
+    Dark,
+    /// Follows the platform
+    #[default]
+    System,
+    Custom(String),
+}
+
+impl From<String> for Choice {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "light" => Self::Light,
+            "dark" => Self::Dark,
+            "system" => Self::System,
+            _ => Self::Custom(name),
+        }
+    }
+}
+
+impl From<Choice> for String {
+    fn from(choice: Choice) -> Self {
+        choice.to_string()
+    }
+}
+
+impl Display for Choice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Light => f.write_str("light"),
+            Self::Dark => f.write_str("dark"),
+            Self::System => f.write_str("system"),
+            Self::Custom(name) => f.write_str(name),
+        }
+    }
+}
+
+/// Palette of the config, colours are hex strings by the names of the [`Palette`] fields,
+/// see [`parse`]. The missing ones are taken from the base
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Colors {
+    #[serde(default)]
+    pub base: Base,
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+impl Colors {
+    /// Malformed and unknown colours are ignored, see [`Palette::checked`] for the rest of
+    /// the warnings
+    pub fn palette(&self) -> (Palette, Vec<String>) {
+        let mut palette = self.base.palette();
+        let mut warnings = Vec::new();
+        for (name, hex) in &self.colors {
+            match (palette.color_mut(name), parse(hex)) {
+                (Some(color), Ok(parsed)) => *color = parsed,
+                (None, _) => warnings.push(format!("unknown colour `{name}` is ignored")),
+                (Some(_), Err(error)) => warnings.push(format!("`{name}` is ignored: {error}")),
+            }
+        }
+        let (palette, checked) = palette.checked();
+        warnings.extend(checked);
+        (palette, warnings)
+    }
+}
+
+/// Theme choice and the custom palettes by their names, kept on the client side
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub theme: Choice,
+    #[serde(default)]
+    pub palettes: BTreeMap<String, Colors>,
+}
+
+impl Config {
+    /// `themes.json` in the platform config directory
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("freezers-client").join("themes.json"))
+    }
+
+    /// Missing file means the system theme
+    ///
+    /// # Errors
+    /// If the file is not readable or malformed
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(json::from_slice(&bytes)?),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// # Errors
+    /// If the file or its directory is not writable
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Built-in themes, then the custom palettes
+    pub fn choices(&self) -> Vec<Choice> {
+        [Choice::System, Choice::Light, Choice::Dark]
+            .into_iter()
+            .chain(self.palettes.keys().cloned().map(Choice::from))
+            .collect()
+    }
+
+    /// Palette of the chosen theme, `system` is the base of the platform theme.
+    /// An unknown custom palette falls back to it
+    pub fn palette(&self, system: Base) -> (Palette, Vec<String>) {
+        match &self.theme {
+            Choice::Light => (Palette::LIGHT, vec![]),
+            Choice::Dark => (Palette::DARK, vec![]),
+            Choice::System => (system.palette(), vec![]),
+            Choice::Custom(name) => match self.palettes.get(name) {
+                Some(colors) => {
+                    let (palette, warnings) = colors.palette();
+                    let warnings = warnings
+                        .into_iter()
+                        .map(|warning| format!("palette `{name}`: {warning}"))
+                        .collect();
+                    (palette, warnings)
+                }
+                None => (
+                    system.palette(),
+                    vec![format!("palette `{name}` is not in the config")],
+                ),
+            },
+        }
+    }
+}
+
+/// Base of the platform theme, dark if it's unknown
+pub fn system() -> Base {
+    if dark_light::detect() == dark_light::Mode::Light {
+        Base::Light
+    } else {
+        Base::Dark
+    }
+}
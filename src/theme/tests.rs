@@ -0,0 +1,125 @@
+use super::{Base, CONTRAST, Choice, Colors, Config, Palette, contrast, parse};
+use iced::Color;
+
+fn colors(base: Base, colors: &[(&str, &str)]) -> Colors {
+    Colors {
+        base,
+        colors: colors
+            .iter()
+            .map(|(name, hex)| ((*name).to_owned(), (*hex).to_owned()))
+            .collect(),
+    }
+}
+
+#[test]
+fn built_ins_are_readable() {
+    for palette in [Palette::LIGHT, Palette::DARK] {
+        let (checked, warnings) = palette.checked();
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(checked, palette);
+    }
+}
+
+#[test]
+fn contrast_ratio() {
+    assert!((contrast(Color::BLACK, Color::WHITE) - 21.0).abs() < 0.01);
+    assert!((contrast(Color::WHITE, Color::BLACK) - 21.0).abs() < 0.01);
+    assert!((contrast(Palette::DARK.text, Palette::DARK.text) - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn hex_formats() {
+    let blue = Color::from_rgb8(0, 0, 0xff);
+    assert_eq!(parse("0000ff").unwrap(), blue);
+    assert_eq!(parse("#0000FF").unwrap(), blue);
+    assert_eq!(parse("0x0000ff").unwrap(), blue);
+    assert_eq!(parse("0000ff00").unwrap(), Color { a: 0.0, ..blue });
+
+    for malformed in ["", "#00f", "0000fff", "+000ff", "blue", "#0x0000ff"] {
+        assert!(parse(malformed).is_err(), "{malformed}");
+    }
+}
+
+#[test]
+fn unreadable_levels_are_blended() {
+    let (palette, warnings) =
+        colors(Base::Dark, &[("debug", "0000ff"), ("info", "57c785")]).palette();
+
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(warnings[0].contains("`debug`"));
+    assert!(contrast(palette.debug, palette.background) >= CONTRAST);
+    assert_ne!(palette.debug, palette.text);
+    assert_eq!(palette.info, Palette::DARK.info);
+}
+
+#[test]
+fn unreadable_text_is_replaced() {
+    let (palette, warnings) = colors(
+        Base::Dark,
+        &[
+            ("background", "#f5f5f5"),
+            ("text", "#ffffff"),
+            ("shadow", "000000"),
+        ],
+    )
+    .palette();
+
+    assert_eq!(palette.text, Color::BLACK);
+    // the dark levels are lost on the light background too
+    assert!(
+        warnings
+            .iter()
+            .any(|warning| warning.contains("unknown colour `shadow`"))
+    );
+    assert!(warnings.iter().any(|warning| warning.contains("`text`")));
+    assert!(warnings.iter().any(|warning| warning.contains("`warn`")));
+    assert!(contrast(palette.warn, palette.background) >= CONTRAST);
+}
+
+#[test]
+fn config_palettes() {
+    let config: Config = json::from_str(
+        r##"{
+            "theme": "solarized",
+            "palettes": {
+                "solarized": { "base": "light", "background": "#fdf6e3", "error": "nope" }
+            }
+        }"##,
+    )
+    .unwrap();
+    assert_eq!(config.theme, Choice::Custom("solarized".into()));
+    assert_eq!(
+        config.choices(),
+        [
+            Choice::System,
+            Choice::Light,
+            Choice::Dark,
+            Choice::Custom("solarized".into())
+        ]
+    );
+
+    let (palette, warnings) = config.palette(Base::Dark);
+    assert_eq!(palette.base, Base::Light);
+    assert_eq!(palette.background, Color::from_rgb8(0xfd, 0xf6, 0xe3));
+    assert_eq!(palette.error, Palette::LIGHT.error);
+    assert!(warnings[0].starts_with("palette `solarized`: `error` is ignored"));
+
+    // the chosen palette is gone
+    let config = Config {
+        theme: Choice::Custom("gone".into()),
+        ..Config::default()
+    };
+    let (palette, warnings) = config.palette(Base::Light);
+    assert_eq!(palette, Palette::LIGHT);
+    assert_eq!(warnings.len(), 1);
+
+    let config = Config {
+        theme: Choice::System,
+        ..Config::default()
+    };
+    assert_eq!(config.palette(Base::Light), (Palette::LIGHT, vec![]));
+    assert_eq!(
+        json::to_value(&config).unwrap(),
+        json::json!({ "theme": "system", "palettes": {} })
+    );
+}
@@ -0,0 +1,434 @@
+use crate::{
+    api::FreezerApi,
+    columee, row,
+    stock::{self, Alert, Level, Thresholds},
+    task::{Tagged, Task, Ticket, Written},
+    theme::{Palette, Tone},
+    utils::{Error, Result, error::anyio},
+};
+use iced::{Element, Length, Subscription, time};
+use iced_aw::{Badge, Card, NumberInput, style::BadgeStyles};
+use iced_native::{
+    Command,
+    widget::helpers::{button, checkbox, column, scrollable, text, text_input},
+};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+use tap::Pipe;
+
+#[cfg(test)]
+mod tests;
+
+/// Freezers requested at once while scanning
+const PARALLEL: usize = 8;
+
+/// Period of the background scan while notifications are on
+const PERIOD: Duration = Duration::from_secs(300);
+
+/// Quiet time after the last change of the thresholds before they are saved and scanned,
+/// a spinner sends a change per step
+const SETTLE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Open,
+    Close,
+    Refresh,
+    OnScan(Tagged<Result<Vec<Alert>>>),
+
+    InputProduct(String),
+    InputMinimum(usize),
+    /// Threshold of the input product
+    SetMinimum,
+    /// Threshold of `product`, overridden in `freezer` if it's given, `None` removes it
+    Threshold {
+        freezer: Option<String>,
+        product: String,
+        minimum: Option<usize>,
+    },
+    /// No changes of the thresholds since the one of the ticket
+    OnSettle(Tagged<()>),
+    OnSave(Tagged<Result<()>>),
+    ToggleNotify(bool),
+    OnNotify(Result<()>),
+
+    /// Freezer of the alert is requested to be shown
+    Show(String),
+
+    Error(Error),
+    Warn(Error),
+}
+
+/// Side effect requested by the update, see [`Alerts::perform`]
+#[derive(Debug)]
+pub enum Effect {
+    Scan {
+        thresholds: Thresholds,
+        ticket: Ticket,
+    },
+    /// Waits for the changes of the thresholds to settle
+    Settle(Ticket),
+    Save {
+        thresholds: Thresholds,
+        ticket: Ticket,
+    },
+    /// Desktop notification of the new alerts
+    Notify(Vec<Alert>),
+    Error(Error),
+    Warn(Error),
+}
+
+/// Products running low across all freezers and their thresholds
+#[derive(Debug)]
+pub struct Alerts {
+    open: bool,
+    thresholds: Thresholds,
+    /// Where thresholds are saved, `None` if there is no config directory
+    path: Option<PathBuf>,
+    /// `None` until the first scan
+    alerts: Option<Vec<Alert>>,
+    product: String,
+    minimum: usize,
+    notify: bool,
+    task: Task,
+    settle: Task,
+    save: Task,
+    written: Written,
+    palette: Palette,
+
+    client: Arc<dyn FreezerApi>,
+}
+
+impl Alerts {
+    pub fn new(client: Arc<dyn FreezerApi>, path: Option<PathBuf>, thresholds: Thresholds) -> Self {
+        Self {
+            open: false,
+            thresholds,
+            path,
+            alerts: None,
+            product: String::new(),
+            minimum: 1,
+            notify: false,
+            task: Task::default(),
+            settle: Task::default(),
+            save: Task::default(),
+            written: Written::default(),
+            palette: Palette::default(),
+            client,
+        }
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    pub const fn thresholds(&self) -> &Thresholds {
+        &self.thresholds
+    }
+
+    /// Count of the known alerts
+    pub fn count(&self) -> usize {
+        self.alerts.as_ref().map_or(0, Vec::len)
+    }
+
+    fn scan(&mut self) -> Effect {
+        Effect::Scan {
+            thresholds: self.thresholds.clone(),
+            ticket: self.task.begin(),
+        }
+    }
+
+    /// Alerts absent in the previous scan or worse than there
+    fn fresh(&self, alerts: &[Alert]) -> Vec<Alert> {
+        let known: HashMap<_, _> = self
+            .alerts
+            .iter()
+            .flatten()
+            .map(|alert| ((&alert.freezer, &alert.product), alert.level))
+            .collect();
+        alerts
+            .iter()
+            .filter(|alert| {
+                !known
+                    .get(&(&alert.freezer, &alert.product))
+                    .is_some_and(|level| *level >= alert.level)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Applies the message to the state, side effects are returned to be performed later
+    pub fn reduce(&mut self, message: Message) -> Vec<Effect> {
+        match message {
+            Message::Open => {
+                self.open = true;
+                if self.alerts.is_none() && !self.task.is_running() {
+                    vec![self.scan()]
+                } else {
+                    vec![]
+                }
+            }
+            Message::Close => {
+                self.open = false;
+                vec![]
+            }
+            Message::Refresh => vec![self.scan()],
+            Message::OnScan(res) => match self.task.accept(res) {
+                Some(Ok(alerts)) => {
+                    // the first scan only sets the baseline
+                    let fresh = if self.notify && self.alerts.is_some() {
+                        self.fresh(&alerts)
+                    } else {
+                        vec![]
+                    };
+                    self.alerts = Some(alerts);
+                    if fresh.is_empty() {
+                        vec![]
+                    } else {
+                        vec![Effect::Notify(fresh)]
+                    }
+                }
+                Some(Err(error)) => vec![Effect::Error(error)],
+                None => vec![],
+            },
+            Message::InputProduct(product) => {
+                self.product = product;
+                vec![]
+            }
+            Message::InputMinimum(minimum) => {
+                self.minimum = minimum;
+                vec![]
+            }
+            Message::SetMinimum if self.product.is_empty() => vec![],
+            Message::SetMinimum => {
+                let product = std::mem::take(&mut self.product);
+                self.reduce(Message::Threshold {
+                    freezer: None,
+                    product,
+                    minimum: Some(self.minimum),
+                })
+            }
+            Message::Threshold {
+                freezer,
+                product,
+                minimum,
+            } => {
+                self.thresholds.set(freezer.as_deref(), &product, minimum);
+                vec![Effect::Settle(self.settle.begin())]
+            }
+            Message::OnSettle(res) => match self.settle.accept(res) {
+                Some(()) => {
+                    let mut effects = vec![Effect::Save {
+                        thresholds: self.thresholds.clone(),
+                        ticket: self.save.begin(),
+                    }];
+                    // stale alerts are worse than a scan
+                    if self.alerts.is_some() {
+                        effects.push(self.scan());
+                    }
+                    effects
+                }
+                None => vec![],
+            },
+            Message::OnSave(res) => match self.save.accept(res) {
+                Some(Err(error)) => {
+                    vec![Effect::Warn(anyio!("thresholds are not saved: {error}"))]
+                }
+                Some(Ok(())) | None => vec![],
+            },
+            Message::OnNotify(res) => match res {
+                Ok(()) => vec![],
+                Err(error) => vec![Effect::Warn(anyio!("notification failed: {error}"))],
+            },
+            Message::ToggleNotify(notify) => {
+                self.notify = notify;
+                if notify && self.alerts.is_none() {
+                    vec![self.scan()]
+                } else {
+                    vec![]
+                }
+            }
+            Message::Show(_) => {
+                self.open = false;
+                vec![]
+            }
+            Message::Error(_) | Message::Warn(_) => vec![],
+        }
+    }
+
+    pub fn perform(&self, effect: Effect) -> Command<Message> {
+        match effect {
+            Effect::Scan { thresholds, ticket } => {
+                let client = self.client.clone();
+                ticket.perform(
+                    async move { stock::scan(&*client, &thresholds, PARALLEL).await },
+                    Message::OnScan,
+                )
+            }
+            Effect::Settle(ticket) => ticket.perform(tokio::time::sleep(SETTLE), Message::OnSettle),
+            Effect::Save { thresholds, ticket } => match self.path.clone() {
+                Some(path) => ticket.write(
+                    self.written.clone(),
+                    move || thresholds.save(&path),
+                    Message::OnSave,
+                ),
+                None => Command::none(),
+            },
+            Effect::Notify(alerts) => Self::notify(alerts),
+            Effect::Error(error) => Command::perform(async move { error }, Message::Error),
+            Effect::Warn(error) => Command::perform(async move { error }, Message::Warn),
+        }
+    }
+
+    #[cfg(feature = "notifications")]
+    fn notify(alerts: Vec<Alert>) -> Command<Message> {
+        let body = alerts
+            .iter()
+            .map(|alert| {
+                format!(
+                    "{} in `{}`: {} of {}",
+                    alert.product, alert.freezer, alert.units, alert.minimum
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Command::perform(
+            tokio::task::spawn_blocking(move || {
+                notify_rust::Notification::new()
+                    .summary("Freezers are running low")
+                    .body(&body)
+                    .show()
+                    .map(drop)
+                    .map_err(Error::from)
+            }),
+            |res| Message::OnNotify(res.map_err(Error::from).and_then(|res| res)),
+        )
+    }
+
+    #[cfg(not(feature = "notifications"))]
+    #[allow(clippy::needless_pass_by_value)]
+    fn notify(_: Vec<Alert>) -> Command<Message> {
+        Command::none()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.notify {
+            time::every(PERIOD).map(|_| Message::Refresh)
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn alerts<'a>(alerts: &[Alert], palette: &Palette) -> Element<'a, Message> {
+        if alerts.is_empty() {
+            return text("everything is stocked").into();
+        }
+
+        let line = |alert: &Alert| {
+            let Alert {
+                freezer,
+                product,
+                units,
+                minimum,
+                level,
+            } = alert;
+            Badge::new(
+                button(text(format!("`{freezer}`: {product} {units}/{minimum}")))
+                    .on_press(Message::Show(freezer.clone())),
+            )
+            .style(style(*level, palette))
+            .into()
+        };
+        column(alerts.iter().map(line).collect()).spacing(5).into()
+    }
+
+    fn thresholds_view(&self) -> Element<'_, Message> {
+        let mut products: Vec<_> = self.thresholds.products.iter().collect();
+        products.sort();
+
+        let line = |(product, minimum): (&String, &usize)| {
+            row![
+                text(format!("{product}: {minimum}")).width(Length::Fill),
+                button("remove").on_press(Message::Threshold {
+                    freezer: None,
+                    product: product.clone(),
+                    minimum: None,
+                }),
+            ]
+            .spacing(10)
+            .into()
+        };
+
+        columee![
+            row![
+                text_input("product", &self.product, Message::InputProduct)
+                    .on_submit(Message::SetMinimum),
+                NumberInput::new(self.minimum, usize::MAX, Message::InputMinimum),
+                button("SET").pipe(|button| {
+                    if self.product.is_empty() {
+                        button
+                    } else {
+                        button.on_press(Message::SetMinimum)
+                    }
+                }),
+            ]
+            .spacing(10),
+            column(products.into_iter().map(line).collect()).spacing(5),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// Alert panel, shown by the app over all panes
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        if !self.open {
+            return None;
+        }
+
+        let refresh = button(text("REFRESH")).pipe(|button| {
+            if self.task.is_running() {
+                button
+            } else {
+                button.on_press(Message::Refresh)
+            }
+        });
+        let head = row![text("Alerts"), refresh].spacing(10).pipe(|head| {
+            if cfg!(feature = "notifications") {
+                head.push(checkbox("notify", self.notify, Message::ToggleNotify))
+            } else {
+                head
+            }
+        });
+
+        let alerts = match &self.alerts {
+            Some(alerts) => Self::alerts(alerts, &self.palette),
+            None => text("scanning the freezers...").into(),
+        };
+
+        let body = row![
+            columee![text("LOW STOCK").size(30), scrollable(alerts)].width(Length::FillPortion(2)),
+            columee![
+                text("THRESHOLDS").size(30),
+                text("freezers override them in the preview").size(14),
+                self.thresholds_view(),
+            ]
+            .width(Length::FillPortion(1)),
+        ]
+        .spacing(20);
+
+        Some(
+            Card::new(head, body)
+                .max_width(1200)
+                .on_close(Message::Close)
+                .into(),
+        )
+    }
+}
+
+/// Badge of the product stock
+pub const fn style(level: Level, palette: &Palette) -> BadgeStyles {
+    palette.badge(match level {
+        Level::Enough => Tone::Good,
+        Level::Low => Tone::Warning,
+        Level::Out => Tone::Danger,
+    })
+}
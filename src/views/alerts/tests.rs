@@ -0,0 +1,187 @@
+use super::{Alerts, Effect, Message};
+use crate::{
+    stock::{Alert, Level, Thresholds},
+    task::Ticket,
+    utils::error::anyio,
+    views::test_support::{client, ticket},
+};
+
+/// Effects are never performed, so neither the backend nor the disk is reached
+fn alerts() -> Alerts {
+    Alerts::new(client(), None, Thresholds::default())
+}
+
+fn alert(freezer: &str, units: usize, level: Level) -> Alert {
+    Alert {
+        freezer: freezer.into(),
+        product: "pelmeni".into(),
+        units,
+        minimum: 3,
+        level,
+    }
+}
+
+fn scan_ticket(effects: Vec<Effect>) -> Ticket {
+    ticket!(effects, Effect::Scan { ticket, .. } => ticket)
+}
+
+#[test]
+fn scanned_once() {
+    let mut alerts = alerts();
+
+    let ticket = scan_ticket(alerts.reduce(Message::Open));
+    let effects = alerts.reduce(Message::OnScan(ticket.resolve(Ok(vec![alert(
+        "garage",
+        1,
+        Level::Low,
+    )]))));
+    assert!(effects.is_empty(), "{effects:?}");
+    assert_eq!(alerts.count(), 1);
+
+    alerts.reduce(Message::Close);
+    assert!(alerts.reduce(Message::Open).is_empty());
+}
+
+/// Effects of the change once it has settled
+fn settled(alerts: &mut Alerts, effects: Vec<Effect>) -> Vec<Effect> {
+    let [Effect::Settle(ticket)] = &effects[..] else {
+        panic!("change settles first: {effects:?}");
+    };
+    alerts.reduce(Message::OnSettle(ticket.resolve(())))
+}
+
+#[test]
+fn thresholds_are_saved() {
+    let mut alerts = alerts();
+
+    alerts.reduce(Message::InputProduct("pelmeni".into()));
+    alerts.reduce(Message::InputMinimum(3));
+    let effects = alerts.reduce(Message::SetMinimum);
+    assert_eq!(alerts.thresholds().minimum("garage", "pelmeni"), Some(3));
+    assert!(
+        matches!(&settled(&mut alerts, effects)[..], [Effect::Save { thresholds, .. }]
+        if thresholds.minimum("garage", "pelmeni") == Some(3))
+    );
+    assert!(alerts.product.is_empty());
+
+    // known alerts are outdated by the change
+    let ticket = scan_ticket(alerts.reduce(Message::Refresh));
+    alerts.reduce(Message::OnScan(ticket.resolve(Ok(vec![]))));
+    let effects = alerts.reduce(Message::Threshold {
+        freezer: Some("garage".into()),
+        product: "pelmeni".into(),
+        minimum: Some(0),
+    });
+    assert!(matches!(
+        &settled(&mut alerts, effects)[..],
+        [Effect::Save { .. }, Effect::Scan { thresholds, .. }]
+            if thresholds.minimum("garage", "pelmeni") == Some(0)
+    ));
+    assert_eq!(alerts.thresholds().minimum("kitchen", "pelmeni"), Some(3));
+}
+
+#[test]
+fn steps_are_saved_once() {
+    let mut alerts = alerts();
+    let ticket = scan_ticket(alerts.reduce(Message::Refresh));
+    alerts.reduce(Message::OnScan(ticket.resolve(Ok(vec![]))));
+
+    let steps: Vec<_> = (1..=3)
+        .map(|minimum| {
+            alerts.reduce(Message::Threshold {
+                freezer: Some("garage".into()),
+                product: "pelmeni".into(),
+                minimum: Some(minimum),
+            })
+        })
+        .collect();
+    let [first, _, last] = <[_; 3]>::try_from(steps).unwrap();
+
+    // superseded steps neither save nor scan
+    let Effect::Settle(ticket) = &first[0] else {
+        panic!("change settles first");
+    };
+    assert!(
+        alerts
+            .reduce(Message::OnSettle(ticket.resolve(())))
+            .is_empty()
+    );
+    let effects = settled(&mut alerts, last);
+    let [
+        Effect::Save {
+            thresholds,
+            ticket: older,
+        },
+        Effect::Scan { .. },
+    ] = &effects[..]
+    else {
+        panic!("saved and scanned: {effects:?}");
+    };
+    assert_eq!(thresholds.minimum("garage", "pelmeni"), Some(3));
+
+    // removal of the override falls back to the threshold of the product
+    let effects = alerts.reduce(Message::Threshold {
+        freezer: Some("garage".into()),
+        product: "pelmeni".into(),
+        minimum: None,
+    });
+    assert!(alerts.thresholds().freezers.is_empty());
+    let effects = settled(&mut alerts, effects);
+    let Effect::Save { ticket: newer, .. } = &effects[0] else {
+        panic!("saved: {effects:?}");
+    };
+
+    // response of the superseded save is dropped
+    let failed = || Err(anyio!("disk is full"));
+    assert!(
+        alerts
+            .reduce(Message::OnSave(older.resolve(failed())))
+            .is_empty()
+    );
+    assert!(matches!(
+        &alerts.reduce(Message::OnSave(newer.resolve(failed())))[..],
+        [Effect::Warn(_)]
+    ));
+}
+
+#[test]
+fn notified_of_fresh_alerts() {
+    let mut alerts = alerts();
+
+    let ticket = scan_ticket(alerts.reduce(Message::ToggleNotify(true)));
+    let baseline = vec![
+        alert("garage", 1, Level::Low),
+        alert("kitchen", 2, Level::Low),
+    ];
+    assert!(
+        alerts
+            .reduce(Message::OnScan(ticket.resolve(Ok(baseline))))
+            .is_empty()
+    );
+
+    let ticket = scan_ticket(alerts.reduce(Message::Refresh));
+    let effects = alerts.reduce(Message::OnScan(ticket.resolve(Ok(vec![
+        alert("garage", 0, Level::Out),
+        alert("kitchen", 2, Level::Low),
+        alert("office", 1, Level::Low),
+    ]))));
+    let [Effect::Notify(fresh)] = &effects[..] else {
+        panic!("{effects:?}");
+    };
+    assert_eq!(
+        fresh,
+        &[
+            alert("garage", 0, Level::Out),
+            alert("office", 1, Level::Low)
+        ]
+    );
+
+    // nothing new
+    let ticket = scan_ticket(alerts.reduce(Message::Refresh));
+    let effects = alerts.reduce(Message::OnScan(ticket.resolve(Ok(vec![alert(
+        "garage",
+        0,
+        Level::Out,
+    )]))));
+    assert!(effects.is_empty(), "{effects:?}");
+}
@@ -0,0 +1,511 @@
+use crate::{
+    api::FreezerApi,
+    columee,
+    inventory::{self, Format},
+    model::{Freezer, Permission},
+    owners::{self, Owner},
+    row,
+    task::{Tagged, Task, Ticket},
+    utils::{Error, Result, error::anyio},
+};
+use iced::{Element, Length};
+use iced_aw::Card;
+use iced_native::{
+    Command,
+    widget::helpers::{button, progress_bar, scrollable, text, text_input},
+};
+use std::{collections::VecDeque, fmt::Write, path::PathBuf, sync::Arc};
+use tap::Pipe;
+
+#[cfg(test)]
+mod tests;
+
+/// Freezers processed at once
+const PARALLEL: usize = 4;
+
+/// Action over the selected freezers of the list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Delete,
+    Owner(Owner),
+    /// Writes the freezers to the file, see [`inventory::write`]
+    Export,
+}
+
+impl Action {
+    fn question(&self, count: usize) -> String {
+        match self {
+            Self::Delete => format!("delete {count} freezers?"),
+            Self::Owner(owner) => format!("assign {count} freezers to {owner}?"),
+            Self::Export => format!("export {count} freezers?"),
+        }
+    }
+
+    const fn verb(&self) -> &'static str {
+        match self {
+            Self::Delete => "deleted",
+            Self::Owner(_) => "reassigned",
+            Self::Export => "exported",
+        }
+    }
+
+    /// Permission the action needs, `None` if it changes nothing
+    pub const fn permission(&self) -> Option<Permission> {
+        match self {
+            Self::Delete => Some(Permission::Delete),
+            Self::Owner(_) => Some(Permission::Update),
+            Self::Export => None,
+        }
+    }
+
+    const fn changes(&self) -> bool {
+        self.permission().is_some()
+    }
+}
+
+/// Result of the action on a single freezer
+#[derive(Debug, Clone)]
+// a freezer per exported item, not worth the boxing
+#[allow(clippy::large_enum_variant)]
+pub enum Done {
+    Applied,
+    /// The server refused the change
+    Refused,
+    /// Fetched to be written by the export
+    Fetched(Freezer),
+}
+
+impl Done {
+    const fn applied(is_applied: bool) -> Self {
+        if is_applied {
+            Self::Applied
+        } else {
+            Self::Refused
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Asks to confirm the action over `ids`
+    Open {
+        ids: Vec<String>,
+        action: Action,
+    },
+    Close,
+    InputPath(String),
+
+    Start,
+    OnItem {
+        slot: usize,
+        res: Tagged<(String, Result<Done>)>,
+    },
+    OnWrite(Tagged<Result<()>>),
+    Cancel,
+
+    /// Freezers are changed by the action, the list is outdated
+    Changed,
+
+    Error(Error),
+    Warn(Error),
+    Info(String),
+}
+
+/// Side effect requested by the update, see [`Bulk::perform`]
+#[derive(Debug)]
+pub enum Effect {
+    Item {
+        id: String,
+        action: Action,
+        slot: usize,
+        ticket: Ticket,
+    },
+    Write {
+        path: PathBuf,
+        format: Format,
+        freezers: Vec<Freezer>,
+        ticket: Ticket,
+    },
+    Changed,
+    Error(Error),
+    Warn(Error),
+    Info(String),
+}
+
+#[derive(Debug)]
+struct Job {
+    action: Action,
+    /// File of the export
+    target: Option<(PathBuf, Format)>,
+    pending: VecDeque<String>,
+    /// Freezer in flight per slot
+    running: [Option<String>; PARALLEL],
+    results: Vec<(String, Result<Done>)>,
+    total: usize,
+}
+
+impl Job {
+    /// Fetched freezers are not exported if the file is not written
+    fn unwritten(&mut self, error: &Error) {
+        for (_, result) in &mut self.results {
+            if matches!(result, Ok(Done::Fetched(_))) {
+                *result = Err(error.clone());
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Stage {
+    Idle,
+    Confirm { ids: Vec<String>, action: Action },
+    Running(Job),
+    Writing(Job),
+}
+
+/// Delete, reassignment and export of the freezers selected in the list,
+/// [`PARALLEL`] freezers at once
+#[derive(Debug)]
+pub struct Bulk {
+    open: bool,
+    path: String,
+    stage: Stage,
+    /// A request per slot, the export is written by the first one
+    slots: [Task; PARALLEL],
+
+    client: Arc<dyn FreezerApi>,
+}
+
+impl Bulk {
+    pub fn new(client: Arc<dyn FreezerApi>) -> Self {
+        Self {
+            open: false,
+            path: String::new(),
+            stage: Stage::Idle,
+            slots: Default::default(),
+            client,
+        }
+    }
+
+    const fn is_busy(&self) -> bool {
+        matches!(self.stage, Stage::Running(_) | Stage::Writing(_))
+    }
+
+    fn target(&self) -> Result<(PathBuf, Format)> {
+        let path = PathBuf::from(&self.path);
+        let format = Format::from_path(&path)
+            .ok_or_else(|| anyio!("unknown format of `{}` - use `.json` or `.csv`", self.path))?;
+        Ok((path, format))
+    }
+
+    /// Sends the next freezer of the job by the idle `slot`
+    fn next(&mut self, slot: usize) -> Option<Effect> {
+        let Stage::Running(job) = &mut self.stage else {
+            return None;
+        };
+        let id = job.pending.pop_front()?;
+        job.running[slot] = Some(id.clone());
+        Some(Effect::Item {
+            id,
+            action: job.action.clone(),
+            slot,
+            ticket: self.slots[slot].begin(),
+        })
+    }
+
+    /// Writes the export or reports the job once every freezer is processed
+    fn finish(&mut self) -> Vec<Effect> {
+        let Stage::Running(job) = std::mem::replace(&mut self.stage, Stage::Idle) else {
+            return vec![];
+        };
+        let Some((path, format)) = job.target.clone() else {
+            return self.report(job);
+        };
+
+        let freezers = job
+            .results
+            .iter()
+            .filter_map(|(_, result)| match result {
+                Ok(Done::Fetched(freezer)) => Some(freezer.clone()),
+                _ => None,
+            })
+            .collect();
+        self.stage = Stage::Writing(job);
+        vec![Effect::Write {
+            path,
+            format,
+            freezers,
+            ticket: self.slots[0].begin(),
+        }]
+    }
+
+    /// Closes the dialog with the result per freezer and the summary
+    fn report(&mut self, job: Job) -> Vec<Effect> {
+        self.stage = Stage::Idle;
+        self.open = false;
+
+        let Job {
+            action,
+            pending,
+            running,
+            results,
+            total,
+            ..
+        } = job;
+        let verb = action.verb();
+        let (mut applied, mut refused, mut failed) = (0, 0, 0);
+        let mut effects = vec![];
+        for (id, result) in results {
+            match result {
+                Ok(Done::Applied | Done::Fetched(_)) => {
+                    applied += 1;
+                    effects.push(Effect::Info(format!("`{id}` is {verb}")));
+                }
+                Ok(Done::Refused) => {
+                    refused += 1;
+                    effects.push(Effect::Warn(anyio!(
+                        "unauthorized access: `{id}` is not {verb}"
+                    )));
+                }
+                Err(error) => {
+                    failed += 1;
+                    effects.push(Effect::Error(anyio!("`{id}` is not {verb}: {error}")));
+                }
+            }
+        }
+
+        let mut summary =
+            format!("{verb} {applied} of {total} freezers, {refused} refused, {failed} failed");
+        let cancelled = pending.len() + running.iter().flatten().count();
+        if cancelled > 0 {
+            let _ = write!(summary, ", {cancelled} cancelled");
+        }
+        effects.push(Effect::Info(summary));
+        if action.changes() && applied > 0 {
+            effects.push(Effect::Changed);
+        }
+        effects
+    }
+
+    /// Applies the message to the state, side effects are returned to be performed later
+    pub fn reduce(&mut self, message: Message) -> Vec<Effect> {
+        match message {
+            Message::Open { .. } if self.is_busy() => {
+                self.open = true;
+                vec![Effect::Warn(anyio!("another bulk action is running"))]
+            }
+            Message::Open { ids, .. } if ids.is_empty() => vec![],
+            Message::Open { ids, action } => {
+                self.stage = Stage::Confirm { ids, action };
+                self.open = true;
+                vec![]
+            }
+            Message::Close => {
+                let effects = self.reduce(Message::Cancel);
+                self.stage = Stage::Idle;
+                self.open = false;
+                effects
+            }
+            Message::InputPath(path) => {
+                self.path = path;
+                vec![]
+            }
+            Message::Start => {
+                let Stage::Confirm { ids, action } = &self.stage else {
+                    return vec![];
+                };
+                let target = if *action == Action::Export {
+                    match self.target() {
+                        Ok(target) => Some(target),
+                        Err(error) => return vec![Effect::Error(error)],
+                    }
+                } else {
+                    None
+                };
+                self.stage = Stage::Running(Job {
+                    action: action.clone(),
+                    target,
+                    pending: ids.iter().cloned().collect(),
+                    running: Default::default(),
+                    results: vec![],
+                    total: ids.len(),
+                });
+
+                let effects: Vec<_> = (0..PARALLEL).filter_map(|slot| self.next(slot)).collect();
+                if effects.is_empty() {
+                    self.finish()
+                } else {
+                    effects
+                }
+            }
+            Message::OnItem { slot, res } => {
+                let Some(result) = self.slots.get_mut(slot).and_then(|task| task.accept(res))
+                else {
+                    return vec![];
+                };
+                let Stage::Running(job) = &mut self.stage else {
+                    return vec![];
+                };
+                job.running[slot] = None;
+                job.results.push(result);
+
+                match self.next(slot) {
+                    Some(effect) => vec![effect],
+                    None if self.slots.iter().any(Task::is_running) => vec![],
+                    None => self.finish(),
+                }
+            }
+            Message::OnWrite(res) => {
+                let Some(res) = self.slots[0].accept(res) else {
+                    return vec![];
+                };
+                let Stage::Writing(mut job) = std::mem::replace(&mut self.stage, Stage::Idle)
+                else {
+                    return vec![];
+                };
+                let path = self.path.clone();
+                match res {
+                    Ok(()) => {
+                        let mut effects = self.report(job);
+                        effects.push(Effect::Info(format!("export is written to `{path}`")));
+                        effects
+                    }
+                    Err(error) => {
+                        job.unwritten(&anyio!("`{path}` is not written: {error}"));
+                        self.report(job)
+                    }
+                }
+            }
+            Message::Cancel => {
+                for slot in &mut self.slots {
+                    slot.cancel();
+                }
+                match std::mem::replace(&mut self.stage, Stage::Idle) {
+                    Stage::Running(job) => self.report(job),
+                    Stage::Writing(mut job) => {
+                        job.unwritten(&anyio!("export is cancelled"));
+                        self.report(job)
+                    }
+                    stage => {
+                        self.stage = stage;
+                        vec![]
+                    }
+                }
+            }
+            Message::Changed | Message::Error(_) | Message::Warn(_) | Message::Info(_) => vec![],
+        }
+    }
+
+    pub fn perform(&self, effect: Effect) -> Command<Message> {
+        let client = self.client.clone();
+        match effect {
+            Effect::Item {
+                id,
+                action,
+                slot,
+                ticket,
+            } => ticket.perform(
+                async move {
+                    let done = match action {
+                        Action::Delete => client.delete_freezer(&id).await.map(Done::applied),
+                        Action::Owner(owner) => owners::assign(&*client, &id, &owner)
+                            .await
+                            .map(Done::applied),
+                        Action::Export => client.freezer(&id).await.map(Done::Fetched),
+                    };
+                    (id, done)
+                },
+                move |res| Message::OnItem { slot, res },
+            ),
+            Effect::Write {
+                path,
+                format,
+                freezers,
+                ticket,
+            } => ticket.perform(
+                async move {
+                    let bytes = inventory::write(format, &inventory::rows(&freezers))?;
+                    tokio::fs::write(path, bytes).await?;
+                    Ok(())
+                },
+                Message::OnWrite,
+            ),
+            Effect::Changed => Command::perform(async {}, |_| Message::Changed),
+            Effect::Error(error) => Command::perform(async move { error }, Message::Error),
+            Effect::Warn(error) => Command::perform(async move { error }, Message::Warn),
+            Effect::Info(info) => Command::perform(async move { info }, Message::Info),
+        }
+    }
+
+    fn stage(&self) -> Element<'_, Message> {
+        match &self.stage {
+            Stage::Idle => text("").into(),
+            Stage::Confirm { ids, action } => columee![
+                text(action.question(ids.len())),
+                scrollable(text(ids.join(", "))).height(Length::Units(100)),
+            ]
+            .pipe(|body| {
+                if *action == Action::Export {
+                    body.push(text_input(
+                        "path to `.json` or `.csv`",
+                        &self.path,
+                        Message::InputPath,
+                    ))
+                } else {
+                    body
+                }
+            })
+            .push(
+                row![
+                    button(text("START")).on_press(Message::Start),
+                    button(text("CANCEL")).on_press(Message::Close),
+                ]
+                .spacing(10),
+            )
+            .spacing(10)
+            .into(),
+            Stage::Running(job) => {
+                let processed = job.results.len();
+                let failed = job.results.iter().filter(|(_, res)| res.is_err()).count();
+                let running: Vec<_> = job
+                    .running
+                    .iter()
+                    .flatten()
+                    .map(|id| format!("`{id}`"))
+                    .collect();
+                #[allow(clippy::cast_precision_loss)]
+                let bar = progress_bar(0.0..=job.total as f32, processed as f32);
+                columee![
+                    bar,
+                    text(format!(
+                        "{processed}/{}, {failed} failed - {}",
+                        job.total,
+                        running.join(", ")
+                    )),
+                    button(text("CANCEL")).on_press(Message::Cancel),
+                ]
+                .spacing(10)
+                .into()
+            }
+            Stage::Writing(_) => columee![
+                text(format!("writing `{}`...", self.path)),
+                button(text("CANCEL")).on_press(Message::Cancel),
+            ]
+            .spacing(10)
+            .into(),
+        }
+    }
+
+    /// Progress of the bulk action, shown by the app over all panes
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        if !self.open || matches!(self.stage, Stage::Idle) {
+            return None;
+        }
+        Some(
+            Card::new(text("Selected freezers"), self.stage())
+                .max_width(700)
+                .on_close(Message::Close)
+                .into(),
+        )
+    }
+}
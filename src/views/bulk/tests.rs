@@ -0,0 +1,180 @@
+use super::{Action, Bulk, Done, Effect, Message};
+use crate::{
+    owners::Owner,
+    task::Ticket,
+    utils::error::anyio,
+    views::test_support::{client, freezer},
+};
+
+fn ids(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(ToString::to_string).collect()
+}
+
+fn started(ids: Vec<String>, action: Action) -> (Bulk, Vec<Effect>) {
+    let mut bulk = Bulk::new(client());
+    bulk.reduce(Message::Open { ids, action });
+    let effects = bulk.reduce(Message::Start);
+    (bulk, effects)
+}
+
+fn items(effects: Vec<Effect>) -> Vec<(String, usize, Ticket)> {
+    effects
+        .into_iter()
+        .filter_map(|effect| match effect {
+            Effect::Item {
+                id, slot, ticket, ..
+            } => Some((id, slot, ticket)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn infos(effects: &[Effect]) -> Vec<&str> {
+    effects
+        .iter()
+        .filter_map(|effect| match effect {
+            Effect::Info(info) => Some(info.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn bounded_concurrency() {
+    let (mut bulk, effects) = started(ids(&["a", "b", "c", "d", "e", "f"]), Action::Delete);
+    let running = items(effects);
+    assert_eq!(
+        running
+            .iter()
+            .map(|(id, slot, _)| (id.as_str(), *slot))
+            .collect::<Vec<_>>(),
+        [("a", 0), ("b", 1), ("c", 2), ("d", 3)]
+    );
+
+    let mut effects = vec![];
+    for (id, slot, ticket) in running {
+        let res = ticket.resolve((id, Ok(Done::Applied)));
+        let next = items(bulk.reduce(Message::OnItem { slot, res }));
+        effects.extend(next);
+    }
+    // an idle slot takes the next freezer
+    assert_eq!(
+        effects
+            .iter()
+            .map(|(id, slot, _)| (id.as_str(), *slot))
+            .collect::<Vec<_>>(),
+        [("e", 0), ("f", 1)]
+    );
+
+    let mut last = vec![];
+    for (id, slot, ticket) in effects {
+        let res = ticket.resolve((id, Ok(Done::Applied)));
+        last = bulk.reduce(Message::OnItem { slot, res });
+    }
+    assert_eq!(
+        infos(&last).last(),
+        Some(&"deleted 6 of 6 freezers, 0 refused, 0 failed")
+    );
+    assert_eq!(infos(&last).len(), 7);
+    assert!(matches!(last.last(), Some(Effect::Changed)));
+    assert!(bulk.view().is_none());
+}
+
+#[test]
+fn result_per_freezer() {
+    let owner = Action::Owner(Owner::User("bob".to_owned()));
+    let (mut bulk, effects) = started(ids(&["a", "b", "c"]), owner);
+
+    let mut results = [
+        Ok(Done::Applied),
+        Ok(Done::Refused),
+        Err(anyio!("connection reset")),
+    ]
+    .into_iter();
+    let mut effects: Vec<_> = items(effects)
+        .into_iter()
+        .flat_map(|(id, slot, ticket)| {
+            let res = ticket.resolve((id, results.next().unwrap()));
+            bulk.reduce(Message::OnItem { slot, res })
+        })
+        .collect();
+
+    assert!(matches!(effects.pop(), Some(Effect::Changed)));
+    assert!(matches!(
+        &effects[..],
+        [
+            Effect::Info(applied),
+            Effect::Warn(_),
+            Effect::Error(failed),
+            Effect::Info(summary),
+        ] if applied == "`a` is reassigned"
+            && failed.to_string() == "`c` is not reassigned: connection reset"
+            && summary == "reassigned 1 of 3 freezers, 1 refused, 1 failed"
+    ));
+}
+
+#[test]
+fn cancel() {
+    let (mut bulk, effects) = started(ids(&["a", "b", "c", "d", "e", "f"]), Action::Delete);
+    let mut running = items(effects).into_iter();
+    let (id, slot, ticket) = running.next().unwrap();
+    let next = bulk.reduce(Message::OnItem {
+        slot,
+        res: ticket.resolve((id, Ok(Done::Applied))),
+    });
+
+    let effects = bulk.reduce(Message::Cancel);
+    assert_eq!(
+        infos(&effects),
+        [
+            "`a` is deleted",
+            "deleted 1 of 6 freezers, 0 refused, 0 failed, 5 cancelled"
+        ]
+    );
+    assert!(matches!(effects.last(), Some(Effect::Changed)));
+
+    // responses of the cancelled requests are dropped
+    for (id, slot, ticket) in running.chain(items(next)) {
+        let res = ticket.resolve((id, Ok(Done::Applied)));
+        assert!(bulk.reduce(Message::OnItem { slot, res }).is_empty());
+    }
+}
+
+#[test]
+fn export() {
+    let mut bulk = Bulk::new(client());
+    bulk.reduce(Message::Open {
+        ids: ids(&["a", "b"]),
+        action: Action::Export,
+    });
+    bulk.reduce(Message::InputPath("dump.txt".to_owned()));
+    assert!(matches!(
+        &bulk.reduce(Message::Start)[..],
+        [Effect::Error(_)]
+    ));
+
+    bulk.reduce(Message::InputPath("dump.csv".to_owned()));
+    let mut effects = vec![];
+    for (id, slot, ticket) in items(bulk.reduce(Message::Start)) {
+        let res = ticket.resolve((id.clone(), Ok(Done::Fetched(freezer(&id, &[])))));
+        effects = bulk.reduce(Message::OnItem { slot, res });
+    }
+    let Some(Effect::Write {
+        freezers, ticket, ..
+    }) = effects.pop()
+    else {
+        panic!("export is written");
+    };
+    assert_eq!(freezers, [freezer("a", &[]), freezer("b", &[])]);
+
+    let effects = bulk.reduce(Message::OnWrite(ticket.resolve(Ok(()))));
+    assert_eq!(
+        infos(&effects),
+        [
+            "`a` is exported",
+            "`b` is exported",
+            "exported 2 of 2 freezers, 0 refused, 0 failed",
+            "export is written to `dump.csv`"
+        ]
+    );
+}
@@ -0,0 +1,319 @@
+use crate::{
+    api::FreezerApi,
+    columee, model, row,
+    task::{Tagged, Task, Ticket},
+    theme::Palette,
+    utils::{Error, Result},
+};
+use iced::{Color, Element, Length};
+use iced_native::{
+    Command,
+    widget::helpers::{button, column, horizontal_rule, pick_list, scrollable, text},
+};
+use std::{collections::BTreeSet, fmt::Display, mem, sync::Arc};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Shows the panels instead of the preview with the given freezers
+    Open {
+        left: Option<String>,
+        right: Option<String>,
+    },
+    Close,
+    Select(Side, String),
+    OnFetch(Side, Tagged<Result<model::Freezer>>),
+    Swap,
+    /// Fetches the shown freezers again, e.g. after they are changed elsewhere
+    Refresh,
+
+    Error(Error),
+}
+
+/// Side effect requested by the update, see [`Compare::perform`]
+#[derive(Debug)]
+pub enum Effect {
+    Fetch {
+        side: Side,
+        id: String,
+        ticket: Ticket,
+    },
+    Error(Error),
+}
+
+/// Row of the comparison, `None` where the freezer has no value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line<T> {
+    pub label: String,
+    pub left: Option<T>,
+    pub right: Option<T>,
+}
+
+impl<T: PartialEq> Line<T> {
+    pub fn differs(&self) -> bool {
+        self.left != self.right
+    }
+}
+
+/// Model and details of both freezers, see [`model::Details::fields`]
+pub fn fields(left: &model::Freezer, right: &model::Freezer) -> Vec<Line<String>> {
+    let summary = |freezer: &model::Freezer| {
+        [
+            ("model", Some(freezer.model.name.clone())),
+            ("year", Some(freezer.model.year.to_string())),
+            ("owner", freezer.owner.clone()),
+        ]
+        .into_iter()
+        .chain(freezer.details.fields())
+    };
+    summary(left)
+        .zip(summary(right))
+        .map(|((label, left), (_, right))| Line {
+            label: label.to_owned(),
+            left,
+            right,
+        })
+        .collect()
+}
+
+/// Amounts aligned by product ids, products of either freezer in the order of ids
+pub fn products(left: &model::Freezer, right: &model::Freezer) -> Vec<Line<usize>> {
+    let ids: BTreeSet<_> = left.products.keys().chain(right.products.keys()).collect();
+    ids.into_iter()
+        .map(|id| Line {
+            label: id.clone(),
+            left: left.products.get(id).copied(),
+            right: right.products.get(id).copied(),
+        })
+        .collect()
+}
+
+/// Freezer shown on one side
+#[derive(Debug, Default)]
+struct Panel {
+    id: Option<String>,
+    freezer: Option<model::Freezer>,
+    task: Task,
+}
+
+impl Panel {
+    /// Fetches the shown freezer again, the old contents are kept meanwhile
+    fn fetch(&mut self, side: Side) -> Option<Effect> {
+        Some(Effect::Fetch {
+            side,
+            id: self.id.clone()?,
+            ticket: self.task.begin(),
+        })
+    }
+
+    fn select(&mut self, side: Side, id: String) -> Option<Effect> {
+        self.freezer = None;
+        self.id = Some(id);
+        self.fetch(side)
+    }
+}
+
+/// Two freezers side by side, shown by the app instead of the preview
+#[derive(Debug)]
+pub struct Compare {
+    open: bool,
+    /// Freezers to choose from, the loaded ones of the list
+    freezers: Vec<String>,
+    left: Panel,
+    right: Panel,
+    palette: Palette,
+
+    client: Arc<dyn FreezerApi>,
+}
+
+impl Compare {
+    pub fn new(client: Arc<dyn FreezerApi>) -> Self {
+        Self {
+            open: false,
+            freezers: Vec::new(),
+            left: Panel::default(),
+            right: Panel::default(),
+            palette: Palette::default(),
+            client,
+        }
+    }
+
+    pub const fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn set_freezers(&mut self, freezers: Vec<String>) {
+        self.freezers = freezers;
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    fn panel(&mut self, side: Side) -> &mut Panel {
+        match side {
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+        }
+    }
+
+    /// Applies the message to the state, side effects are returned to be performed later
+    pub fn reduce(&mut self, message: Message) -> Vec<Effect> {
+        match message {
+            Message::Open { left, right } => {
+                self.open = true;
+                [(Side::Left, left), (Side::Right, right)]
+                    .into_iter()
+                    .filter_map(|(side, id)| {
+                        let id = id.filter(|id| self.panel(side).id.as_ref() != Some(id))?;
+                        self.panel(side).select(side, id)
+                    })
+                    .collect()
+            }
+            Message::Close => {
+                self.left.task.cancel();
+                self.right.task.cancel();
+                self.open = false;
+                vec![]
+            }
+            Message::Select(side, id) => self.panel(side).select(side, id).into_iter().collect(),
+            Message::OnFetch(side, res) => match self.panel(side).task.accept(res) {
+                Some(Ok(freezer)) => {
+                    self.panel(side).freezer = Some(freezer);
+                    vec![]
+                }
+                Some(Err(error)) => vec![Effect::Error(error)],
+                None => vec![],
+            },
+            Message::Swap => {
+                mem::swap(&mut self.left, &mut self.right);
+                // responses in flight are tagged with the old side
+                [Side::Left, Side::Right]
+                    .into_iter()
+                    .filter_map(|side| {
+                        let panel = self.panel(side);
+                        if panel.task.is_running() {
+                            panel.fetch(side)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect()
+            }
+            Message::Refresh if !self.open => vec![],
+            Message::Refresh => [Side::Left, Side::Right]
+                .into_iter()
+                .filter_map(|side| self.panel(side).fetch(side))
+                .collect(),
+            Message::Error(_) => vec![],
+        }
+    }
+
+    pub fn perform(&self, effect: Effect) -> Command<Message> {
+        let client = self.client.clone();
+        match effect {
+            Effect::Fetch { side, id, ticket } => ticket
+                .perform(async move { client.freezer(&id).await }, move |res| {
+                    Message::OnFetch(side, res)
+                }),
+            Effect::Error(error) => Command::perform(async move { error }, Message::Error),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        fn cell<'a, T: Display>(
+            value: Option<&T>,
+            highlight: Option<Color>,
+        ) -> Element<'a, Message> {
+            let value = value.map_or_else(|| "-".to_owned(), ToString::to_string);
+            let cell = text(value).width(Length::FillPortion(3));
+            match highlight {
+                Some(color) => cell.style(color).into(),
+                None => cell.into(),
+            }
+        }
+        fn line<'a, T: Display + PartialEq>(
+            line: &Line<T>,
+            highlight: Color,
+        ) -> Element<'a, Message> {
+            let differs = line.differs().then_some(highlight);
+            row![
+                text(&line.label).width(Length::FillPortion(2)),
+                cell(line.left.as_ref(), differs),
+                cell(line.right.as_ref(), differs),
+            ]
+            .spacing(10)
+            .into()
+        }
+
+        let pick = |side: Side, panel: &Panel| {
+            pick_list(self.freezers.clone(), panel.id.clone(), move |id| {
+                Message::Select(side, id)
+            })
+            .placeholder(if panel.task.is_running() {
+                "fetching..."
+            } else {
+                "freezer"
+            })
+            .width(Length::FillPortion(3))
+        };
+        let header = row![
+            row![
+                button("swap").on_press(Message::Swap),
+                button("close").on_press(Message::Close),
+            ]
+            .spacing(5)
+            .width(Length::FillPortion(2)),
+            pick(Side::Left, &self.left),
+            pick(Side::Right, &self.right),
+        ]
+        .spacing(10);
+
+        let (Some(left), Some(right)) = (&self.left.freezer, &self.right.freezer) else {
+            return columee![header, text("choose two freezers to compare")]
+                .spacing(10)
+                .into();
+        };
+        let highlight = self.palette.warn;
+        let units = Line {
+            label: "units".to_owned(),
+            left: Some(left.units()),
+            right: Some(right.units()),
+        };
+
+        columee![
+            header,
+            column(
+                fields(left, right)
+                    .iter()
+                    .map(|field| line(field, highlight))
+                    .collect()
+            )
+            .spacing(5),
+            horizontal_rule(10),
+            text("PRODUCTS").size(40),
+            line(&units, highlight),
+            scrollable(
+                column(
+                    products(left, right)
+                        .iter()
+                        .map(|product| line(product, highlight))
+                        .collect()
+                )
+                .spacing(5)
+            )
+            .height(Length::Fill),
+        ]
+        .spacing(10)
+        .padding(10)
+        .into()
+    }
+}
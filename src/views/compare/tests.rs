@@ -0,0 +1,99 @@
+use super::{Compare, Effect, Line, Message, Side, fields, products};
+use crate::{
+    task::Ticket,
+    views::test_support::{client, freezer},
+};
+
+fn compare() -> Compare {
+    Compare::new(client())
+}
+
+fn fetched(effects: Vec<Effect>) -> Vec<(Side, String, Ticket)> {
+    effects
+        .into_iter()
+        .map(|effect| match effect {
+            Effect::Fetch { side, id, ticket } => (side, id, ticket),
+            effect => panic!("freezer is fetched: {effect:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn products_are_aligned() {
+    let left = freezer("kitchen", &[("pelmeni", 3), ("berries", 2)]);
+    let right = freezer("garage", &[("pelmeni", 3), ("ice-cream", 1)]);
+
+    let lines = products(&left, &right);
+    let line = |label: &str, left, right| Line {
+        label: label.to_owned(),
+        left,
+        right,
+    };
+    assert_eq!(
+        lines,
+        [
+            line("berries", Some(2), None),
+            line("ice-cream", None, Some(1)),
+            line("pelmeni", Some(3), Some(3)),
+        ]
+    );
+    assert_eq!(
+        lines.iter().map(Line::differs).collect::<Vec<_>>(),
+        [true, true, false]
+    );
+}
+
+#[test]
+fn model_fields_differ() {
+    let left = freezer("kitchen", &[]);
+    let mut right = freezer("garage", &[]);
+    right.model.year = 2018;
+    right.details.location = Some("garage".into());
+
+    let differ: Vec<_> = fields(&left, &right)
+        .into_iter()
+        .filter(Line::differs)
+        .map(|line| line.label)
+        .collect();
+    assert_eq!(differ, ["year", "location"]);
+}
+
+#[test]
+fn swap_keeps_responses() {
+    let mut compare = compare();
+    let mut effects = fetched(compare.reduce(Message::Open {
+        left: Some("kitchen".into()),
+        right: Some("garage".into()),
+    }));
+    assert!(compare.is_open());
+    let (_, _, garage) = effects.pop().unwrap();
+    let (side, id, kitchen) = effects.pop().unwrap();
+    assert_eq!((side, id.as_str()), (Side::Left, "kitchen"));
+
+    compare.reduce(Message::OnFetch(
+        Side::Left,
+        kitchen.resolve(Ok(freezer("kitchen", &[]))),
+    ));
+
+    // the garage is still fetched, again for its new side
+    let mut effects = fetched(compare.reduce(Message::Swap));
+    assert_eq!(effects.len(), 1);
+    let (side, id, fresh) = effects.pop().unwrap();
+    assert_eq!((side, id.as_str()), (Side::Left, "garage"));
+
+    compare.reduce(Message::OnFetch(
+        Side::Right,
+        garage.resolve(Ok(freezer("garage", &[]))),
+    ));
+    assert!(compare.left.freezer.is_none());
+    compare.reduce(Message::OnFetch(
+        Side::Left,
+        fresh.resolve(Ok(freezer("garage", &[]))),
+    ));
+    assert_eq!(compare.left.freezer.as_ref().unwrap().name, "garage");
+    assert_eq!(compare.right.freezer.as_ref().unwrap().name, "kitchen");
+
+    // both are fetched again, the shown ones are kept meanwhile
+    assert_eq!(compare.reduce(Message::Refresh).len(), 2);
+    assert!(compare.right.freezer.is_some());
+}
@@ -0,0 +1,210 @@
+use crate::{
+    api::FreezerApi,
+    columee, row,
+    stock::{self, Report, Stock},
+    task::{Tagged, Task, Ticket},
+    theme::{Palette, Tone},
+    utils::{Error, Result},
+};
+use iced::{Element, Length};
+use iced_aw::{Badge, Card};
+use iced_native::{
+    Command,
+    widget::helpers::{button, column, progress_bar, scrollable, text},
+};
+use std::sync::Arc;
+use tap::Pipe;
+
+#[cfg(test)]
+mod tests;
+
+/// Freezers requested at once while crawling
+const PARALLEL: usize = 8;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Open,
+    Close,
+    Refresh,
+    OnReport(Tagged<Result<Report>>),
+
+    Error(Error),
+}
+
+/// Side effect requested by the update, see [`Dashboard::perform`]
+#[derive(Debug)]
+pub enum Effect {
+    Report { ticket: Ticket },
+    Error(Error),
+}
+
+/// Stock across all freezers
+#[derive(Debug)]
+pub struct Dashboard {
+    open: bool,
+    report: Option<Report>,
+    task: Task,
+    palette: Palette,
+
+    client: Arc<dyn FreezerApi>,
+}
+
+impl Dashboard {
+    pub fn new(client: Arc<dyn FreezerApi>) -> Self {
+        Self {
+            open: false,
+            report: None,
+            task: Task::default(),
+            palette: Palette::default(),
+            client,
+        }
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    /// Applies the message to the state, side effect is returned to be performed later
+    pub fn reduce(&mut self, message: Message) -> Option<Effect> {
+        match message {
+            // the report is kept until explicit refresh - crawling is expensive
+            Message::Open if self.report.is_some() => {
+                self.open = true;
+                None
+            }
+            Message::Open => {
+                self.open = true;
+                self.reduce(Message::Refresh)
+            }
+            Message::Close => {
+                self.open = false;
+                self.task.cancel();
+                None
+            }
+            Message::Refresh => Some(Effect::Report {
+                ticket: self.task.begin(),
+            }),
+            Message::OnReport(res) => match self.task.accept(res)? {
+                Ok(report) => {
+                    self.report = Some(report);
+                    None
+                }
+                Err(error) => Some(Effect::Error(error)),
+            },
+            Message::Error(_) => None,
+        }
+    }
+
+    pub fn perform(&self, effect: Effect) -> Command<Message> {
+        match effect {
+            Effect::Report { ticket } => {
+                let client = self.client.clone();
+                ticket.perform(
+                    async move { stock::report(&*client, PARALLEL).await },
+                    Message::OnReport,
+                )
+            }
+            Effect::Error(error) => Command::perform(async move { error }, Message::Error),
+        }
+    }
+
+    fn stock<'a>(stock: &[Stock], palette: &Palette) -> Element<'a, Message> {
+        let line = |stock: &Stock| {
+            let Stock {
+                product,
+                units,
+                default,
+            } = stock;
+            let default =
+                default.map_or_else(|| "unknown".to_owned(), |default| default.to_string());
+
+            Badge::new(text(format!("{product}: {units} (default {default})")))
+                .style(palette.badge(if stock.is_low() {
+                    Tone::Danger
+                } else {
+                    Tone::Good
+                }))
+                .into()
+        };
+        column(stock.iter().map(line).collect()).spacing(5).into()
+    }
+
+    fn freezers<'a>(freezers: &[(String, usize)]) -> Element<'a, Message> {
+        let max = freezers.first().map_or(0, |(_, units)| *units).max(1);
+
+        #[allow(clippy::cast_precision_loss)]
+        let line = |(name, units): &(String, usize)| {
+            row![
+                text(name).width(Length::Units(150)),
+                progress_bar(0.0..=max as f32, *units as f32).width(Length::Fill),
+                text(units.to_string()).width(Length::Units(60)),
+            ]
+            .spacing(10)
+            .into()
+        };
+        column(freezers.iter().map(line).collect())
+            .spacing(5)
+            .into()
+    }
+
+    /// Dashboard dialog, shown by the app over all panes
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        if !self.open {
+            return None;
+        }
+
+        let refresh = button(text("REFRESH")).pipe(|button| {
+            if self.task.is_running() {
+                button
+            } else {
+                button.on_press(Message::Refresh)
+            }
+        });
+
+        let body: Element<_> = match &self.report {
+            Some(Report {
+                stock,
+                freezers,
+                crawled,
+            }) => {
+                let low = stock.iter().filter(|stock| stock.is_low()).count();
+                let source = if *crawled {
+                    "collected from every freezer"
+                } else {
+                    "aggregated by the server"
+                };
+                columee![
+                    text(format!("{low} products are below their default, {source}")),
+                    row![
+                        columee![text("PRODUCTS").size(30), Self::stock(stock, &self.palette)]
+                            .width(Length::FillPortion(1)),
+                        columee![text("FREEZERS").size(30), Self::freezers(freezers)]
+                            .width(Length::FillPortion(2)),
+                    ]
+                    .spacing(20)
+                    .pipe(scrollable)
+                    .height(Length::Fill),
+                ]
+                .spacing(10)
+                .into()
+            }
+            None => text("collecting the stock...").into(),
+        };
+
+        let status = if self.task.is_running() && self.report.is_some() {
+            "refreshing..."
+        } else {
+            ""
+        };
+
+        Some(
+            Card::new(
+                row![text("Dashboard"), refresh, text(status)].spacing(10),
+                body,
+            )
+            .max_width(1200)
+            .on_close(Message::Close)
+            .into(),
+        )
+    }
+}
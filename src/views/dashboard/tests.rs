@@ -0,0 +1,71 @@
+use super::{Dashboard, Effect, Message};
+use crate::{
+    stock::{Report, Stock},
+    utils::error::anyio,
+    views::test_support::client,
+};
+
+fn dashboard() -> Dashboard {
+    Dashboard::new(client())
+}
+
+fn report() -> Report {
+    Report {
+        stock: vec![Stock {
+            product: "pelmeni".into(),
+            units: 4,
+            default: Some(5),
+        }],
+        freezers: vec![("kitchen".into(), 4)],
+        crawled: true,
+    }
+}
+
+#[test]
+fn report_is_kept() {
+    let mut dashboard = dashboard();
+
+    let Some(Effect::Report { ticket }) = dashboard.reduce(Message::Open) else {
+        panic!("report is requested");
+    };
+    assert!(
+        dashboard
+            .reduce(Message::OnReport(ticket.resolve(Ok(report()))))
+            .is_none()
+    );
+    assert!(dashboard.report.is_some());
+
+    dashboard.reduce(Message::Close);
+    assert!(dashboard.reduce(Message::Open).is_none());
+    assert!(matches!(
+        dashboard.reduce(Message::Refresh),
+        Some(Effect::Report { .. })
+    ));
+}
+
+#[test]
+fn closed_while_collecting() {
+    let mut dashboard = dashboard();
+
+    let Some(Effect::Report { ticket }) = dashboard.reduce(Message::Open) else {
+        panic!("report is requested");
+    };
+    dashboard.reduce(Message::Close);
+    assert!(
+        dashboard
+            .reduce(Message::OnReport(ticket.resolve(Ok(report()))))
+            .is_none()
+    );
+    assert!(dashboard.report.is_none());
+}
+
+#[test]
+fn failed_report() {
+    let mut dashboard = dashboard();
+
+    let Some(Effect::Report { ticket }) = dashboard.reduce(Message::Refresh) else {
+        panic!("report is requested");
+    };
+    let effect = dashboard.reduce(Message::OnReport(ticket.resolve(Err(anyio!("oops")))));
+    assert!(matches!(effect, Some(Effect::Error(_))));
+}
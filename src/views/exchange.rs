@@ -0,0 +1,431 @@
+use crate::{
+    api::FreezerApi,
+    columee,
+    inventory::{self, Change, Format, Plan},
+    model::{Account, Permission},
+    row,
+    task::{Tagged, Task, Ticket},
+    utils::{Error, Result, error::anyio},
+};
+use iced::{Element, Length};
+use iced_aw::Card;
+use iced_native::{
+    Command,
+    widget::helpers::{button, column, progress_bar, scrollable, text, text_input},
+};
+use std::{collections::VecDeque, path::PathBuf, sync::Arc};
+use tap::Pipe;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Open,
+    Close,
+    InputPath(String),
+
+    StartExport,
+    OnExport(Tagged<Result<usize>>),
+
+    /// Dry run of the import
+    StartPlan,
+    OnPlan(Tagged<Result<Plan>>),
+
+    StartApply,
+    OnApply(Tagged<Result<()>>),
+
+    Cancel,
+
+    /// Freezers are changed by the import, the list is outdated
+    Imported,
+
+    Error(Error),
+    Warn(Error),
+    Info(String),
+}
+
+/// Side effect requested by the update, see [`Exchange::perform`]
+#[derive(Debug)]
+pub enum Effect {
+    Export {
+        path: PathBuf,
+        format: Format,
+        ticket: Ticket,
+    },
+    Plan {
+        path: PathBuf,
+        format: Format,
+        ticket: Ticket,
+    },
+    Apply {
+        change: Change,
+        ticket: Ticket,
+    },
+    Imported,
+    Error(Error),
+    Warn(Error),
+    Info(String),
+}
+
+#[derive(Debug)]
+enum Stage {
+    Idle,
+    Exporting,
+    Planning,
+    Planned(Plan),
+    Applying {
+        pending: VecDeque<Change>,
+        /// freezer of the change in flight
+        current: String,
+        done: usize,
+        failed: usize,
+        total: usize,
+    },
+}
+
+/// Import and export of the whole inventory as JSON or CSV
+#[derive(Debug)]
+pub struct Exchange {
+    open: bool,
+    path: String,
+    stage: Stage,
+    task: Task,
+    /// Logged in user, nothing is imported without [`Permission::Update`]
+    account: Account,
+
+    client: Arc<dyn FreezerApi>,
+}
+
+impl Exchange {
+    pub fn new(client: Arc<dyn FreezerApi>) -> Self {
+        Self {
+            open: false,
+            path: String::new(),
+            stage: Stage::Idle,
+            task: Task::default(),
+            account: Account::unknown(""),
+            client,
+        }
+    }
+
+    pub fn set_account(&mut self, account: Account) {
+        self.account = account;
+    }
+
+    pub const fn is_open(&self) -> bool {
+        self.open
+    }
+
+    const fn is_busy(&self) -> bool {
+        matches!(
+            self.stage,
+            Stage::Exporting | Stage::Planning | Stage::Applying { .. }
+        )
+    }
+
+    fn target(&self) -> Result<(PathBuf, Format)> {
+        let path = PathBuf::from(&self.path);
+        let format = Format::from_path(&path)
+            .ok_or_else(|| anyio!("unknown format of `{}` - use `.json` or `.csv`", self.path))?;
+        Ok((path, format))
+    }
+
+    /// Sends the next change of the import or reports the summary
+    fn next(&mut self) -> Vec<Effect> {
+        let Stage::Applying {
+            pending,
+            current,
+            done,
+            failed,
+            total,
+        } = &mut self.stage
+        else {
+            return vec![];
+        };
+
+        if let Some(change) = pending.pop_front() {
+            *current = change.freezer().name.clone();
+            return vec![Effect::Apply {
+                change,
+                ticket: self.task.begin(),
+            }];
+        }
+
+        let summary = format!("imported {done} of {total} freezers, {failed} failed");
+        self.stage = Stage::Idle;
+        vec![Effect::Info(summary), Effect::Imported]
+    }
+
+    /// Applies the message to the state, side effects are returned to be performed later
+    pub fn reduce(&mut self, message: Message) -> Vec<Effect> {
+        match message {
+            Message::Open => {
+                self.open = true;
+                vec![]
+            }
+            Message::Close => {
+                let effects = self.reduce(Message::Cancel);
+                self.open = false;
+                effects
+            }
+            Message::InputPath(path) => {
+                self.path = path;
+                vec![]
+            }
+            Message::StartExport | Message::StartPlan if self.is_busy() => vec![],
+            Message::StartExport => match self.target() {
+                Ok((path, format)) => {
+                    self.stage = Stage::Exporting;
+                    vec![Effect::Export {
+                        path,
+                        format,
+                        ticket: self.task.begin(),
+                    }]
+                }
+                Err(error) => vec![Effect::Error(error)],
+            },
+            Message::StartPlan => match self.target() {
+                Ok((path, format)) => {
+                    self.stage = Stage::Planning;
+                    vec![Effect::Plan {
+                        path,
+                        format,
+                        ticket: self.task.begin(),
+                    }]
+                }
+                Err(error) => vec![Effect::Error(error)],
+            },
+            Message::OnExport(res) => match self.task.accept(res) {
+                Some(res) => {
+                    self.stage = Stage::Idle;
+                    match res {
+                        Ok(count) => vec![Effect::Info(format!(
+                            "exported {count} freezers to `{}`",
+                            self.path
+                        ))],
+                        Err(error) => vec![Effect::Error(error)],
+                    }
+                }
+                None => vec![],
+            },
+            Message::OnPlan(res) => match self.task.accept(res) {
+                Some(Ok(plan)) => {
+                    let effects = plan.errors.iter().cloned().map(Effect::Warn).collect();
+                    self.stage = Stage::Planned(plan);
+                    effects
+                }
+                Some(Err(error)) => {
+                    self.stage = Stage::Idle;
+                    vec![Effect::Error(error)]
+                }
+                None => vec![],
+            },
+            Message::StartApply if !self.account.can(Permission::Update) => {
+                vec![Effect::Warn(anyio!(
+                    "`{}` may not import freezers",
+                    self.account.login
+                ))]
+            }
+            Message::StartApply => {
+                let Stage::Planned(plan) = &mut self.stage else {
+                    return vec![];
+                };
+                let pending: VecDeque<_> = plan.changes.drain(..).collect();
+                self.stage = Stage::Applying {
+                    total: pending.len(),
+                    pending,
+                    current: String::new(),
+                    done: 0,
+                    failed: 0,
+                };
+                self.next()
+            }
+            Message::OnApply(res) => {
+                let Some(res) = self.task.accept(res) else {
+                    return vec![];
+                };
+                let Stage::Applying { done, failed, .. } = &mut self.stage else {
+                    return vec![];
+                };
+
+                let mut effects = match res {
+                    Ok(()) => {
+                        *done += 1;
+                        vec![]
+                    }
+                    Err(error) => {
+                        *failed += 1;
+                        vec![Effect::Warn(error)]
+                    }
+                };
+                effects.extend(self.next());
+                effects
+            }
+            Message::Cancel => {
+                self.task.cancel();
+                match std::mem::replace(&mut self.stage, Stage::Idle) {
+                    Stage::Applying { done, total, .. } => vec![
+                        Effect::Info(format!(
+                            "import is cancelled after {done} of {total} freezers"
+                        )),
+                        Effect::Imported,
+                    ],
+                    // the dry run stays to be applied
+                    Stage::Planned(plan) => {
+                        self.stage = Stage::Planned(plan);
+                        vec![]
+                    }
+                    _ => vec![],
+                }
+            }
+            Message::Imported | Message::Error(_) | Message::Warn(_) | Message::Info(_) => vec![],
+        }
+    }
+
+    pub fn perform(&self, effect: Effect) -> Command<Message> {
+        let client = self.client.clone();
+        match effect {
+            Effect::Export {
+                path,
+                format,
+                ticket,
+            } => ticket.perform(
+                async move {
+                    let freezers = inventory::export(&*client).await?;
+                    let bytes = inventory::write(format, &inventory::rows(&freezers))?;
+                    tokio::fs::write(path, bytes).await?;
+                    Ok(freezers.len())
+                },
+                Message::OnExport,
+            ),
+            Effect::Plan {
+                path,
+                format,
+                ticket,
+            } => ticket.perform(
+                async move {
+                    let bytes = tokio::fs::read(path).await?;
+                    let (freezers, errors) = inventory::parse(format, &bytes)?;
+                    let mut plan = inventory::plan(&*client, freezers).await?;
+                    // rows go first, they are in the file order
+                    plan.errors.splice(0..0, errors);
+                    Ok(plan)
+                },
+                Message::OnPlan,
+            ),
+            Effect::Apply { change, ticket } => ticket.perform(
+                async move { inventory::apply(&*client, change).await },
+                Message::OnApply,
+            ),
+            Effect::Imported => Command::perform(async {}, |_| Message::Imported),
+            Effect::Error(error) => Command::perform(async move { error }, Message::Error),
+            Effect::Warn(error) => Command::perform(async move { error }, Message::Warn),
+            Effect::Info(info) => Command::perform(async move { info }, Message::Info),
+        }
+    }
+
+    fn stage(&self) -> Element<'_, Message> {
+        match &self.stage {
+            Stage::Idle => text("").into(),
+            Stage::Exporting => text("exporting...").into(),
+            Stage::Planning => text("checking the import...").into(),
+            Stage::Planned(Plan {
+                changes,
+                unchanged,
+                errors,
+            }) => {
+                let creates = changes
+                    .iter()
+                    .filter(|change| matches!(change, Change::Create(_)))
+                    .count();
+                let summary = format!(
+                    "{creates} to create, {} to update, {unchanged} unchanged, {} skipped - \
+                     see the log",
+                    changes.len() - creates,
+                    errors.len(),
+                );
+                let diff = changes
+                    .iter()
+                    .flat_map(Change::diff)
+                    .map(|line| text(line).into())
+                    .collect();
+                columee![text(summary), scrollable(column(diff)).height(Length::Fill)]
+                    .spacing(10)
+                    .into()
+            }
+            Stage::Applying {
+                current,
+                done,
+                failed,
+                total,
+                ..
+            } => {
+                let processed = done + failed;
+                #[allow(clippy::cast_precision_loss)]
+                let bar = progress_bar(0.0..=*total as f32, processed as f32);
+                columee![
+                    bar,
+                    text(format!(
+                        "{processed}/{total}, {failed} failed - `{current}`"
+                    ))
+                ]
+                .spacing(10)
+                .into()
+            }
+        }
+    }
+
+    /// Import/export dialog, shown by the app over all panes
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        if !self.open {
+            return None;
+        }
+
+        let action = |label, message| {
+            button(text(label)).pipe(|button| {
+                if self.is_busy() {
+                    button
+                } else {
+                    button.on_press(message)
+                }
+            })
+        };
+        let can_apply = self.account.can(Permission::Update)
+            && matches!(&self.stage, Stage::Planned(plan) if !plan.changes.is_empty());
+
+        let actions = row![
+            action("EXPORT", Message::StartExport),
+            action("DRY RUN", Message::StartPlan),
+            button(text("APPLY")).pipe(|button| {
+                if can_apply {
+                    button.on_press(Message::StartApply)
+                } else {
+                    button
+                }
+            }),
+            button(text("CANCEL")).pipe(|button| {
+                if self.is_busy() {
+                    button.on_press(Message::Cancel)
+                } else {
+                    button
+                }
+            }),
+        ]
+        .spacing(10);
+
+        let body = columee![
+            text_input("path to `.json` or `.csv`", &self.path, Message::InputPath),
+            actions,
+            self.stage(),
+        ]
+        .spacing(10);
+
+        Some(
+            Card::new(text("Import / Export"), body)
+                .max_width(900)
+                .on_close(Message::Close)
+                .into(),
+        )
+    }
+}
@@ -0,0 +1,141 @@
+use super::{Effect, Exchange, Message, Stage};
+use crate::{
+    inventory::{Change, Plan},
+    model::Account,
+    task::Ticket,
+    utils::error::anyio,
+    views::test_support::{client, freezer, ticket},
+};
+
+fn exchange(path: &str) -> Exchange {
+    let mut exchange = Exchange::new(client());
+    exchange.reduce(Message::Open);
+    exchange.reduce(Message::InputPath(path.to_owned()));
+    exchange
+}
+
+fn change(name: &str) -> Change {
+    Change::Create(freezer(name, &[]))
+}
+
+/// Dry run that results in `plan`
+fn planned(exchange: &mut Exchange, plan: Plan) -> Vec<Effect> {
+    let Some(Effect::Plan { ticket, .. }) = exchange.reduce(Message::StartPlan).pop() else {
+        panic!("dry run is requested");
+    };
+    exchange.reduce(Message::OnPlan(ticket.resolve(Ok(plan))))
+}
+
+fn apply_ticket(effects: Vec<Effect>) -> Ticket {
+    ticket!(effects, Effect::Apply { ticket, .. } => ticket)
+}
+
+#[test]
+fn unknown_format() {
+    let mut exchange = exchange("dump.txt");
+
+    assert!(matches!(
+        &exchange.reduce(Message::StartExport)[..],
+        [Effect::Error(_)]
+    ));
+    assert!(matches!(
+        &exchange.reduce(Message::StartPlan)[..],
+        [Effect::Error(_)]
+    ));
+    assert!(!exchange.is_busy());
+}
+
+#[test]
+fn dry_run_reports_rows() {
+    let mut exchange = exchange("dump.csv");
+
+    let effects = planned(
+        &mut exchange,
+        Plan {
+            changes: vec![change("attic")],
+            unchanged: 0,
+            errors: vec![anyio!("row 2: empty freezer id"), anyio!("row 5: oops")],
+        },
+    );
+    assert!(matches!(&effects[..], [Effect::Warn(_), Effect::Warn(_)]));
+    assert!(matches!(&exchange.stage, Stage::Planned(plan) if plan.changes.len() == 1));
+}
+
+#[test]
+fn apply_in_order() {
+    let mut exchange = exchange("dump.json");
+    planned(
+        &mut exchange,
+        Plan {
+            changes: vec![change("attic"), change("cellar")],
+            ..Plan::default()
+        },
+    );
+
+    let first = apply_ticket(exchange.reduce(Message::StartApply));
+    assert!(
+        matches!(&exchange.stage, Stage::Applying { current, total: 2, .. } if current == "attic")
+    );
+
+    // failure of a row doesn't stop the import
+    let effects = exchange.reduce(Message::OnApply(first.resolve(Err(anyio!("refused")))));
+    assert!(matches!(effects[0], Effect::Warn(_)));
+    assert!(
+        matches!(&exchange.stage, Stage::Applying { current, failed: 1, .. } if current == "cellar")
+    );
+
+    let second = apply_ticket(effects);
+    let effects = exchange.reduce(Message::OnApply(second.resolve(Ok(()))));
+    assert!(matches!(
+        &effects[..],
+        [Effect::Info(summary), Effect::Imported] if summary == "imported 1 of 2 freezers, 1 failed"
+    ));
+    assert!(!exchange.is_busy());
+}
+
+#[test]
+fn read_only_account() {
+    let mut exchange = exchange("dump.json");
+    exchange.set_account(Account {
+        permissions: Some([].into()),
+        ..Account::unknown("guest")
+    });
+    planned(
+        &mut exchange,
+        Plan {
+            changes: vec![change("attic")],
+            ..Plan::default()
+        },
+    );
+
+    assert!(matches!(
+        &exchange.reduce(Message::StartApply)[..],
+        [Effect::Warn(error)] if error.to_string() == "`guest` may not import freezers"
+    ));
+    // the dry run stays
+    assert!(matches!(&exchange.stage, Stage::Planned(plan) if plan.changes.len() == 1));
+}
+
+#[test]
+fn cancel_import() {
+    let mut exchange = exchange("dump.json");
+    planned(
+        &mut exchange,
+        Plan {
+            changes: vec![change("attic"), change("cellar")],
+            ..Plan::default()
+        },
+    );
+
+    let ticket = apply_ticket(exchange.reduce(Message::StartApply));
+    let effects = exchange.reduce(Message::Close);
+    assert!(matches!(&effects[..], [Effect::Info(_), Effect::Imported]));
+    assert!(!exchange.is_open());
+
+    // response of the cancelled change is dropped
+    assert!(
+        exchange
+            .reduce(Message::OnApply(ticket.resolve(Ok(()))))
+            .is_empty()
+    );
+}
@@ -1,13 +1,27 @@
-use crate::{columee, utils::Result, Client};
+use crate::{
+    api::FreezerApi,
+    columee,
+    model::Account,
+    owners::Owner,
+    row,
+    task::{Tagged, Task, Ticket},
+    utils::Result,
+    views::bulk::Action,
+};
 use iced::{
     alignment::{Horizontal, Vertical},
-    Element, Length, Renderer, Theme,
+    keyboard::{self, Modifiers},
+    subscription, theme, Element, Event, Length, Renderer, Subscription, Theme,
 };
 use iced_native::Command;
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
+use tap::Pipe;
 
 use crate::utils::Error;
-use iced_native::widget::helpers::{button, column, container, scrollable, text};
+use iced_native::widget::helpers::{button, column, container, pick_list, scrollable, text};
+
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug, PartialEq)]
 enum State {
@@ -15,15 +29,69 @@ enum State {
     Ready,
 }
 
+/// Freezers shown by the list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    All,
+    /// Owned by the logged in user
+    Mine,
+    Unowned,
+    Owner(String),
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     /// `update` happens, but we do nothing
     Silent,
 
+    Filter(Filter),
+    SelectAssignee(Owner),
+
+    Modifiers(Modifiers),
+    /// Selects the freezer and shows it in the preview,
+    /// ctrl-click toggles it and shift-click selects the range from the last click
+    Click(usize),
+    /// Selects every shown freezer
+    SelectAll,
+    SelectNone,
+    /// Requests the action over the selected freezers
+    StartBulk(Action),
+    /// The action is confirmed and run by the app
+    Bulk {
+        ids: Vec<String>,
+        action: Action,
+    },
+    /// Shown freezers are changed elsewhere
+    Reload,
+
     Ping(String),
-    PageAdd(Result<Vec<String>>),
+    /// Freezer got a new id, it keeps its place in the list
+    Renamed {
+        old: String,
+        new: String,
+    },
+    PageAdd(Tagged<Result<Vec<String>>>),
     ScrollListEnd,
 
+    Info(String),
+    Error(Error),
+}
+
+/// Side effect requested by the update, see [`List::perform`]
+#[derive(Debug)]
+pub enum Effect {
+    FetchPage {
+        offset: usize,
+        /// `None` for every freezer
+        owner: Option<Owner>,
+        ticket: Ticket,
+    },
+    Ping(String),
+    Bulk {
+        ids: Vec<String>,
+        action: Action,
+    },
+    Info(String),
     Error(Error),
 }
 
@@ -34,60 +102,296 @@ const PAGE: usize = 30;
 pub struct List {
     state: State,
     freezers: Vec<String>,
+    filter: Filter,
+    /// Pages of the filter, superseded when it changes
+    pages: Task,
+    /// Logged in user: the owner of [`Filter::Mine`], bulk actions it may not do are disabled
+    account: Account,
+    /// Candidates for owners, see [`owners::candidates`]
+    owners: Vec<String>,
+    assignee: Option<Owner>,
+    selected: HashSet<String>,
+    /// Last clicked freezer, the start of the shift-click range
+    anchor: Option<usize>,
+    modifiers: Modifiers,
 
-    client: Arc<Client>,
+    client: Arc<dyn FreezerApi>,
 }
 
 impl List {
-    async fn freezers_list(client: Arc<Client>, count: usize) -> Result<Vec<String>> {
-        client.freezers_by(PAGE, count).await
+    async fn freezers_list(
+        client: Arc<dyn FreezerApi>,
+        owner: Option<Owner>,
+        count: usize,
+    ) -> Result<Vec<String>> {
+        match owner {
+            Some(owner) => client.freezers_owned(&owner, Some(PAGE), Some(count)).await,
+            None => client.freezers_by(Some(PAGE), Some(count)).await,
+        }
     }
 
-    pub fn new(client: Arc<Client>) -> (Self, Command<Message>) {
-        (
-            Self {
-                state: State::Loading,
-                freezers: vec![],
-                client: client.clone(),
-            },
-            Command::perform(
-                async move { Self::freezers_list(client, 0).await },
-                Message::PageAdd,
-            ),
-        )
+    /// Returns the list of every freezer with the request of the first page
+    pub fn new(client: Arc<dyn FreezerApi>, account: Account) -> (Self, Effect) {
+        let mut list = Self {
+            state: State::Loading,
+            freezers: vec![],
+            filter: Filter::All,
+            pages: Task::default(),
+            account,
+            owners: vec![],
+            assignee: None,
+            selected: HashSet::new(),
+            anchor: None,
+            modifiers: Modifiers::empty(),
+            client,
+        };
+        let effect = list.reload();
+        (list, effect)
+    }
+
+    /// Loaded freezers in the order of the list
+    pub fn freezers(&self) -> &[String] {
+        &self.freezers
     }
 
-    pub fn update(&mut self, message: Message) -> Command<Message> {
+    /// Selected freezers in the order of the list
+    pub fn selected(&self) -> Vec<String> {
+        self.freezers
+            .iter()
+            .filter(|id| self.selected.contains(*id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn set_owners(&mut self, owners: Vec<String>) {
+        self.owners = owners;
+    }
+
+    fn permits(&self, action: &Action) -> bool {
+        action
+            .permission()
+            .map_or(true, |permission| self.account.can(permission))
+    }
+
+    /// Owner filter of the server, `None` for every freezer
+    fn owner(&self) -> Option<Owner> {
+        match &self.filter {
+            Filter::All => None,
+            Filter::Mine => Some(Owner::User(self.account.login.clone())),
+            Filter::Unowned => Some(Owner::Nobody),
+            Filter::Owner(owner) => Some(Owner::User(owner.clone())),
+        }
+    }
+
+    /// Drops the shown freezers and requests the first page again
+    fn reload(&mut self) -> Effect {
+        self.freezers.clear();
+        self.selected.clear();
+        self.anchor = None;
+        self.state = State::Loading;
+        Effect::FetchPage {
+            offset: 0,
+            owner: self.owner(),
+            ticket: self.pages.begin(),
+        }
+    }
+
+    /// Applies the message to the state, side effects are returned to be performed later
+    pub fn reduce(&mut self, message: Message) -> Vec<Effect> {
         match message {
-            Message::PageAdd(freezers) => match freezers {
-                Ok(freezers) => {
+            Message::PageAdd(freezers) => match self.pages.accept(freezers) {
+                Some(Ok(freezers)) => {
                     self.freezers.extend(freezers);
                     self.state = State::Ready;
-                    Command::none()
+                    vec![]
                 }
-                Err(error) => Command::perform(async move { error }, Message::Error),
+                Some(Err(error)) => {
+                    self.state = State::Ready;
+                    vec![Effect::Error(error)]
+                }
+                None => vec![],
             },
+            Message::Renamed { old, new } => {
+                if self.selected.remove(&old) {
+                    self.selected.insert(new.clone());
+                }
+                if let Some(id) = self.freezers.iter_mut().find(|id| **id == old) {
+                    *id = new;
+                }
+                vec![]
+            }
             Message::ScrollListEnd => {
                 if self.state == State::Loading {
-                    Command::none()
+                    vec![]
                 } else {
                     self.state = State::Loading;
-                    let client = Arc::clone(&self.client);
-                    let count = self.freezers.len();
-                    Command::perform(
-                        async move { Self::freezers_list(client, count).await },
-                        Message::PageAdd,
-                    )
+                    vec![Effect::FetchPage {
+                        offset: self.freezers.len(),
+                        owner: self.owner(),
+                        ticket: self.pages.begin(),
+                    }]
+                }
+            }
+            Message::Filter(filter) if filter == self.filter => vec![],
+            Message::Filter(filter) => {
+                self.filter = filter;
+                vec![self.reload()]
+            }
+            Message::SelectAssignee(owner) => {
+                self.assignee = Some(owner);
+                vec![]
+            }
+            Message::Modifiers(modifiers) => {
+                self.modifiers = modifiers;
+                vec![]
+            }
+            Message::Click(index) => {
+                let Some(id) = self.freezers.get(index).cloned() else {
+                    return vec![];
+                };
+                if self.modifiers.shift() {
+                    let anchor = self.anchor.unwrap_or(index);
+                    let range = anchor.min(index)..=anchor.max(index);
+                    self.selected = self.freezers[range].iter().cloned().collect();
+                    vec![]
+                } else if self.modifiers.command() {
+                    if !self.selected.remove(&id) {
+                        self.selected.insert(id);
+                    }
+                    self.anchor = Some(index);
+                    vec![]
+                } else {
+                    self.selected = HashSet::from([id.clone()]);
+                    self.anchor = Some(index);
+                    vec![Effect::Ping(id)]
                 }
             }
-            _ => Command::none(),
+            Message::SelectAll => {
+                self.selected = self.freezers.iter().cloned().collect();
+                vec![]
+            }
+            Message::SelectNone => {
+                self.selected.clear();
+                self.anchor = None;
+                vec![]
+            }
+            Message::StartBulk(action) if !self.permits(&action) => vec![],
+            Message::StartBulk(action) => {
+                let ids = self.selected();
+                if ids.is_empty() {
+                    vec![]
+                } else {
+                    vec![Effect::Bulk { ids, action }]
+                }
+            }
+            // changed freezers may leave the filter
+            Message::Reload => vec![self.reload()],
+            Message::Silent
+            | Message::Bulk { .. }
+            | Message::Ping(_)
+            | Message::Info(_)
+            | Message::Error(_) => vec![],
         }
     }
 
+    pub fn perform(&self, effect: Effect) -> Command<Message> {
+        let client = Arc::clone(&self.client);
+        match effect {
+            Effect::FetchPage {
+                offset,
+                owner,
+                ticket,
+            } => ticket.perform(Self::freezers_list(client, owner, offset), Message::PageAdd),
+            Effect::Ping(id) => Command::perform(async move { id }, Message::Ping),
+            Effect::Bulk { ids, action } => {
+                Command::perform(async move { (ids, action) }, |(ids, action)| {
+                    Message::Bulk { ids, action }
+                })
+            }
+            Effect::Info(info) => Command::perform(async move { info }, Message::Info),
+            Effect::Error(error) => Command::perform(async move { error }, Message::Error),
+        }
+    }
+
+    /// Keyboard modifiers of the clicks
+    pub fn subscription() -> Subscription<Message> {
+        subscription::events_with(|event, _| match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::Modifiers(modifiers))
+            }
+            _ => None,
+        })
+    }
+
+    fn filters(&self) -> Element<'_, Message, Renderer<Theme>> {
+        let filter = |label, filter: Filter| {
+            button(text(label)).pipe(|button| {
+                if self.filter == filter {
+                    button
+                } else {
+                    button.on_press(Message::Filter(filter))
+                }
+            })
+        };
+        let owner = match &self.filter {
+            Filter::Owner(owner) => Some(owner.clone()),
+            _ => None,
+        };
+
+        let assignees: Vec<_> = std::iter::once(Owner::Nobody)
+            .chain(self.owners.iter().cloned().map(Owner::User))
+            .collect();
+        // disabled without the selection or the permission
+        let action = |label, action: Option<Action>| {
+            button(text(label)).pipe(|button| match action {
+                Some(action) if !self.selected.is_empty() && self.permits(&action) => {
+                    button.on_press(Message::StartBulk(action))
+                }
+                _ => button,
+            })
+        };
+        let assign = self.assignee.clone().map(Action::Owner);
+
+        columee![
+            row![
+                filter("all", Filter::All),
+                filter("mine", Filter::Mine),
+                filter("unowned", Filter::Unowned),
+                pick_list(self.owners.clone(), owner, |owner| {
+                    Message::Filter(Filter::Owner(owner))
+                })
+                .placeholder("owner"),
+            ]
+            .spacing(5),
+            row![
+                text(format!("{} selected", self.selected.len())),
+                button(text("all")).on_press(Message::SelectAll),
+                button(text("none")).on_press(Message::SelectNone),
+            ]
+            .spacing(5),
+            row![
+                action("DELETE", Some(Action::Delete)),
+                action("EXPORT", Some(Action::Export)),
+                pick_list(assignees, self.assignee.clone(), Message::SelectAssignee)
+                    .placeholder("owner"),
+                action("ASSIGN", assign),
+            ]
+            .spacing(5),
+        ]
+        .spacing(5)
+        .into()
+    }
+
     pub fn view(&self) -> Element<'_, Message, Renderer<Theme>> {
-        let line = |freezer: String| {
-            button(text(freezer.clone()))
-                .on_press(Message::Ping(freezer))
+        let line = |(index, freezer): (usize, &String)| {
+            let style = if self.selected.contains(freezer) {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            };
+            button(text(freezer))
+                .style(style)
+                .on_press(Message::Click(index))
                 .height(Length::Units(50))
                 .width(Length::Fill)
                 .into()
@@ -103,11 +407,12 @@ impl List {
             }
         }
 
-        let list = scrollable(column(self.freezers.iter().cloned().map(line).collect()).spacing(5))
-            .height(Length::FillPortion(30))
-            .scrollbar_width(0)
-            .scroller_width(5)
-            .on_scroll(on_full_scroll);
+        let list =
+            scrollable(column(self.freezers.iter().enumerate().map(line).collect()).spacing(5))
+                .height(Length::FillPortion(30))
+                .scrollbar_width(0)
+                .scroller_width(5)
+                .on_scroll(on_full_scroll);
 
         let head_inner: Element<Message> = if self.state == State::Loading {
             text("LOADING BITCH...")
@@ -122,6 +427,6 @@ impl List {
             .height(Length::Fill)
             .width(Length::Fill);
 
-        columee![head, list].into()
+        columee![self.filters(), head, list].into()
     }
 }
@@ -0,0 +1,220 @@
+use super::{Effect, Filter, List, Message, PAGE, State};
+use crate::{
+    model::{Account, Permission},
+    owners::Owner,
+    task::Ticket,
+    utils::error::anyio,
+    views::{bulk::Action, test_support::client},
+};
+use iced::keyboard::Modifiers;
+
+fn list() -> (List, Effect) {
+    List::new(client(), Account::unknown("admin"))
+}
+
+fn page(from: usize, len: usize) -> Vec<String> {
+    (from..from + len).map(|i| format!("freezer-{i}")).collect()
+}
+
+fn page_ticket(effect: Effect) -> Ticket {
+    match effect {
+        Effect::FetchPage { ticket, .. } => ticket,
+        effect => panic!("page is requested: {effect:?}"),
+    }
+}
+
+/// First page of the new list is loaded
+fn loaded(len: usize) -> List {
+    let (mut list, effect) = list();
+    list.reduce(Message::PageAdd(
+        page_ticket(effect).resolve(Ok(page(0, len))),
+    ));
+    list
+}
+
+#[test]
+fn paging() {
+    let (mut list, effect) = list();
+    assert!(matches!(
+        effect,
+        Effect::FetchPage {
+            offset: 0,
+            owner: None,
+            ..
+        }
+    ));
+
+    let ticket = page_ticket(effect);
+    assert!(
+        list.reduce(Message::PageAdd(ticket.resolve(Ok(page(0, PAGE)))))
+            .is_empty()
+    );
+    assert_eq!(list.state, State::Ready);
+
+    let mut effects = list.reduce(Message::ScrollListEnd);
+    assert!(matches!(&effects[..], [Effect::FetchPage { offset, .. }] if *offset == PAGE));
+
+    let ticket = page_ticket(effects.remove(0));
+    list.reduce(Message::PageAdd(ticket.resolve(Ok(page(PAGE, 3)))));
+    assert_eq!(list.freezers.len(), PAGE + 3);
+    assert_eq!(list.freezers[PAGE], "freezer-30");
+}
+
+#[test]
+fn scroll_while_loading() {
+    let (mut list, effect) = list();
+
+    assert!(list.reduce(Message::ScrollListEnd).is_empty());
+
+    list.reduce(Message::PageAdd(
+        page_ticket(effect).resolve(Ok(page(0, PAGE))),
+    ));
+    assert_eq!(list.reduce(Message::ScrollListEnd).len(), 1);
+    assert!(list.reduce(Message::ScrollListEnd).is_empty());
+}
+
+#[test]
+fn page_error() {
+    let (mut list, effect) = list();
+
+    let effects = list.reduce(Message::PageAdd(
+        page_ticket(effect).resolve(Err(anyio!("oops"))),
+    ));
+    assert!(matches!(&effects[..], [Effect::Error(_)]));
+    assert!(list.freezers.is_empty());
+}
+
+#[test]
+fn renamed_in_place() {
+    let mut list = loaded(3);
+
+    list.reduce(Message::Renamed {
+        old: "freezer-1".into(),
+        new: "pantry".into(),
+    });
+    assert_eq!(list.freezers, ["freezer-0", "pantry", "freezer-2"]);
+}
+
+#[test]
+fn filter_supersedes_pages() {
+    let (mut list, effect) = list();
+    let stale = page_ticket(effect);
+
+    let mut effects = list.reduce(Message::Filter(Filter::Mine));
+    assert!(matches!(
+        &effects[..],
+        [Effect::FetchPage { offset: 0, owner: Some(Owner::User(owner)), .. }] if owner == "admin"
+    ));
+    let fresh = page_ticket(effects.remove(0));
+
+    list.reduce(Message::PageAdd(stale.resolve(Ok(page(0, PAGE)))));
+    assert!(list.freezers.is_empty());
+    list.reduce(Message::PageAdd(fresh.resolve(Ok(page(0, 2)))));
+    assert_eq!(list.freezers, ["freezer-0", "freezer-1"]);
+
+    // the same filter is kept as is
+    assert!(list.reduce(Message::Filter(Filter::Mine)).is_empty());
+    let effects = list.reduce(Message::Filter(Filter::Unowned));
+    assert!(matches!(
+        &effects[..],
+        [Effect::FetchPage {
+            owner: Some(Owner::Nobody),
+            ..
+        }]
+    ));
+}
+
+fn bulk(effects: Vec<Effect>) -> Option<Vec<String>> {
+    match &effects[..] {
+        [Effect::Bulk { ids, .. }] => Some(ids.clone()),
+        _ => None,
+    }
+}
+
+#[test]
+fn click_selects() {
+    let mut list = loaded(5);
+
+    assert!(matches!(
+        &list.reduce(Message::Click(1))[..],
+        [Effect::Ping(id)] if id == "freezer-1"
+    ));
+    assert_eq!(
+        bulk(list.reduce(Message::StartBulk(Action::Delete))),
+        Some(page(1, 1))
+    );
+
+    // the plain click drops the selection
+    list.reduce(Message::Click(3));
+    assert_eq!(
+        bulk(list.reduce(Message::StartBulk(Action::Delete))),
+        Some(page(3, 1))
+    );
+
+    list.reduce(Message::SelectNone);
+    assert!(list.reduce(Message::StartBulk(Action::Delete)).is_empty());
+}
+
+#[test]
+fn ctrl_and_shift_click() {
+    let mut list = loaded(6);
+
+    list.reduce(Message::Click(4));
+    list.reduce(Message::Modifiers(Modifiers::COMMAND));
+    assert!(list.reduce(Message::Click(0)).is_empty());
+    assert_eq!(
+        bulk(list.reduce(Message::StartBulk(Action::Export))),
+        Some(vec!["freezer-0".into(), "freezer-4".into()])
+    );
+
+    // toggled off
+    list.reduce(Message::Click(4));
+    assert_eq!(
+        bulk(list.reduce(Message::StartBulk(Action::Export))),
+        Some(page(0, 1))
+    );
+
+    // range from the last click
+    list.reduce(Message::Modifiers(Modifiers::SHIFT));
+    assert!(list.reduce(Message::Click(2)).is_empty());
+    assert_eq!(
+        bulk(list.reduce(Message::StartBulk(Action::Export))),
+        Some(page(2, 3))
+    );
+}
+
+#[test]
+fn select_all_shown() {
+    let mut list = loaded(3);
+    list.reduce(Message::SelectAll);
+    assert_eq!(
+        bulk(list.reduce(Message::StartBulk(Action::Owner(Owner::Nobody)))),
+        Some(page(0, 3))
+    );
+
+    // changed freezers are loaded again
+    let effects = list.reduce(Message::Reload);
+    assert!(matches!(
+        &effects[..],
+        [Effect::FetchPage { offset: 0, .. }]
+    ));
+    assert!(list.freezers.is_empty());
+    assert!(list.reduce(Message::StartBulk(Action::Delete)).is_empty());
+}
+
+#[test]
+fn bulk_needs_permission() {
+    let mut list = loaded(2);
+    list.account.permissions = Some([Permission::Update].into());
+    list.reduce(Message::SelectAll);
+
+    assert!(list.reduce(Message::StartBulk(Action::Delete)).is_empty());
+    assert!(bulk(list.reduce(Message::StartBulk(Action::Export))).is_some());
+    assert!(bulk(list.reduce(Message::StartBulk(Action::Owner(Owner::Nobody)))).is_some());
+
+    list.account.permissions = Some([].into());
+    assert!(
+        list.reduce(Message::StartBulk(Action::Owner(Owner::Nobody)))
+            .is_empty()
+    );
+}
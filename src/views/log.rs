@@ -1,8 +1,7 @@
+use crate::theme::Palette;
 use iced::{Color, Element, Length};
 use iced_native::widget::{column, scrollable, text};
 
-use hex_colors::color_from_hex;
-
 #[derive(Clone)]
 enum Level {
     Trace,
@@ -12,31 +11,14 @@ enum Level {
     Error,
 }
 
-macro_rules! hex {
-    ($($tt:tt)*) => {{
-        let [r, g, b]: [u8; 3] = color_from_hex!($($tt)*);
-        Color::from_rgb(
-            r as f32 / 255.0,
-            g as f32 / 255.0,
-            b as f32 / 255.0,
-        )
-    }};
-}
-
-const PURPLE: Color = hex!(0x800080);
-const BLUE: Color = hex!(0x0000ff);
-const GREEN: Color = hex!(0x008000);
-const YELLOW: Color = hex!(0xffff00);
-const RED: Color = hex!(0xff0000);
-
 impl Level {
-    const fn as_color(&self) -> Color {
+    const fn as_color(&self, palette: &Palette) -> Color {
         match self {
-            Self::Trace => PURPLE,
-            Self::Debug => BLUE,
-            Self::Info => GREEN,
-            Self::Warn => YELLOW,
-            Self::Error => RED,
+            Self::Trace => palette.trace,
+            Self::Debug => palette.debug,
+            Self::Info => palette.info,
+            Self::Warn => palette.warn,
+            Self::Error => palette.error,
         }
     }
 }
@@ -65,14 +47,26 @@ impl Log {
         self.0.clear();
     }
 
-    pub fn view(&self) -> Element<'_, !> {
-        fn error_text<'a>((level, message): (Level, String)) -> Element<'a, !> {
-            println!("{:?}", level.as_color());
-            text(message).style(level.as_color()).into()
+    #[cfg(test)]
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(_, message)| message.as_str())
+    }
+
+    pub fn view(&self, palette: &Palette) -> Element<'_, !> {
+        fn error_text<'a>((level, message): (Level, String), palette: &Palette) -> Element<'a, !> {
+            text(message).style(level.as_color(palette)).into()
         }
 
         scrollable(
-            column(self.0.iter().rev().cloned().map(error_text).collect()).width(Length::Fill),
+            column(
+                self.0
+                    .iter()
+                    .rev()
+                    .cloned()
+                    .map(|entry| error_text(entry, palette))
+                    .collect(),
+            )
+            .width(Length::Fill),
         )
         .into()
     }
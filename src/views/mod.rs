@@ -1,7 +1,21 @@
+pub mod alerts;
+pub mod bulk;
+pub mod compare;
+pub mod dashboard;
+pub mod exchange;
 pub mod list;
 pub mod log;
+pub mod mover;
 pub mod preview;
+#[cfg(test)]
+mod test_support;
 
+pub use alerts::Alerts;
+pub use bulk::Bulk;
+pub use compare::Compare;
+pub use dashboard::Dashboard;
+pub use exchange::Exchange;
 pub use list::List;
 pub use log::Log;
+pub use mover::Mover;
 pub use preview::Preview;
@@ -0,0 +1,347 @@
+use crate::{
+    api::FreezerApi,
+    columee,
+    model::{self, Account, Permission},
+    row,
+    task::{Tagged, Task, Ticket},
+    transfer::{self, Outcome, Transfer},
+    utils::{Error, Result, error::anyio},
+};
+use iced::{Element, Length};
+use iced_aw::{Card, NumberInput};
+use iced_native::{
+    Command,
+    widget::helpers::{button, pick_list, text},
+};
+use std::sync::Arc;
+use tap::Pipe;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Opens the dialog, the source is preselected if given
+    Open(Option<String>),
+    Close,
+
+    SelectFrom(String),
+    OnSource(Tagged<Result<model::Freezer>>),
+    SelectTo(String),
+    SelectProduct(String),
+    InputAmount(usize),
+
+    Start,
+    OnTransfer(Tagged<(Transfer, Result<Outcome>)>),
+    /// Freezers are changed by the transfer, handled by the app
+    Moved {
+        from: String,
+        to: String,
+    },
+
+    Error(Error),
+    Warn(Error),
+    Info(String),
+}
+
+/// Side effect requested by the update, see [`Mover::perform`]
+#[derive(Debug)]
+pub enum Effect {
+    FetchSource { id: String, ticket: Ticket },
+    Transfer { transfer: Transfer, ticket: Ticket },
+    Moved { from: String, to: String },
+    Error(Error),
+    Warn(Error),
+    Info(String),
+}
+
+/// Dialog to move units of a product from one freezer to another,
+/// see [`transfer::run`]
+#[derive(Debug)]
+pub struct Mover {
+    open: bool,
+    /// Freezers to choose from, the loaded ones of the list
+    freezers: Vec<String>,
+    from: Option<String>,
+    /// Contents of the source
+    source: Option<model::Freezer>,
+    to: Option<String>,
+    product: Option<String>,
+    amount: usize,
+    fetch: Task,
+    task: Task,
+    /// Logged in user, nothing is moved without [`Permission::Update`]
+    account: Account,
+
+    client: Arc<dyn FreezerApi>,
+}
+
+impl Mover {
+    pub fn new(client: Arc<dyn FreezerApi>) -> Self {
+        Self {
+            open: false,
+            freezers: Vec::new(),
+            from: None,
+            source: None,
+            to: None,
+            product: None,
+            amount: 1,
+            fetch: Task::default(),
+            task: Task::default(),
+            account: Account::unknown(""),
+            client,
+        }
+    }
+
+    pub fn set_freezers(&mut self, freezers: Vec<String>) {
+        self.freezers = freezers;
+    }
+
+    pub fn set_account(&mut self, account: Account) {
+        self.account = account;
+    }
+
+    /// Units of the selected product in the source
+    fn available(&self) -> usize {
+        match (&self.source, &self.product) {
+            (Some(source), Some(product)) => {
+                source.products.get(product).copied().unwrap_or_default()
+            }
+            _ => 0,
+        }
+    }
+
+    fn transfer(&self) -> Option<Transfer> {
+        Some(Transfer {
+            from: self.from.clone()?,
+            to: self.to.clone()?,
+            product: self.product.clone()?,
+            amount: self.amount,
+        })
+    }
+
+    fn report(transfer: &Transfer, outcome: Outcome) -> Vec<Effect> {
+        let moved = Effect::Moved {
+            from: transfer.from.clone(),
+            to: transfer.to.clone(),
+        };
+        match outcome {
+            Outcome::Moved { atomic: true, .. } => {
+                vec![Effect::Info(format!("moved {transfer}")), moved]
+            }
+            Outcome::Moved { atomic: false, .. } => vec![
+                Effect::Info(format!("moved {transfer} in two steps")),
+                moved,
+            ],
+            Outcome::Refused => vec![Effect::Warn(anyio!(
+                "unauthorized access: {transfer} is not moved"
+            ))],
+            Outcome::RolledBack(error) => vec![Effect::Error(anyio!(
+                "{transfer} is not moved, `{}` is restored: {error}",
+                transfer.from
+            ))],
+            Outcome::Broken { error, source } => vec![
+                Effect::Error(anyio!(
+                    "{transfer} is moved halfway: `{}` has to be fixed by hand, it had {} \
+                     units of `{}` - {error}",
+                    transfer.from,
+                    source
+                        .products
+                        .get(&transfer.product)
+                        .copied()
+                        .unwrap_or_default(),
+                    transfer.product,
+                )),
+                moved,
+            ],
+        }
+    }
+
+    /// Applies the message to the state, side effects are returned to be performed later
+    pub fn reduce(&mut self, message: Message) -> Vec<Effect> {
+        match message {
+            Message::Open(from) => {
+                self.open = true;
+                match from {
+                    Some(from) if self.from.as_ref() != Some(&from) => {
+                        self.reduce(Message::SelectFrom(from))
+                    }
+                    _ => vec![],
+                }
+            }
+            Message::Close => {
+                self.fetch.cancel();
+                self.open = false;
+                vec![]
+            }
+            Message::SelectFrom(from) => {
+                if self.to.as_ref() == Some(&from) {
+                    self.to = None;
+                }
+                self.source = None;
+                self.product = None;
+                self.from = Some(from.clone());
+                vec![Effect::FetchSource {
+                    id: from,
+                    ticket: self.fetch.begin(),
+                }]
+            }
+            Message::OnSource(res) => match self.fetch.accept(res) {
+                Some(Ok(source)) => {
+                    self.source = Some(source);
+                    vec![]
+                }
+                Some(Err(error)) => vec![Effect::Error(error)],
+                None => vec![],
+            },
+            Message::SelectTo(to) => {
+                self.to = Some(to);
+                vec![]
+            }
+            Message::SelectProduct(product) => {
+                self.product = Some(product);
+                self.amount = self.amount.clamp(1, self.available().max(1));
+                vec![]
+            }
+            Message::InputAmount(amount) => {
+                self.amount = amount;
+                vec![]
+            }
+            Message::Start if self.task.is_running() => vec![],
+            Message::Start if !self.account.can(Permission::Update) => {
+                vec![Effect::Warn(anyio!(
+                    "`{}` may not move products",
+                    self.account.login
+                ))]
+            }
+            Message::Start => match self.transfer() {
+                Some(transfer) if transfer.from == transfer.to => vec![Effect::Error(anyio!(
+                    "`{}` is both the source and the target",
+                    transfer.from
+                ))],
+                Some(transfer) => vec![Effect::Transfer {
+                    transfer,
+                    ticket: self.task.begin(),
+                }],
+                None => vec![Effect::Error(anyio!(
+                    "choose the freezers and the product to move"
+                ))],
+            },
+            Message::OnTransfer(res) => match self.task.accept(res) {
+                Some((transfer, Ok(outcome))) => {
+                    // amounts of the source are outdated
+                    let mut effects = Self::report(&transfer, outcome);
+                    if self.open && self.from.as_ref() == Some(&transfer.from) {
+                        effects.push(Effect::FetchSource {
+                            id: transfer.from,
+                            ticket: self.fetch.begin(),
+                        });
+                    }
+                    effects
+                }
+                Some((_, Err(error))) => vec![Effect::Error(error)],
+                None => vec![],
+            },
+            Message::Moved { .. } | Message::Error(_) | Message::Warn(_) | Message::Info(_) => {
+                vec![]
+            }
+        }
+    }
+
+    pub fn perform(&self, effect: Effect) -> Command<Message> {
+        let client = self.client.clone();
+        match effect {
+            Effect::FetchSource { id, ticket } => {
+                ticket.perform(async move { client.freezer(&id).await }, Message::OnSource)
+            }
+            Effect::Transfer { transfer, ticket } => ticket.perform(
+                async move {
+                    let outcome = transfer::run(&*client, &transfer).await;
+                    (transfer, outcome)
+                },
+                Message::OnTransfer,
+            ),
+            Effect::Moved { from, to } => {
+                Command::perform(async move { (from, to) }, |(from, to)| Message::Moved {
+                    from,
+                    to,
+                })
+            }
+            Effect::Error(error) => Command::perform(async move { error }, Message::Error),
+            Effect::Warn(error) => Command::perform(async move { error }, Message::Warn),
+            Effect::Info(info) => Command::perform(async move { info }, Message::Info),
+        }
+    }
+
+    /// Transfer dialog, shown by the app over all panes
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        if !self.open {
+            return None;
+        }
+
+        let targets: Vec<_> = self
+            .freezers
+            .iter()
+            .filter(|id| self.from.as_ref() != Some(*id))
+            .cloned()
+            .collect();
+        let mut products: Vec<_> = self
+            .source
+            .iter()
+            .flat_map(|source| source.products.keys().cloned())
+            .collect();
+        products.sort();
+        let available = self.available();
+
+        let start = button(text("MOVE")).pipe(|button| {
+            if !self.account.can(Permission::Update)
+                || self.task.is_running()
+                || self.transfer().is_none()
+                || available == 0
+            {
+                button
+            } else {
+                button.on_press(Message::Start)
+            }
+        });
+
+        let body = columee![
+            row![
+                pick_list(
+                    self.freezers.clone(),
+                    self.from.clone(),
+                    Message::SelectFrom
+                )
+                .placeholder("from"),
+                text("->"),
+                pick_list(targets, self.to.clone(), Message::SelectTo).placeholder("to"),
+            ]
+            .spacing(10),
+            row![
+                pick_list(products, self.product.clone(), Message::SelectProduct)
+                    .placeholder("product"),
+                NumberInput::new(self.amount, available.max(1), Message::InputAmount).min(1),
+                text(format!("of {available}")),
+            ]
+            .spacing(10),
+            row![
+                start,
+                text(if self.task.is_running() {
+                    "moving..."
+                } else {
+                    ""
+                }),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10)
+        .width(Length::Fill);
+
+        Some(
+            Card::new(text("Move products"), body)
+                .max_width(600)
+                .on_close(Message::Close)
+                .into(),
+        )
+    }
+}
@@ -0,0 +1,127 @@
+use super::{Effect, Message, Mover};
+use crate::{
+    model::{Account, Freezer},
+    task::Ticket,
+    transfer::{Outcome, Transfer},
+    utils::error::anyio,
+    views::test_support::{self, client},
+};
+
+/// Holds 3 of pelmeni
+fn freezer(name: &str) -> Freezer {
+    test_support::freezer(name, &[("pelmeni", 3)])
+}
+
+fn transfer(amount: usize) -> Transfer {
+    Transfer {
+        from: "kitchen".to_owned(),
+        to: "garage".to_owned(),
+        product: "pelmeni".to_owned(),
+        amount,
+    }
+}
+
+/// Dialog opened from the preview of `kitchen`, its contents are fetched
+fn opened() -> Mover {
+    let mut mover = Mover::new(client());
+    mover.set_freezers(vec!["kitchen".into(), "garage".into()]);
+    let Some(Effect::FetchSource { id, ticket }) =
+        mover.reduce(Message::Open(Some("kitchen".into()))).pop()
+    else {
+        panic!("source is fetched");
+    };
+    assert_eq!(id, "kitchen");
+    mover.reduce(Message::OnSource(ticket.resolve(Ok(freezer("kitchen")))));
+    mover
+}
+
+fn started(mover: &mut Mover) -> (Transfer, Ticket) {
+    match mover.reduce(Message::Start).pop() {
+        Some(Effect::Transfer { transfer, ticket }) => (transfer, ticket),
+        effect => panic!("transfer is started: {effect:?}"),
+    }
+}
+
+#[test]
+fn amount_is_bounded() {
+    let mut mover = opened();
+    mover.reduce(Message::InputAmount(10));
+    mover.reduce(Message::SelectProduct("pelmeni".into()));
+    assert_eq!(mover.available(), 3);
+    assert_eq!(mover.amount, 3);
+
+    // nowhere to move
+    assert!(matches!(
+        &mover.reduce(Message::Start)[..],
+        [Effect::Error(_)]
+    ));
+
+    mover.reduce(Message::SelectTo("garage".into()));
+    mover.reduce(Message::InputAmount(2));
+    assert_eq!(started(&mut mover).0, transfer(2));
+}
+
+#[test]
+fn read_only_account() {
+    let mut mover = opened();
+    mover.set_account(Account {
+        permissions: Some([].into()),
+        ..Account::unknown("guest")
+    });
+    mover.reduce(Message::SelectProduct("pelmeni".into()));
+    mover.reduce(Message::SelectTo("garage".into()));
+    assert!(matches!(
+        &mover.reduce(Message::Start)[..],
+        [Effect::Warn(error)] if error.to_string() == "`guest` may not move products"
+    ));
+}
+
+#[test]
+fn outcome_is_reported() {
+    let mut mover = opened();
+    mover.reduce(Message::SelectProduct("pelmeni".into()));
+    mover.reduce(Message::SelectTo("garage".into()));
+
+    let (moved, ticket) = started(&mut mover);
+    let outcome = Outcome::Moved {
+        from: freezer("kitchen"),
+        to: freezer("garage"),
+        atomic: false,
+    };
+    let effects = mover.reduce(Message::OnTransfer(ticket.resolve((moved, Ok(outcome)))));
+    assert!(matches!(
+        &effects[..],
+        [
+            Effect::Info(info),
+            Effect::Moved { from, to },
+            Effect::FetchSource { .. },
+        ] if info == "moved 1 of `pelmeni` from `kitchen` to `garage` in two steps"
+            && from == "kitchen"
+            && to == "garage"
+    ));
+
+    let (moved, ticket) = started(&mut mover);
+    let outcome = Outcome::RolledBack(anyio!("connection reset"));
+    let effects = mover.reduce(Message::OnTransfer(ticket.resolve((moved, Ok(outcome)))));
+    assert!(matches!(
+        &effects[..],
+        [Effect::Error(error), Effect::FetchSource { .. }]
+            if error.to_string() == "1 of `pelmeni` from `kitchen` to `garage` is not moved, \
+                                     `kitchen` is restored: connection reset"
+    ));
+
+    let (moved, ticket) = started(&mut mover);
+    let outcome = Outcome::Broken {
+        error: anyio!("connection reset"),
+        source: freezer("kitchen"),
+    };
+    let effects = mover.reduce(Message::OnTransfer(ticket.resolve((moved, Ok(outcome)))));
+    assert!(matches!(
+        &effects[..],
+        [
+            Effect::Error(_),
+            Effect::Moved { .. },
+            Effect::FetchSource { .. }
+        ]
+    ));
+}
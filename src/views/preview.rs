@@ -1,35 +1,57 @@
-use crate::{columee, empty, model, row, utils::Result, Client};
+use crate::{
+    api::FreezerApi,
+    cache::{Entry, ImageCache},
+    client::{Image, Rename},
+    columee, empty, history,
+    model::{self, Account, Permission},
+    owners::{self, Owner},
+    row,
+    stock::Thresholds,
+    task::{Tagged, Task, Ticket},
+    theme::{Palette, Tone},
+    utils::Result,
+    views::alerts,
+};
 
-use futures::try_join;
+use bytesize::ByteSize;
+use chrono::{Datelike, Local, NaiveDate};
 use iced::{
-    alignment::Horizontal,
-    widget,
+    ContentFit, Element, Length, Subscription, theme, time, widget,
     widget::{Container, Tooltip},
-    Element, Length,
 };
-use iced_aw::{style::BadgeStyles, Badge, Card, Modal, NumberInput};
+use iced_aw::{Badge, Card, NumberInput, style::BadgeStyles};
 use iced_native::Command;
-use std::sync::Arc;
+use std::{collections::HashMap, mem, ops::RangeInclusive, sync::Arc, time::Duration};
 
 use crate::{
-    model::{Model, Product},
-    utils::{error::anyio, Error},
+    model::{Batch, Details, Expiry, Model, Product},
+    utils::{Error, error::anyio},
 };
 use iced_native::{
     image,
     widget::{
-        helpers::{button, column, container, horizontal_rule, image, scrollable, text},
+        helpers::{
+            button, column, container, horizontal_rule, image, pick_list, progress_bar, scrollable,
+            text,
+        },
+        image::Viewer,
         tooltip::Position,
     },
 };
 use tap::Pipe;
 
-#[derive(Debug, Clone)]
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Freezer {
     pub name: String,
     pub model: Model,
     pub owner: Option<String>,
+    pub details: Details,
     pub products: Vec<(String, usize)>,
+    /// Sorted to use the expiring first
+    pub batches: HashMap<String, Vec<Batch>>,
 }
 
 impl From<Freezer> for model::Freezer {
@@ -38,14 +60,18 @@ impl From<Freezer> for model::Freezer {
             name,
             model,
             owner,
+            details,
             products,
+            batches,
         }: Freezer,
     ) -> Self {
         Self {
             name,
             model,
             owner,
+            details,
             products: products.into_iter().collect(),
+            batches,
         }
     }
 }
@@ -56,261 +82,1396 @@ impl From<model::Freezer> for Freezer {
             name,
             model,
             owner,
+            details,
             products,
+            mut batches,
         }: model::Freezer,
     ) -> Self {
+        batches
+            .values_mut()
+            .for_each(|batches| Batch::sort(batches));
         Self {
             name,
             model,
             owner,
+            details,
             products: products.into_iter().collect(),
+            batches,
+        }
+    }
+}
+
+/// Edited field rejected by [`Freezer::validate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    Model,
+    Year,
+}
+
+/// Why the freezer can't get the name, `None` if it can
+fn check_name(name: &str) -> Option<&'static str> {
+    if name.trim().is_empty() {
+        Some("name cannot be empty")
+    } else if name.trim() != name {
+        Some("name cannot start or end with spaces")
+    } else if name.contains(['/', '?', '#']) {
+        // it's a segment of the API paths
+        Some("name cannot contain `/`, `?` or `#`")
+    } else {
+        None
+    }
+}
+
+/// Model years from the first freezer on sale up to the current one
+fn years(today: NaiveDate) -> RangeInclusive<usize> {
+    const FIRST: usize = 1999;
+    FIRST..=usize::try_from(today.year()).unwrap_or(FIRST)
+}
+
+impl Freezer {
+    /// Problems preventing the update by field, empty if the freezer can be saved
+    pub fn validate(&self, today: NaiveDate) -> HashMap<Field, String> {
+        let mut errors = HashMap::new();
+        if self.model.name.trim().is_empty() {
+            errors.insert(Field::Model, "model name cannot be empty".to_owned());
+        }
+        let years = years(today);
+        if !years.contains(&self.model.year) {
+            errors.insert(
+                Field::Year,
+                format!("year must be from {} to {}", years.start(), years.end()),
+            );
+        }
+        errors
+    }
+}
+
+/// Batch to be added to the input product, frozen today
+#[derive(Debug, Clone)]
+struct NewBatch {
+    quantity: usize,
+    /// `YYYY-MM-DD`, empty if unknown
+    best_before: String,
+}
+
+impl Default for NewBatch {
+    fn default() -> Self {
+        Self {
+            quantity: 1,
+            best_before: String::new(),
         }
     }
 }
 
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Page of the shown freezer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    Details,
+    History,
+}
+
+const fn expiry_style(expiry: Expiry, palette: &Palette) -> BadgeStyles {
+    palette.badge(match expiry {
+        Expiry::Fresh => Tone::Plain,
+        Expiry::Soon => Tone::Warning,
+        Expiry::Expired => Tone::Danger,
+    })
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
-    /// `update` happens, but we do nothing
-    Silent,
-    ModalExit,
+    /// Spinner frame of the running operations
+    Tick,
+    Cancel(Operation),
+
+    FetchInfo(Tagged<Result<Freezer>>),
+    FetchImage(Tagged<Result<Option<Photo>>>),
+    FetchHistory(Tagged<Result<Vec<model::Revision>>>),
 
-    FetchInfo(Result<(image::Handle, Freezer)>),
+    ShowTab(Tab),
+    /// Saves the freezer as it was at the history entry with given index
+    Revert(usize),
+
+    OpenViewer,
+    CloseViewer,
+    FitViewer(Fit),
+    /// Shows the tab of the freezer, a new one is opened unless it's open already
     FetchRequest(String),
+    /// Fetches the open freezer again unless it has unsaved edits, the shown tab is kept
+    Refresh(String),
+    /// Shows the open freezer with given index
+    ShowSheet(usize),
+    /// Closes the tab, its unsaved edits are dropped
+    CloseSheet(usize),
+    MoveSheet {
+        from: usize,
+        to: usize,
+    },
 
+    InputName(String),
+    StartRename,
+    /// Answer for the requested new name, the input may be changed meanwhile
+    OnRename(String, Tagged<Result<Rename>>),
+    /// Rename is done, handled by the app - the list shows the ids
+    Renamed {
+        old: String,
+        new: String,
+    },
+
+    /// Empty unsets the owner
     InputOwner(String),
+    /// Owner becomes the logged in user
+    AssignToMe,
     InputModel(String),
     InputYear(usize),
+    /// `0` if unknown
+    InputCapacity(usize),
+    InputLocation(String),
+    InputTemperature(i32),
+    ClearTemperature,
+    InputNotes(String),
     InputProduct(String),
+    InputBatch(usize),
+    InputBestBefore(String),
+    /// Adds the batch to the input product
+    AddBatch,
     ChangeProduct {
         id: usize,
         amount: usize,
     },
+    /// Threshold of the product in the freezer, `None` falls back to the one of the product.
+    /// Handled by the app - thresholds belong to [`alerts::Alerts`]
+    ChangeMinimum {
+        freezer: String,
+        product: String,
+        minimum: Option<usize>,
+    },
 
     StartAddProduct,
-    OnAddProduct(Result<Option<Product>>),
+    /// Answer for the requested product, the input may be changed meanwhile
+    OnAddProduct(String, Tagged<Result<Option<Product>>>),
 
     StartUpdate,
-    OnUpdate(Result<Option<model::Freezer>>),
+    OnUpdate(Tagged<Result<Option<model::Freezer>>>),
 
     StartDelete,
-    OnDelete(Result<bool>),
+    OnDelete(Tagged<Result<bool>>),
+
+    Error(Error),
+    Warn(Error),
+}
 
+/// Side effect requested by the update, see [`Preview::perform`]
+#[derive(Debug)]
+pub enum Effect {
+    FetchFreezer {
+        id: String,
+        ticket: Ticket,
+    },
+    FetchImage {
+        id: String,
+        ticket: Ticket,
+    },
+    FetchHistory {
+        id: String,
+        ticket: Ticket,
+    },
+    Update {
+        freezer: model::Freezer,
+        ticket: Ticket,
+    },
+    Delete {
+        id: String,
+        ticket: Ticket,
+    },
+    Rename {
+        old: String,
+        new: String,
+        ticket: Ticket,
+    },
+    Renamed {
+        old: String,
+        new: String,
+    },
+    FindProduct {
+        id: String,
+        ticket: Ticket,
+    },
     Error(Error),
     Warn(Error),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Fetch,
+    Image,
+    History,
+    Save,
+    Delete,
+    Rename,
+    AddProduct,
+}
+
+impl Operation {
+    const ALL: [Self; 7] = [
+        Self::Fetch,
+        Self::Image,
+        Self::History,
+        Self::Save,
+        Self::Delete,
+        Self::Rename,
+        Self::AddProduct,
+    ];
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Fetch => "fetching",
+            Self::Image => "loading image of",
+            Self::History => "fetching history of",
+            Self::Save => "saving",
+            Self::Delete => "deleting",
+            Self::Rename => "renaming",
+            Self::AddProduct => "adding product to",
+        }
+    }
+}
+
+/// Request issued for the freezer with given name
+#[derive(Debug, Default)]
+struct Pending {
+    freezer: String,
+    task: Task,
+}
+
+impl Pending {
+    fn begin(&mut self, freezer: String) -> Ticket {
+        self.freezer = freezer;
+        self.task.begin()
+    }
+
+    /// The response belongs to the current request
+    fn issued<T>(&self, tagged: &Tagged<T>) -> bool {
+        self.task.issued(tagged)
+    }
+
+    /// Returns the response with the freezer name it was issued for
+    fn accept<T>(&mut self, tagged: Tagged<T>) -> Option<(String, T)> {
+        self.task
+            .accept(tagged)
+            .map(|value| (mem::take(&mut self.freezer), value))
+    }
+}
+
+/// Every operation of the open freezer runs independently:
+/// - fetching (`Fetch`, `Image` and `History`) supersedes the previous one
+///   and the product lookup
+/// - `Save`, `Delete` and `Rename` outlive fetching and are applied only
+///   if their freezer is still shown, the tab can't be closed meanwhile
+#[derive(Debug, Default)]
+struct State {
+    fetch: Pending,
+    image: Pending,
+    history: Pending,
+    save: Pending,
+    delete: Pending,
+    rename: Pending,
+    add_product: Pending,
+}
+
+impl State {
+    const fn get(&self, operation: Operation) -> &Pending {
+        match operation {
+            Operation::Fetch => &self.fetch,
+            Operation::Image => &self.image,
+            Operation::History => &self.history,
+            Operation::Save => &self.save,
+            Operation::Delete => &self.delete,
+            Operation::Rename => &self.rename,
+            Operation::AddProduct => &self.add_product,
+        }
+    }
+
+    fn get_mut(&mut self, operation: Operation) -> &mut Pending {
+        match operation {
+            Operation::Fetch => &mut self.fetch,
+            Operation::Image => &mut self.image,
+            Operation::History => &mut self.history,
+            Operation::Save => &mut self.save,
+            Operation::Delete => &mut self.delete,
+            Operation::Rename => &mut self.rename,
+            Operation::AddProduct => &mut self.add_product,
+        }
+    }
+
+    fn running(&self) -> impl Iterator<Item = (Operation, &str)> + '_ {
+        Operation::ALL.into_iter().filter_map(move |op| {
+            let Pending { freezer, task } = self.get(op);
+            task.is_running().then_some((op, freezer.as_str()))
+        })
+    }
+
+    fn is_busy(&self) -> bool {
+        self.running().next().is_some()
+    }
+
+    /// The freezer is being saved, deleted or renamed
+    const fn is_changing(&self) -> bool {
+        self.save.task.is_running()
+            || self.delete.task.is_running()
+            || self.rename.task.is_running()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Photo {
+    handle: image::Handle,
+    size: ByteSize,
+    /// `None` if format is unknown
+    dimensions: Option<(usize, usize)>,
+}
+
+impl Photo {
+    fn new(bytes: &[u8]) -> Self {
+        Self {
+            handle: image::Handle::from_memory(bytes.to_vec()),
+            size: ByteSize::b(bytes.len() as u64),
+            dimensions: imagesize::blob_size(bytes)
+                .ok()
+                .map(|size| (size.width, size.height)),
+        }
+    }
+
+    fn metadata(&self) -> String {
+        match self.dimensions {
+            Some((width, height)) => format!("{width}x{height} px, {}", self.size),
+            None => format!("unknown dimensions, {}", self.size),
+        }
+    }
+}
+
+/// Freezer image, placeholder is shown if it's missing or failed to load
 #[derive(Debug, Clone)]
-enum State {
-    Loading { on_cancel: Box<State> },
-    Ready,
+enum Picture {
+    Loading,
+    Ready(Photo),
+    Missing,
+    Failed,
 }
 
+/// Image size in the full-window viewer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fit {
+    /// Scaled to the window keeping aspect ratio
+    Window,
+    /// Original size with zoom (wheel) and pan (drag)
+    Actual,
+}
+
+impl Picture {
+    fn view<'a>(&self) -> Element<'a, Message> {
+        let placeholder = |label| {
+            container(text(label))
+                .width(Length::Fill)
+                .height(Length::Units(200))
+                .center_x()
+                .center_y()
+                .into()
+        };
+
+        match self {
+            Self::Ready(photo) => button(
+                image(photo.handle.clone())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .content_fit(ContentFit::Contain),
+            )
+            .on_press(Message::OpenViewer)
+            .style(theme::Button::Text)
+            .height(Length::Units(400))
+            .into(),
+            Self::Loading => placeholder("loading image..."),
+            Self::Missing => placeholder("no image"),
+            Self::Failed => placeholder("image failed to load"),
+        }
+    }
+}
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+const UNAUTHORIZED: &str = "unauthorized access - try login with high privileges";
+
+/// Freezer open in a tab with its own edits, requests and image
 #[derive(Debug)]
-pub struct Preview {
+struct Sheet {
+    /// Id the freezer is open by, follows the renames
+    id: String,
     state: State,
 
-    info: Option<(image::Handle, Freezer)>,
+    info: Option<Freezer>,
+    /// As fetched or saved, edits of `info` are unsaved while it differs
+    saved: Option<model::Freezer>,
+    tab: Tab,
+    /// Changes of the shown freezer, `None` until fetched
+    history: Option<Vec<history::Entry>>,
+    picture: Picture,
+    /// full-window image viewer is open
+    viewer: Option<Fit>,
+    /// New name of the shown freezer
+    name: String,
     product: String,
-    client: Arc<Client>,
+    new_batch: NewBatch,
+}
+
+#[derive(Debug)]
+pub struct Preview {
+    /// Open freezers in the order of the tabs
+    sheets: Vec<Sheet>,
+    /// Index of the shown one
+    active: usize,
+    spinner: usize,
+    thresholds: Thresholds,
+    /// Candidates for owners, see [`owners::candidates`]
+    owners: Vec<String>,
+    /// Logged in user, the controls they may not use are disabled
+    account: Account,
+    palette: Palette,
+    client: Arc<dyn FreezerApi>,
+    images: Arc<ImageCache>,
 }
 
 impl Preview {
-    async fn fetch_image(client: Arc<Client>, id: String) -> Result<image::Handle> {
-        client
-            .image_bytes(&id)
-            .await
-            .map(|b| b.as_ref().to_vec())
-            .map(image::Handle::from_memory)
+    /// Revalidates the cached image by its `ETag`,
+    /// the cached one is also used if the server is unreachable
+    async fn fetch_image(
+        client: Arc<dyn FreezerApi>,
+        cache: Arc<ImageCache>,
+        id: String,
+    ) -> Result<Option<Photo>> {
+        let cached = cache.get(&id).await;
+        let etag = cached.as_ref().and_then(|entry| entry.etag.as_deref());
+
+        let response = client.image(&id, etag).await;
+
+        let bytes = match response {
+            Ok(Image::Fresh { etag, bytes }) => {
+                cache
+                    .insert(
+                        &id,
+                        Entry {
+                            etag,
+                            bytes: bytes.clone(),
+                        },
+                    )
+                    .await;
+                Some(bytes)
+            }
+            Ok(Image::NotModified) => cached.map(|entry| entry.bytes),
+            Ok(Image::Missing) => {
+                cache.remove(&id).await;
+                None
+            }
+            Err(error) => Some(cached.ok_or(error)?.bytes),
+        };
+        Ok(bytes.map(|bytes| Photo::new(&bytes)))
     }
 
-    async fn fetch_freezer(client: Arc<Client>, id: String) -> Result<Freezer> {
+    async fn fetch_freezer(client: Arc<dyn FreezerApi>, id: String) -> Result<Freezer> {
         client.freezer(&id).await.map(Into::into)
     }
 
-    async fn fetch_info(client: Arc<Client>, id: String) -> Result<(image::Handle, Freezer)> {
-        try_join!(
-            async { Self::fetch_image(client.clone(), id.clone()).await },
-            async { Self::fetch_freezer(client.clone(), id.clone()).await }
-        )
+    pub fn new(client: Arc<dyn FreezerApi>) -> Self {
+        Self {
+            sheets: Vec::new(),
+            active: 0,
+            spinner: 0,
+            thresholds: Thresholds::default(),
+            owners: Vec::new(),
+            account: Account::unknown(""),
+            palette: Palette::default(),
+            client,
+            images: Arc::default(),
+        }
     }
 
-    pub fn new(client: Arc<Client>) -> (Self, Command<Message>) {
-        (
-            Self {
-                state: State::Ready,
-                info: None,
-                product: String::new(),
-                client,
-            },
-            Command::none(),
-        )
+    /// Thresholds to highlight the products running low
+    pub fn set_thresholds(&mut self, thresholds: Thresholds) {
+        self.thresholds = thresholds;
     }
 
-    // fixme: use more understand state manager
-    #[warn(clippy::too_many_lines)]
-    pub fn update(&mut self, message: Message) -> Command<Message> {
-        if let State::Ready = self.state && let Message::FetchRequest(id) = &message {
-            self.state = State::Loading {
-                on_cancel: box State::Ready,
-            };
-            let id = id.clone();
-            let client = self.client.clone();
-            return Command::perform(
-                async { Self::fetch_info(client, id).await },
-                Message::FetchInfo,
-            );
+    /// Colours of the badges and of the invalid fields
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    /// Images of the logged in host, the default cache is in memory only
+    pub fn set_images(&mut self, images: Arc<ImageCache>) {
+        self.images = images;
+    }
+
+    /// Logged in user, the one of "assign to me"
+    pub fn set_account(&mut self, account: Account) {
+        self.account = account;
+    }
+
+    /// Users the freezers can be assigned to, owners of the shown freezers are added too
+    pub fn add_owners(&mut self, owners: Vec<String>) {
+        self.owners = owners::candidates(mem::take(&mut self.owners), owners);
+    }
+
+    pub fn owners(&self) -> &[String] {
+        &self.owners
+    }
+
+    /// Sheet of the shown tab
+    fn sheet(&self) -> Option<&Sheet> {
+        self.sheets.get(self.active)
+    }
+
+    /// Id of the shown freezer
+    pub fn shown(&self) -> Option<&str> {
+        let freezer = self.sheet()?.info.as_ref()?;
+        Some(freezer.name.as_str())
+    }
+
+    /// Index of the sheet that issued the request of the response
+    fn issuer<T>(&self, operation: Operation, tagged: &Tagged<T>) -> Option<usize> {
+        self.sheets
+            .iter()
+            .position(|sheet| sheet.state.get(operation).issued(tagged))
+    }
+
+    /// Shows the tab of the freezer, a new one is opened unless it's open already.
+    /// The open one is fetched again unless it has unsaved edits
+    fn open(&mut self, id: String) -> Vec<Effect> {
+        match self.sheets.iter().position(|sheet| sheet.id == id) {
+            Some(index) => {
+                self.active = index;
+                self.refresh(index)
+            }
+            None => {
+                self.sheets.push(Sheet::new(id));
+                self.active = self.sheets.len() - 1;
+                self.sheets[self.active].fetch()
+            }
         }
+    }
 
-        match &mut self.state {
-            State::Loading { on_cancel } => {
-                self.state = State::Ready;
-                match message {
-                    Message::ModalExit => Command::none(),
-                    Message::FetchInfo(Ok(info)) => {
-                        self.info = Some(info);
-                        Command::none()
-                    }
-                    Message::OnUpdate(Ok(Some(_))) => Command::none(),
-                    Message::OnUpdate(Ok(None)) => Command::perform(
-                        async move { anyio!("unauthorized access - try login with high privileges") },
-                        Message::Warn,
-                    ),
-                    Message::OnDelete(Ok(is_delete)) => {
-                        if is_delete {
-                            self.info = None;
-                            Command::none()
-                        } else {
-                            Command::perform(
-                                async move {
-                                    anyio!("unauthorized access - try login with high privileges")
-                                },
-                                Message::Warn,
-                            )
-                        }
-                    }
-                    Message::OnAddProduct(Ok(Some(Product { name, default }))) => {
-                        if let Some((_, freezer)) = &mut self.info {
-                            if let Some(index) =
-                                freezer.products.iter().position(|(key, _)| key == &name)
-                            {
-                                return Command::perform(
-                                    async move { anyio!("already exists `{name}` at `{index}`") },
-                                    Message::Error,
-                                );
-                            }
-                            freezer.products.push((name, default));
-                        }
-                        Command::none()
-                    }
-                    Message::FetchInfo(Err(error)) => {
-                        Command::perform(async move { error }, Message::Error)
-                    }
-                    Message::OnUpdate(Err(error)) => {
-                        Command::perform(async move { error }, Message::Error)
-                    }
-                    Message::OnDelete(Err(error)) => {
-                        Command::perform(async move { error }, Message::Error)
+    fn refresh(&mut self, index: usize) -> Vec<Effect> {
+        match self.sheets.get_mut(index) {
+            Some(sheet) if !sheet.is_dirty() => sheet.fetch(),
+            _ => vec![],
+        }
+    }
+
+    /// Drops the sheet with its requests, the neighbour is shown instead
+    fn remove(&mut self, index: usize) -> Sheet {
+        let mut sheet = self.sheets.remove(index);
+        for operation in Operation::ALL {
+            sheet.state.get_mut(operation).task.cancel();
+        }
+        if self.active > index {
+            self.active -= 1;
+        }
+        self.active = self.active.min(self.sheets.len().saturating_sub(1));
+        sheet
+    }
+
+    /// Moves the tab keeping the shown one
+    fn move_sheet(&mut self, from: usize, to: usize) {
+        let len = self.sheets.len();
+        if from >= len || to >= len {
+            return;
+        }
+        let sheet = self.sheets.remove(from);
+        self.sheets.insert(to, sheet);
+        let active = self.active;
+        self.active = if active == from {
+            to
+        } else if from < active && active <= to {
+            active - 1
+        } else if to <= active && active < from {
+            active + 1
+        } else {
+            active
+        };
+    }
+
+    /// Applies the message to the state, side effects are returned to be performed later
+    pub fn reduce(&mut self, message: Message) -> Vec<Effect> {
+        match message {
+            Message::Tick => {
+                self.spinner = self.spinner.wrapping_add(1);
+                vec![]
+            }
+            Message::FetchRequest(id) => self.open(id),
+            Message::Refresh(id) => match self.sheets.iter().position(|sheet| sheet.id == id) {
+                Some(index) => self.refresh(index),
+                None => vec![],
+            },
+            Message::ShowSheet(index) => {
+                if index < self.sheets.len() {
+                    self.active = index;
+                }
+                vec![]
+            }
+            Message::CloseSheet(index) => match self.sheets.get(index) {
+                Some(sheet) if !sheet.state.is_changing() => {
+                    let sheet = self.remove(index);
+                    if sheet.is_dirty() {
+                        vec![Effect::Warn(anyio!(
+                            "unsaved edits of `{}` are dropped",
+                            sheet.id
+                        ))]
+                    } else {
+                        vec![]
                     }
-                    Message::OnAddProduct(Ok(None)) => {
-                        let product = self.product.clone();
-                        Command::perform(
-                            async move { anyio!("Not found product `{product}`") },
-                            Message::Error,
-                        )
+                }
+                _ => vec![],
+            },
+            Message::MoveSheet { from, to } => {
+                self.move_sheet(from, to);
+                vec![]
+            }
+            Message::OnDelete(res) => {
+                let Some(index) = self.issuer(Operation::Delete, &res) else {
+                    return vec![];
+                };
+                match self.sheets[index].state.delete.accept(res) {
+                    // nothing to show in the tab
+                    Some((_, Ok(true))) => {
+                        self.remove(index);
+                        vec![]
                     }
-                    Message::OnAddProduct(Err(error)) => {
-                        Command::perform(async move { error }, Message::Error)
+                    Some((name, Ok(false))) => vec![Effect::Warn(anyio!(
+                        "{UNAUTHORIZED}: `{name}` is not deleted"
+                    ))],
+                    Some((_, Err(error))) => vec![Effect::Error(error)],
+                    None => vec![],
+                }
+            }
+            message => {
+                // responses go to the sheet of their request, the rest to the shown one
+                let index = match &message {
+                    Message::FetchInfo(res) => self.issuer(Operation::Fetch, res),
+                    Message::FetchImage(res) => self.issuer(Operation::Image, res),
+                    Message::FetchHistory(res) => self.issuer(Operation::History, res),
+                    Message::OnUpdate(res) => self.issuer(Operation::Save, res),
+                    Message::OnRename(_, res) => self.issuer(Operation::Rename, res),
+                    Message::OnAddProduct(_, res) => self.issuer(Operation::AddProduct, res),
+                    _ => Some(self.active),
+                };
+                let fetched = matches!(message, Message::FetchInfo(_));
+                let Some(sheet) = index.and_then(|index| self.sheets.get_mut(index)) else {
+                    return vec![];
+                };
+                let effects = sheet.reduce(message, &self.account);
+                // owners of the fetched freezers are candidates too
+                if fetched {
+                    let owner = sheet.info.as_ref().and_then(|info| info.owner.clone());
+                    self.add_owners(owner.into_iter().collect());
+                }
+                effects
+            }
+        }
+    }
+}
+
+impl Sheet {
+    fn new(id: String) -> Self {
+        Self {
+            id,
+            state: State::default(),
+            info: None,
+            saved: None,
+            tab: Tab::Details,
+            history: None,
+            picture: Picture::Loading,
+            viewer: None,
+            name: String::new(),
+            product: String::new(),
+            new_batch: NewBatch::default(),
+        }
+    }
+
+    fn is_shown(&self, name: &str) -> bool {
+        matches!(&self.info, Some(freezer) if freezer.name == name)
+    }
+
+    /// Edits are not saved yet
+    fn is_dirty(&self) -> bool {
+        self.info.is_some() && self.info.clone().map(model::Freezer::from) != self.saved
+    }
+
+    /// Drops the known history of `id`, it's fetched again if the tab is open
+    fn refresh_history(&mut self, id: String) -> Option<Effect> {
+        self.history = None;
+        (self.tab == Tab::History).then(|| Effect::FetchHistory {
+            ticket: self.state.history.begin(id.clone()),
+            id,
+        })
+    }
+
+    /// Requests the freezer with its image, and its history if the tab is open
+    fn fetch(&mut self) -> Vec<Effect> {
+        let id = self.id.clone();
+        self.state.add_product.task.cancel();
+        self.state.history.task.cancel();
+        self.picture = Picture::Loading;
+        self.viewer = None;
+
+        let mut effects = vec![
+            Effect::FetchFreezer {
+                ticket: self.state.fetch.begin(id.clone()),
+                id: id.clone(),
+            },
+            Effect::FetchImage {
+                ticket: self.state.image.begin(id.clone()),
+                id: id.clone(),
+            },
+        ];
+        effects.extend(self.refresh_history(id));
+        effects
+    }
+
+    /// Messages of the shown tab and responses to the requests of this one
+    fn reduce(&mut self, message: Message, account: &Account) -> Vec<Effect> {
+        match message {
+            Message::Cancel(operation) => {
+                self.state.get_mut(operation).task.cancel();
+                vec![]
+            }
+            Message::ShowTab(tab) => {
+                self.tab = tab;
+                match &self.info {
+                    Some(freezer)
+                        if self.history.is_none() && !self.state.history.task.is_running() =>
+                    {
+                        let id = freezer.name.clone();
+                        self.refresh_history(id).into_iter().collect()
                     }
-                    _ => Command::none(),
+                    _ => vec![],
                 }
             }
-            State::Ready => {
-                if let Some((_, freezer)) = &mut self.info {
-                    match message.clone() {
-                        Message::InputOwner(owner) => {
-                            freezer.owner = Some(owner);
-                        }
-                        Message::InputModel(model) => {
-                            freezer.model.name = model;
+            Message::FetchHistory(res) => match self.state.history.accept(res) {
+                Some((_, Ok(revisions))) => {
+                    self.history = Some(history::entries(revisions));
+                    vec![]
+                }
+                Some((_, Err(error))) => vec![Effect::Warn(error)],
+                None => vec![],
+            },
+            Message::FetchInfo(res) => match self.state.fetch.accept(res) {
+                Some((_, Ok(info))) => {
+                    self.name.clone_from(&info.name);
+                    self.saved = Some(info.clone().into());
+                    self.info = Some(info);
+                    vec![]
+                }
+                Some((_, Err(error))) => vec![Effect::Error(error)],
+                None => vec![],
+            },
+            Message::FetchImage(res) => match self.state.image.accept(res) {
+                Some((_, Ok(photo))) => {
+                    self.picture = photo.map_or(Picture::Missing, Picture::Ready);
+                    vec![]
+                }
+                Some((_, Err(error))) => {
+                    self.picture = Picture::Failed;
+                    vec![Effect::Warn(error)]
+                }
+                None => vec![],
+            },
+            Message::OnUpdate(res) => match self.state.save.accept(res) {
+                // the update is the newest revision now
+                Some((name, Ok(Some(stored)))) if self.is_shown(&name) => {
+                    // sorted like the edited one
+                    self.saved = Some(Freezer::from(stored).into());
+                    self.refresh_history(name).into_iter().collect()
+                }
+                Some((_, Ok(Some(_)))) | None => vec![],
+                Some((name, Ok(None))) => vec![Effect::Warn(anyio!(
+                    "{UNAUTHORIZED}: `{name}` is not updated"
+                ))],
+                Some((_, Err(error))) => vec![Effect::Error(error)],
+            },
+            Message::OnRename(requested, res) => match self.state.rename.accept(res) {
+                Some((old, Ok(Rename::Renamed(freezer)))) => {
+                    let new = freezer.name;
+                    let mut effects = vec![Effect::Renamed {
+                        old: old.clone(),
+                        new: new.clone(),
+                    }];
+                    // local edits are kept, only the id changes
+                    if self.is_shown(&old) {
+                        self.id.clone_from(&new);
+                        if let Some(info) = &mut self.info {
+                            info.name.clone_from(&new);
                         }
-                        Message::InputYear(year) => {
-                            freezer.model.year = year;
+                        if let Some(saved) = &mut self.saved {
+                            saved.name.clone_from(&new);
                         }
-                        Message::ChangeProduct { id, amount } => {
-                            freezer.products[id].1 = amount;
+                        self.name.clone_from(&new);
+                        effects.extend(self.refresh_history(new));
+                    }
+                    effects
+                }
+                Some((old, Ok(Rename::Taken))) => vec![Effect::Error(anyio!(
+                    "`{old}` is not renamed: `{requested}` is taken by another freezer"
+                ))],
+                Some((old, Ok(Rename::Refused))) => vec![Effect::Warn(anyio!(
+                    "{UNAUTHORIZED}: `{old}` is not renamed"
+                ))],
+                Some((_, Err(error))) => vec![Effect::Error(error)],
+                None => vec![],
+            },
+            Message::OnAddProduct(product, res) => match self.state.add_product.accept(res) {
+                Some((_, Ok(Some(Product { name, default })))) => {
+                    if let Some(freezer) = &mut self.info {
+                        if let Some(index) =
+                            freezer.products.iter().position(|(key, _)| key == &name)
+                        {
+                            return vec![Effect::Error(anyio!(
+                                "already exists `{name}` at `{index}`"
+                            ))];
                         }
-                        Message::InputProduct(product) => {
-                            self.product = product;
+                        freezer.products.push((name, default));
+                    }
+                    vec![]
+                }
+                Some((_, Ok(None))) => {
+                    vec![Effect::Error(anyio!("Not found product `{product}`"))]
+                }
+                Some((_, Err(error))) => vec![Effect::Error(error)],
+                None => vec![],
+            },
+            Message::OpenViewer => {
+                if let Picture::Ready(_) = self.picture {
+                    self.viewer = Some(Fit::Window);
+                }
+                vec![]
+            }
+            Message::CloseViewer => {
+                self.viewer = None;
+                vec![]
+            }
+            Message::FitViewer(fit) => {
+                self.viewer = self.viewer.map(|_| fit);
+                vec![]
+            }
+            Message::InputName(name) => {
+                self.name = name;
+                vec![]
+            }
+            Message::InputProduct(product) => {
+                self.product = product;
+                vec![]
+            }
+            Message::InputBatch(quantity) => {
+                self.new_batch.quantity = quantity;
+                vec![]
+            }
+            Message::InputBestBefore(date) => {
+                self.new_batch.best_before = date;
+                vec![]
+            }
+            message => self.edit(message, account).into_iter().collect(),
+        }
+    }
+
+    /// Local edits and mutations of the shown freezer
+    fn edit(&mut self, message: Message, account: &Account) -> Option<Effect> {
+        let freezer = self.info.as_mut()?;
+
+        // freezer is going away or changing its id - nothing to edit
+        if self.state.delete.task.is_running() || self.state.rename.task.is_running() {
+            return None;
+        }
+
+        // controls are disabled, still checked for the messages sent before the login
+        let required = match message {
+            Message::StartDelete => Some(Permission::Delete),
+            Message::InputOwner(_)
+            | Message::AssignToMe
+            | Message::InputModel(_)
+            | Message::InputYear(_)
+            | Message::InputCapacity(_)
+            | Message::InputLocation(_)
+            | Message::InputTemperature(_)
+            | Message::ClearTemperature
+            | Message::InputNotes(_)
+            | Message::ChangeProduct { .. }
+            | Message::AddBatch
+            | Message::StartUpdate
+            | Message::StartRename
+            | Message::StartAddProduct
+            | Message::Revert(_) => Some(Permission::Update),
+            _ => None,
+        };
+        if required.is_some_and(|permission| !account.can(permission)) {
+            return Some(Effect::Warn(anyio!(
+                "`{}` may not change `{}`",
+                account.login,
+                freezer.name
+            )));
+        }
+
+        match message {
+            Message::InputOwner(owner) => {
+                freezer.owner = (!owner.trim().is_empty()).then_some(owner);
+            }
+            Message::AssignToMe => {
+                if !account.login.is_empty() {
+                    freezer.owner = Some(account.login.clone());
+                }
+            }
+            Message::InputModel(model) => {
+                freezer.model.name = model;
+            }
+            Message::InputYear(year) => {
+                freezer.model.year = year;
+            }
+            Message::InputCapacity(capacity) => {
+                freezer.details.capacity = (capacity > 0).then_some(capacity);
+            }
+            Message::InputLocation(location) => {
+                freezer.details.location = (!location.is_empty()).then_some(location);
+            }
+            Message::InputTemperature(degrees) => {
+                freezer.details.temperature = Some(degrees);
+            }
+            Message::ClearTemperature => {
+                freezer.details.temperature = None;
+            }
+            Message::InputNotes(notes) => {
+                freezer.details.notes = (!notes.is_empty()).then_some(notes);
+            }
+            Message::ChangeProduct { id, amount } => {
+                let (product, units) = &mut freezer.products[id];
+                *units = amount;
+                if let Some(batches) = freezer.batches.get_mut(product.as_str()) {
+                    Batch::trim(batches, amount);
+                }
+            }
+            Message::AddBatch => {
+                let NewBatch {
+                    quantity,
+                    best_before,
+                } = &self.new_batch;
+                let best_before = match best_before.trim() {
+                    "" => None,
+                    date => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                        Ok(date) => Some(date),
+                        Err(error) => {
+                            return Some(Effect::Error(anyio!(
+                                "best-before `{date}` is not `YYYY-MM-DD`: {error}"
+                            )));
                         }
-                        Message::StartUpdate => {
-                            self.state = State::Loading {
-                                on_cancel: box State::Ready,
-                            };
-                            let client = self.client.clone();
-                            let freezer = freezer.clone();
-                            return Command::perform(
-                                async move { client.update_freezer(freezer.into()).await },
-                                Message::OnUpdate,
-                            );
+                    },
+                };
+                let Some((product, units)) = freezer
+                    .products
+                    .iter_mut()
+                    .find(|(product, _)| product == &self.product)
+                else {
+                    return Some(Effect::Error(anyio!(
+                        "add `{}` to the freezer before its batches",
+                        self.product
+                    )));
+                };
+
+                *units += quantity;
+                let batches = freezer.batches.entry(product.clone()).or_default();
+                batches.push(Batch {
+                    quantity: *quantity,
+                    frozen: Some(today()),
+                    best_before,
+                });
+                Batch::sort(batches);
+                self.new_batch = NewBatch::default();
+            }
+            Message::Revert(index) => {
+                // contents only: the freezer may be renamed since
+                let old = model::Freezer {
+                    name: freezer.name.clone(),
+                    ..self.history.as_ref()?.get(index)?.freezer()?.clone()
+                };
+                *freezer = old.clone().into();
+                return Some(Effect::Update {
+                    ticket: self.state.save.begin(self.id.clone()),
+                    freezer: old,
+                });
+            }
+            Message::StartUpdate => {
+                let mut errors: Vec<_> = freezer.validate(today()).into_values().collect();
+                if !errors.is_empty() {
+                    errors.sort();
+                    return Some(Effect::Error(anyio!(
+                        "`{}` is not updated: {}",
+                        freezer.name,
+                        errors.join(", ")
+                    )));
+                }
+                return Some(Effect::Update {
+                    ticket: self.state.save.begin(freezer.name.clone()),
+                    freezer: freezer.clone().into(),
+                });
+            }
+            Message::StartRename => {
+                if let Some(error) = check_name(&self.name) {
+                    return Some(Effect::Error(anyio!(
+                        "`{}` is not renamed: {error}",
+                        freezer.name
+                    )));
+                }
+                // saved under the old id otherwise
+                if self.name == freezer.name || self.state.save.task.is_running() {
+                    return None;
+                }
+                return Some(Effect::Rename {
+                    ticket: self.state.rename.begin(freezer.name.clone()),
+                    old: freezer.name.clone(),
+                    new: self.name.clone(),
+                });
+            }
+            Message::StartDelete => {
+                return Some(Effect::Delete {
+                    ticket: self.state.delete.begin(freezer.name.clone()),
+                    id: freezer.name.clone(),
+                });
+            }
+            Message::StartAddProduct => {
+                return Some(Effect::FindProduct {
+                    ticket: self.state.add_product.begin(freezer.name.clone()),
+                    id: self.product.clone(),
+                });
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl Preview {
+    pub fn perform(&self, effect: Effect) -> Command<Message> {
+        let client = self.client.clone();
+        match effect {
+            Effect::FetchFreezer { id, ticket } => {
+                ticket.perform(Self::fetch_freezer(client, id), Message::FetchInfo)
+            }
+            Effect::FetchImage { id, ticket } => ticket.perform(
+                Self::fetch_image(client, self.images.clone(), id),
+                Message::FetchImage,
+            ),
+            Effect::FetchHistory { id, ticket } => ticket.perform(
+                async move { client.freezer_history(&id).await },
+                Message::FetchHistory,
+            ),
+            Effect::Update { freezer, ticket } => ticket.perform(
+                async move { client.update_freezer(freezer).await },
+                Message::OnUpdate,
+            ),
+            Effect::Delete { id, ticket } => {
+                let images = self.images.clone();
+                ticket.perform(
+                    async move {
+                        let deleted = client.delete_freezer(&id).await;
+                        if matches!(deleted, Ok(true)) {
+                            images.remove(&id).await;
                         }
-                        Message::StartDelete => {
-                            self.state = State::Loading {
-                                on_cancel: box State::Ready,
-                            };
-                            let client = self.client.clone();
-                            let id = freezer.name.clone();
-                            return Command::perform(
-                                async move { client.delete_freezer(&id).await },
-                                Message::OnDelete,
-                            );
+                        deleted
+                    },
+                    Message::OnDelete,
+                )
+            }
+            Effect::Rename { old, new, ticket } => {
+                let images = self.images.clone();
+                let requested = new.clone();
+                ticket.perform(
+                    async move {
+                        let renamed = client.rename_freezer(&old, &new).await;
+                        // the image is kept by the new name
+                        if let Ok(Rename::Renamed(freezer)) = &renamed {
+                            images.rename(&old, &freezer.name).await;
                         }
-                        _ => {}
-                    }
+                        renamed
+                    },
+                    move |res| Message::OnRename(requested.clone(), res),
+                )
+            }
+            Effect::Renamed { old, new } => {
+                Command::perform(async move { (old, new) }, |(old, new)| Message::Renamed {
+                    old,
+                    new,
+                })
+            }
+            Effect::FindProduct { id, ticket } => {
+                let product = id.clone();
+                ticket.perform(async move { client.product(&id).await }, move |res| {
+                    Message::OnAddProduct(product.clone(), res)
+                })
+            }
+            Effect::Error(error) => Command::perform(async move { error }, Message::Error),
+            Effect::Warn(error) => Command::perform(async move { error }, Message::Warn),
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.sheets.iter().any(|sheet| sheet.state.is_busy()) {
+            time::every(Duration::from_millis(100)).map(|_| Message::Tick)
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn status(&self) -> Element<'_, Message> {
+        let spinner = SPINNER[self.spinner % SPINNER.len()];
+        column(
+            self.sheet()
+                .into_iter()
+                .flat_map(|sheet| sheet.state.running())
+                .map(|(operation, freezer)| {
+                    row![
+                        text(format!("{spinner} {} `{freezer}`", operation.label()))
+                            .width(Length::Fill),
+                        button("cancel").on_press(Message::Cancel(operation)),
+                    ]
+                    .spacing(10)
+                    .into()
+                })
+                .collect(),
+        )
+        .into()
+    }
+
+    /// Full-window image viewer, shown by the app over all panes
+    pub fn viewer(&self) -> Option<Element<'_, Message>> {
+        let sheet = self.sheet()?;
+        let (Some(fit), Picture::Ready(photo)) = (sheet.viewer, &sheet.picture) else {
+            return None;
+        };
+
+        let toggle = |label, to| {
+            button(text(label)).pipe(|button| {
+                if fit == to {
+                    button
+                } else {
+                    button.on_press(Message::FitViewer(to))
                 }
-                match message {
-                    Message::StartAddProduct => {
-                        self.state = State::Loading {
-                            on_cancel: box State::Ready,
-                        };
-                        let product = self.product.clone();
-                        let client = self.client.clone();
-                        Command::perform(
-                            async move { client.product(&product).await },
-                            Message::OnAddProduct,
-                        )
-                    }
-                    _ => Command::none(),
+            })
+        };
+
+        let content: Element<_> = match fit {
+            Fit::Window => image(photo.handle.clone())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .content_fit(ContentFit::Contain)
+                .into(),
+            Fit::Actual => Viewer::new(photo.handle.clone())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .min_scale(0.1)
+                .max_scale(10.0)
+                .into(),
+        };
+
+        let head = row![
+            toggle("fit", Fit::Window),
+            toggle("actual size", Fit::Actual),
+            text(photo.metadata()),
+        ]
+        .spacing(10);
+
+        Some(
+            Card::new(head, content)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .on_close(Message::CloseViewer)
+                .into(),
+        )
+    }
+
+    /// Fill of the freezer by its capacity
+    fn gauge<'a>(units: usize, capacity: Option<usize>) -> Element<'a, Message> {
+        let Some(capacity) = capacity.filter(|capacity| *capacity > 0) else {
+            return text(format!("{units} units, capacity is unknown")).into();
+        };
+
+        #[allow(clippy::cast_precision_loss)]
+        let bar = progress_bar(0.0..=capacity as f32, units.min(capacity) as f32)
+            .width(Length::Units(200));
+        let label = if units > capacity {
+            format!("{units}/{capacity} units - over capacity")
+        } else {
+            format!("{units}/{capacity} units, {}%", units * 100 / capacity)
+        };
+        row![bar, text(label)].spacing(10).into()
+    }
+
+    /// Batches of a product, the expired and expiring soon are highlighted
+    fn batches<'a>(batches: &[Batch], today: NaiveDate, palette: &Palette) -> Element<'a, Message> {
+        let date =
+            |date: Option<NaiveDate>| date.map_or_else(|| "?".to_owned(), |date| date.to_string());
+        let line = |batch: &Batch| {
+            let label = match batch.expiry(today) {
+                Expiry::Fresh => "",
+                Expiry::Soon => " - use soon",
+                Expiry::Expired => " - expired",
+            };
+            Badge::new(text(format!(
+                "{} best before {}, frozen {}{label}",
+                batch.quantity,
+                date(batch.best_before),
+                date(batch.frozen)
+            )))
+            .style(expiry_style(batch.expiry(today), palette))
+            .into()
+        };
+        column(batches.iter().map(line).collect())
+            .spacing(2)
+            .padding([0, 0, 5, 30])
+            .into()
+    }
+
+    /// Tabs of the open freezers, `*` marks the unsaved edits
+    fn sheet_bar(&self) -> Element<'_, Message> {
+        let tab = |(index, sheet): (usize, &Sheet)| {
+            let label = if sheet.is_dirty() {
+                format!("{} *", sheet.id)
+            } else {
+                sheet.id.clone()
+            };
+            let style = if index == self.active {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            };
+            // the freezer is changing, the response needs the sheet
+            let close = button("x").pipe(|close| {
+                if sheet.state.is_changing() {
+                    close
+                } else {
+                    close.on_press(Message::CloseSheet(index))
                 }
-            }
+            });
+            row![
+                button(text(label))
+                    .style(style)
+                    .on_press(Message::ShowSheet(index)),
+                close
+            ]
+            .into()
+        };
+        let shift = |label, to: Option<usize>| {
+            button(label).pipe(|button| match to {
+                Some(to) => button.on_press(Message::MoveSheet {
+                    from: self.active,
+                    to,
+                }),
+                None => button,
+            })
+        };
+
+        row![
+            widget::Row::with_children(self.sheets.iter().enumerate().map(tab).collect())
+                .spacing(5),
+            shift("<", self.active.checked_sub(1)),
+            shift(
+                ">",
+                Some(self.active + 1).filter(|to| *to < self.sheets.len())
+            ),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    fn tabs(sheet: &Sheet) -> Element<'_, Message> {
+        let tab = |label, tab| {
+            button(text(label)).pipe(|button| {
+                if sheet.tab == tab {
+                    button
+                } else {
+                    button.on_press(Message::ShowTab(tab))
+                }
+            })
+        };
+        row![tab("DETAILS", Tab::Details), tab("HISTORY", Tab::History)]
+            .spacing(10)
+            .into()
+    }
+
+    /// Changes of the freezer, the newest first
+    fn history_view<'a>(&'a self, sheet: &'a Sheet) -> Element<'a, Message> {
+        let Some(entries) = &sheet.history else {
+            return text("fetching the history...").into();
+        };
+        if entries.is_empty() {
+            return text("no history is kept for this freezer").into();
         }
+
+        let saving = sheet.state.save.task.is_running()
+            || sheet.state.delete.task.is_running()
+            || !self.account.can(Permission::Update);
+        let line = |(index, entry): (usize, &history::Entry)| {
+            let model::Revision { at, user, .. } = &entry.revision;
+            let at = at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S");
+            let user = user.as_deref().unwrap_or("unknown");
+            // the newest one is the current version
+            let revert = button("revert to this version").pipe(|revert| {
+                if index == 0 || saving || entry.freezer().is_none() {
+                    revert
+                } else {
+                    revert.on_press(Message::Revert(index))
+                }
+            });
+            columee![
+                row![text(format!("{at} by {user}")).width(Length::Fill), revert].spacing(10),
+                column(
+                    entry
+                        .diff
+                        .iter()
+                        .map(|line| text(line).size(14).into())
+                        .collect()
+                )
+                .padding([0, 0, 0, 30]),
+                horizontal_rule(10),
+            ]
+            .into()
+        };
+
+        scrollable(column(entries.iter().enumerate().map(line).collect()).spacing(5))
+            .height(Length::FillPortion(30))
+            .into()
     }
 
     pub fn view(&self) -> Element<'_, Message> {
-        let view = if let Some((image, freezer)) = self.info.clone() {
-            Self::ready(&self.product, image, freezer)
+        let Some(sheet) = self.sheet() else {
+            return empty();
+        };
+        let view = match (sheet.info.clone(), sheet.tab) {
+            (Some(freezer), Tab::Details) => self.ready(sheet, freezer),
+            (Some(_), Tab::History) => self.history_view(sheet),
+            (None, _) => empty(),
+        };
+        let tabs = if sheet.info.is_some() {
+            Self::tabs(sheet)
         } else {
             empty()
         };
 
-        Modal::new(
-            matches!(self.state, State::Loading { .. }),
-            container(view).width(Length::Shrink),
-            || {
-                Card::new(
-                    text("Wait please..."),
-                    button(text("Cancel").horizontal_alignment(Horizontal::Center))
-                        .width(Length::Fill)
-                        .on_press(Message::ModalExit),
-                )
-                .max_width(300)
-                //.width(Length::Shrink)
-                .into()
-            },
-        )
+        columee![
+            self.sheet_bar(),
+            self.status(),
+            tabs,
+            container(view).width(Length::Shrink)
+        ]
         .into()
     }
 
-    fn ready<'a>(product: &str, handle: image::Handle, freezer: Freezer) -> Element<'a, Message> {
+    fn ready<'a>(&'a self, sheet: &'a Sheet, freezer: Freezer) -> Element<'a, Message> {
         pub fn tooltip<'a, Message: Clone + 'a>(
             tip: impl ToString,
             content: impl Into<Element<'a, Message>>,
@@ -318,12 +1479,44 @@ impl Preview {
             widget::tooltip(content, tip, Position::FollowCursor)
         }
 
+        let Sheet {
+            name: new_name,
+            product,
+            new_batch,
+            picture,
+            state,
+            ..
+        } = sheet;
+        let Self {
+            thresholds,
+            owners,
+            account,
+            palette,
+            ..
+        } = self;
+        let login = &account.login;
+        let (can_update, can_delete) = (
+            account.can(Permission::Update),
+            account.can(Permission::Delete),
+        );
+        let new_name = new_name.as_str();
+        let errors = freezer.validate(today());
+
         let Freezer {
             name,
             model: Model { name: model, year },
             owner,
+            details:
+                Details {
+                    capacity,
+                    location,
+                    temperature,
+                    notes,
+                },
             products,
+            batches,
         } = freezer;
+        let units: usize = products.iter().map(|(_, amount)| amount).sum();
 
         pub fn text_input<'a, Message: Clone + 'a>(
             place: &str,
@@ -333,7 +1526,15 @@ impl Preview {
             widget::TextInput::new(place, value, on_change)
         }
 
-        let number_input = |place, on_change| NumberInput::new(place, 2022, on_change).min(1999);
+        let today = today();
+        let years = years(today);
+        let number_input =
+            |place, on_change| NumberInput::new(place, *years.end(), on_change).min(*years.start());
+        let error = |field| {
+            errors.get(&field).map_or_else(empty, |error| {
+                text(error).size(14).style(palette.error).pipe(info).into()
+            })
+        };
 
         pub fn info<'a, Message: Clone + 'a>(
             content: impl Into<Element<'a, Message>>,
@@ -341,51 +1542,254 @@ impl Preview {
             Container::new(content).width(Length::Fill).padding(5)
         }
 
+        // read-only accounts see the values without the inputs
+        let editable = |input: Element<'a, Message>, value: String| -> Element<'a, Message> {
+            if can_update {
+                input
+            } else {
+                text(value).into()
+            }
+        };
+
         let content = columee![
-            // todo: fix max width (possible before 800)
-            container(image(handle)).max_height(400),
+            picture.view(),
+            row![
+                tooltip(
+                    "name",
+                    text_input("Name cannot be empty", new_name, Message::InputName)
+                        .on_submit(Message::StartRename)
+                ),
+                button("RENAME").pipe(|rename| {
+                    let busy = state.save.task.is_running()
+                        || state.delete.task.is_running()
+                        || state.rename.task.is_running();
+                    if !can_update || busy || new_name == name || check_name(new_name).is_some() {
+                        rename
+                    } else {
+                        rename.on_press(Message::StartRename)
+                    }
+                }),
+            ]
+            .spacing(10)
+            .pipe(info),
+            check_name(new_name).map_or_else(empty, |error| {
+                text(error).size(14).style(palette.error).pipe(info).into()
+            }),
+            row![
+                tooltip(
+                    "owner",
+                    editable(
+                        pick_list(
+                            std::iter::once(Owner::Nobody)
+                                .chain(owners.iter().cloned().map(Owner::User))
+                                .collect::<Vec<_>>(),
+                            Some(Owner::from(owner.clone())),
+                            |owner: Owner| {
+                                Message::InputOwner(owner.login().unwrap_or_default().to_owned())
+                            }
+                        )
+                        .into(),
+                        Owner::from(owner.clone()).to_string()
+                    )
+                ),
+                button("assign to me").pipe(|assign| {
+                    if !can_update || login.is_empty() || owner.as_ref() == Some(login) {
+                        assign
+                    } else {
+                        assign.on_press(Message::AssignToMe)
+                    }
+                }),
+            ]
+            .spacing(10)
+            .pipe(info),
             tooltip(
-                "name",
-                text_input("Name cannot be empty", &name, |_| Message::Silent)
+                "model",
+                editable(
+                    text_input("Name cannot be empty", &model, Message::InputModel).into(),
+                    model
+                )
             )
             .pipe(info),
+            error(Field::Model),
             tooltip(
-                "owner",
-                text_input("None", &owner.unwrap_or_default(), Message::InputOwner)
+                "year",
+                editable(
+                    number_input(year, Message::InputYear).into(),
+                    year.to_string()
+                )
             )
             .pipe(info),
+            error(Field::Year),
             tooltip(
-                "model",
-                text_input("Name cannot be empty", &model, Message::InputModel)
+                "location",
+                editable(
+                    text_input(
+                        "unknown",
+                        location.as_deref().unwrap_or_default(),
+                        Message::InputLocation
+                    )
+                    .into(),
+                    location.unwrap_or_default()
+                )
+            )
+            .pipe(info),
+            row![
+                tooltip(
+                    "target temperature, °C",
+                    editable(
+                        NumberInput::new(temperature.unwrap_or(-18), 20, Message::InputTemperature)
+                            .min(-60)
+                            .into(),
+                        temperature
+                            .map(|degrees| degrees.to_string())
+                            .unwrap_or_default()
+                    )
+                ),
+                text(if temperature.is_some() {
+                    "°C"
+                } else {
+                    "°C (unset)"
+                }),
+                button("unset").pipe(|unset| {
+                    if can_update && temperature.is_some() {
+                        unset.on_press(Message::ClearTemperature)
+                    } else {
+                        unset
+                    }
+                }),
+            ]
+            .spacing(10)
+            .pipe(info),
+            tooltip(
+                "notes",
+                editable(
+                    text_input(
+                        "no notes",
+                        notes.as_deref().unwrap_or_default(),
+                        Message::InputNotes
+                    )
+                    .into(),
+                    notes.unwrap_or_default()
+                )
             )
             .pipe(info),
-            tooltip("year", number_input(year, Message::InputYear)).pipe(info),
+            row![
+                tooltip(
+                    "capacity in units, 0 if unknown",
+                    editable(
+                        NumberInput::new(capacity.unwrap_or(0), usize::MAX, Message::InputCapacity)
+                            .into(),
+                        capacity
+                            .map(|capacity| capacity.to_string())
+                            .unwrap_or_default()
+                    )
+                ),
+                Self::gauge(units, capacity),
+            ]
+            .spacing(10)
+            .pipe(info),
             columee![
                 row![
                     text("PRODUCTS").size(40),
-                    text_input("new product", product, Message::InputProduct)
-                        .on_submit(Message::StartAddProduct)
+                    text_input("new product", product, Message::InputProduct).pipe(|input| {
+                        if can_update {
+                            input.on_submit(Message::StartAddProduct)
+                        } else {
+                            input
+                        }
+                    })
                 ],
+                if can_update {
+                    row![
+                        text_input(
+                            "best before YYYY-MM-DD",
+                            &new_batch.best_before,
+                            Message::InputBestBefore
+                        )
+                        .on_submit(Message::AddBatch),
+                        NumberInput::new(new_batch.quantity, usize::MAX, Message::InputBatch)
+                            .min(1),
+                        button("ADD BATCH").on_press(Message::AddBatch),
+                    ]
+                    .spacing(10)
+                    .into()
+                } else {
+                    empty()
+                },
                 column(
                     products
                         .into_iter()
                         .enumerate()
-                        .map(|(id, (product, amount))| Badge::new(row![
-                            text(product).size(25),
-                            NumberInput::new(amount, usize::MAX, move |amount| {
-                                Message::ChangeProduct { id, amount }
-                            })
-                        ])
-                        .style(BadgeStyles::Info)
-                        .into())
+                        .map(|(id, (product, amount))| {
+                            let level = thresholds.level(&name, &product, amount);
+                            let minimum = thresholds.minimum(&name, &product).unwrap_or(0);
+                            let overridden = thresholds
+                                .freezers
+                                .get(&name)
+                                .is_some_and(|overrides| overrides.contains_key(&product));
+                            let reset = Message::ChangeMinimum {
+                                freezer: name.clone(),
+                                product: product.clone(),
+                                minimum: None,
+                            };
+                            let (freezer, key) = (name.clone(), product.clone());
+                            let dated = batches.get(&product).map_or(&[][..], Vec::as_slice);
+                            let badge = Badge::new(row![
+                                text(product).size(25),
+                                editable(
+                                    NumberInput::new(amount, usize::MAX, move |amount| {
+                                        Message::ChangeProduct { id, amount }
+                                    })
+                                    .into(),
+                                    amount.to_string()
+                                ),
+                                text("min"),
+                                NumberInput::new(minimum, usize::MAX, move |minimum| {
+                                    Message::ChangeMinimum {
+                                        freezer: freezer.clone(),
+                                        product: key.clone(),
+                                        minimum: Some(minimum),
+                                    }
+                                }),
+                                tooltip(
+                                    "back to the threshold of the product",
+                                    button("reset").pipe(|button| {
+                                        if overridden {
+                                            button.on_press(reset)
+                                        } else {
+                                            button
+                                        }
+                                    })
+                                ),
+                            ])
+                            .style(alerts::style(level, palette));
+                            columee![badge, Self::batches(dated, today, palette)].into()
+                        })
                         .collect()
                 )
                 .width(Length::Fill) //.height(Length::Fill)
             ],
             horizontal_rule(10),
             row![
-                button("UPDATE").on_press(Message::StartUpdate),
-                button("DELETE").on_press(Message::StartDelete),
+                // disabled while invalid, not permitted or the same request is in-flight
+                button("UPDATE").pipe(|update| {
+                    if !can_update
+                        || !errors.is_empty()
+                        || state.save.task.is_running()
+                        || state.delete.task.is_running()
+                    {
+                        update
+                    } else {
+                        update.on_press(Message::StartUpdate)
+                    }
+                }),
+                button("DELETE").pipe(|delete| {
+                    if !can_delete || state.delete.task.is_running() {
+                        delete
+                    } else {
+                        delete.on_press(Message::StartDelete)
+                    }
+                }),
             ]
         ];
 
@@ -0,0 +1,666 @@
+use super::{Effect, Field, Message, Operation, Preview, Tab, today};
+use crate::{
+    api::FreezerApi,
+    cache::{Entry, ImageCache},
+    client::{Client, Rename},
+    model::{self, Account, Batch, Details, Permission, Product, Revision},
+    task::Ticket,
+    tests::temp_dir,
+    utils::error::anyio,
+    views::test_support::{self, client, ticket},
+};
+use bytesize::ByteSize;
+use chrono::Datelike;
+use mock_server::{Fault, MockServer, PIXEL, Route, Seed};
+use std::sync::Arc;
+
+/// Effects are never performed, so the backend is never reached
+fn preview() -> Preview {
+    Preview::new(client())
+}
+
+/// Holds 3 of pelmeni
+fn freezer(name: &str) -> super::Freezer {
+    test_support::freezer(name, &[("pelmeni", 3)]).into()
+}
+
+/// Requests `name` and completes the fetch of its data
+fn shown(preview: &mut Preview, name: &str) {
+    let effects = preview.reduce(Message::FetchRequest(name.to_owned()));
+    let ticket = fetch_ticket(effects);
+    preview.reduce(Message::FetchInfo(ticket.resolve(Ok(freezer(name)))));
+}
+
+fn fetch_ticket(effects: Vec<Effect>) -> Ticket {
+    ticket!(effects, Effect::FetchFreezer { ticket, .. } => ticket)
+}
+
+fn single(mut effects: Vec<Effect>) -> Effect {
+    assert_eq!(effects.len(), 1, "{effects:?}");
+    effects.remove(0)
+}
+
+#[test]
+fn fetch_request() {
+    let mut preview = preview();
+
+    let effects = preview.reduce(Message::FetchRequest("kitchen".into()));
+    assert!(matches!(
+        &effects[..],
+        [Effect::FetchFreezer { id: a, .. }, Effect::FetchImage { id: b, .. }]
+            if a == "kitchen" && b == "kitchen"
+    ));
+    assert!(preview.sheet().unwrap().state.is_busy());
+
+    let ticket = fetch_ticket(effects);
+    assert!(
+        preview
+            .reduce(Message::FetchInfo(ticket.resolve(Ok(freezer("kitchen")))))
+            .is_empty()
+    );
+    assert_eq!(preview.shown(), Some("kitchen"));
+}
+
+#[test]
+fn tabs_keep_their_edits() {
+    let mut preview = preview();
+
+    let kitchen = fetch_ticket(preview.reduce(Message::FetchRequest("kitchen".into())));
+    let garage = fetch_ticket(preview.reduce(Message::FetchRequest("garage".into())));
+    assert_eq!(preview.sheets.len(), 2);
+
+    // the response goes to the tab of its request
+    preview.reduce(Message::FetchInfo(kitchen.resolve(Ok(freezer("kitchen")))));
+    assert!(preview.sheet().unwrap().info.is_none());
+    preview.reduce(Message::FetchInfo(garage.resolve(Ok(freezer("garage")))));
+    assert_eq!(preview.shown(), Some("garage"));
+
+    preview.reduce(Message::InputModel("Bosch".into()));
+    assert!(preview.sheet().unwrap().is_dirty());
+
+    // the open one is shown as is, its edits are kept
+    assert!(preview.reduce(Message::ShowSheet(0)).is_empty());
+    assert_eq!(preview.shown(), Some("kitchen"));
+    assert!(
+        preview
+            .reduce(Message::FetchRequest("garage".into()))
+            .is_empty()
+    );
+    assert_eq!(
+        preview.sheet().unwrap().info.as_ref().unwrap().model.name,
+        "Bosch"
+    );
+
+    // still shown in front of the kitchen
+    preview.reduce(Message::MoveSheet { from: 1, to: 0 });
+    assert_eq!(preview.active, 0);
+    assert_eq!(preview.shown(), Some("garage"));
+
+    let effect = single(preview.reduce(Message::CloseSheet(0)));
+    assert!(
+        matches!(effect, Effect::Warn(error) if error.to_string() == "unsaved edits of `garage` are dropped")
+    );
+    assert_eq!(preview.shown(), Some("kitchen"));
+
+    // saved edits are not marked
+    preview.reduce(Message::InputModel("Bosch".into()));
+    let Effect::Update { freezer, ticket } = single(preview.reduce(Message::StartUpdate)) else {
+        panic!("update is requested");
+    };
+    preview.reduce(Message::OnUpdate(ticket.resolve(Ok(Some(freezer)))));
+    assert!(!preview.sheet().unwrap().is_dirty());
+}
+
+#[test]
+fn cancel() {
+    let mut preview = preview();
+
+    let ticket = fetch_ticket(preview.reduce(Message::FetchRequest("kitchen".into())));
+    preview.reduce(Message::Cancel(Operation::Fetch));
+
+    assert!(
+        preview
+            .reduce(Message::FetchInfo(ticket.resolve(Ok(freezer("kitchen")))))
+            .is_empty()
+    );
+    assert!(preview.sheet().unwrap().info.is_none());
+}
+
+#[test]
+fn fetch_error() {
+    let mut preview = preview();
+
+    let ticket = fetch_ticket(preview.reduce(Message::FetchRequest("kitchen".into())));
+    let effect = single(preview.reduce(Message::FetchInfo(ticket.resolve(Err(anyio!("oops"))))));
+    assert!(matches!(effect, Effect::Error(_)));
+}
+
+#[test]
+fn add_duplicate_product() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::InputProduct("pelmeni".into()));
+    let Effect::FindProduct { id, ticket } = single(preview.reduce(Message::StartAddProduct))
+    else {
+        panic!("product is requested");
+    };
+    assert_eq!(id, "pelmeni");
+
+    let product = Product {
+        name: "pelmeni".into(),
+        default: 5,
+    };
+    let effect =
+        single(preview.reduce(Message::OnAddProduct(id, ticket.resolve(Ok(Some(product))))));
+    assert!(matches!(effect, Effect::Error(_)));
+    assert_eq!(
+        preview
+            .sheet()
+            .unwrap()
+            .info
+            .as_ref()
+            .unwrap()
+            .products
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn add_product() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::InputProduct("berries".into()));
+    let Effect::FindProduct { id, ticket } = single(preview.reduce(Message::StartAddProduct))
+    else {
+        panic!("product is requested");
+    };
+    let product = Product {
+        name: "berries".into(),
+        default: 2,
+    };
+    assert!(
+        preview
+            .reduce(Message::OnAddProduct(id, ticket.resolve(Ok(Some(product)))))
+            .is_empty()
+    );
+    assert_eq!(
+        preview.sheet().unwrap().info.as_ref().unwrap().products[1],
+        ("berries".to_owned(), 2)
+    );
+}
+
+#[test]
+fn unknown_product_is_the_requested_one() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::InputProduct("berries".into()));
+    let Effect::FindProduct { id, ticket } = single(preview.reduce(Message::StartAddProduct))
+    else {
+        panic!("product is requested");
+    };
+    // typed on while the product is looked up
+    preview.reduce(Message::InputProduct("cherries".into()));
+
+    let Effect::Error(error) =
+        single(preview.reduce(Message::OnAddProduct(id, ticket.resolve(Ok(None)))))
+    else {
+        panic!("unknown product is reported");
+    };
+    assert_eq!(error.to_string(), "Not found product `berries`");
+}
+
+#[test]
+fn refused_update() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::InputOwner("admin".into()));
+    let Effect::Update { freezer, ticket } = single(preview.reduce(Message::StartUpdate)) else {
+        panic!("update is requested");
+    };
+    assert_eq!(freezer.owner.as_deref(), Some("admin"));
+
+    let effect = single(preview.reduce(Message::OnUpdate(ticket.resolve(Ok(None)))));
+    assert!(matches!(effect, Effect::Warn(_)));
+    assert!(!preview.sheet().unwrap().state.is_busy());
+}
+
+#[test]
+fn delete_shown() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    let Effect::Delete { id, ticket } = single(preview.reduce(Message::StartDelete)) else {
+        panic!("delete is requested");
+    };
+    assert_eq!(id, "kitchen");
+
+    // freezer is going away - edits are ignored
+    assert!(preview.reduce(Message::StartUpdate).is_empty());
+    preview.reduce(Message::InputModel("Bosch".into()));
+    assert_eq!(
+        preview.sheet().unwrap().info.as_ref().unwrap().model.name,
+        "Atlant"
+    );
+
+    // its tab is closed
+    assert!(
+        preview
+            .reduce(Message::OnDelete(ticket.resolve(Ok(true))))
+            .is_empty()
+    );
+    assert!(preview.sheets.is_empty());
+}
+
+#[test]
+fn refused_delete() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    let Effect::Delete { ticket, .. } = single(preview.reduce(Message::StartDelete)) else {
+        panic!("delete is requested");
+    };
+    let effect = single(preview.reduce(Message::OnDelete(ticket.resolve(Ok(false)))));
+    assert!(matches!(effect, Effect::Warn(_)));
+    assert_eq!(preview.shown(), Some("kitchen"));
+}
+
+#[test]
+fn permissions() {
+    let mut preview = preview();
+    preview.set_account(Account {
+        permissions: Some([Permission::Update].into()),
+        ..Account::unknown("editor")
+    });
+    shown(&mut preview, "kitchen");
+
+    let effect = single(preview.reduce(Message::StartDelete));
+    assert!(
+        matches!(effect, Effect::Warn(error) if error.to_string() == "`editor` may not change `kitchen`")
+    );
+    assert!(!preview.sheet().unwrap().state.is_busy());
+    assert!(matches!(
+        single(preview.reduce(Message::StartUpdate)),
+        Effect::Update { .. }
+    ));
+
+    preview.set_account(Account {
+        permissions: Some([].into()),
+        ..Account::unknown("guest")
+    });
+    for message in [
+        Message::StartUpdate,
+        Message::StartRename,
+        Message::Revert(1),
+        Message::InputModel("Indesit".into()),
+        Message::AssignToMe,
+        Message::ChangeProduct { id: 0, amount: 0 },
+        Message::AddBatch,
+    ] {
+        assert!(matches!(single(preview.reduce(message)), Effect::Warn(_)));
+    }
+    assert_ne!(
+        preview.sheet().unwrap().info.as_ref().unwrap().model.name,
+        "Indesit"
+    );
+}
+
+#[test]
+fn add_batch() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::InputProduct("berries".into()));
+    let effect = single(preview.reduce(Message::AddBatch));
+    assert!(matches!(effect, Effect::Error(_)));
+
+    preview.reduce(Message::InputProduct("pelmeni".into()));
+    preview.reduce(Message::InputBestBefore("next week".into()));
+    let effect = single(preview.reduce(Message::AddBatch));
+    assert!(matches!(effect, Effect::Error(_)));
+
+    preview.reduce(Message::InputBatch(2));
+    preview.reduce(Message::InputBestBefore("2022-12-01".into()));
+    assert!(preview.reduce(Message::AddBatch).is_empty());
+    preview.reduce(Message::InputBestBefore("2022-11-20".into()));
+    assert!(preview.reduce(Message::AddBatch).is_empty());
+
+    let info = preview.sheet().unwrap().info.as_ref().unwrap();
+    assert_eq!(info.products[0], ("pelmeni".to_owned(), 6));
+    let dates: Vec<_> = info.batches["pelmeni"]
+        .iter()
+        .map(|batch| (batch.quantity, batch.best_before.unwrap().to_string()))
+        .collect();
+    assert_eq!(dates, [(1, "2022-11-20".into()), (2, "2022-12-01".into())]);
+}
+
+#[test]
+fn expiring_batches_are_used_first() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    let batch = |quantity, best_before: &str| Batch {
+        quantity,
+        frozen: None,
+        best_before: Some(best_before.parse().unwrap()),
+    };
+    preview.sheets[preview.active]
+        .info
+        .as_mut()
+        .unwrap()
+        .batches
+        .insert(
+            "pelmeni".into(),
+            vec![batch(1, "2022-11-20"), batch(2, "2022-12-01")],
+        );
+
+    preview.reduce(Message::ChangeProduct { id: 0, amount: 1 });
+    let Effect::Update { freezer, .. } = single(preview.reduce(Message::StartUpdate)) else {
+        panic!("update is requested");
+    };
+    assert_eq!(freezer.products["pelmeni"], 1);
+    assert_eq!(freezer.batches["pelmeni"], [batch(1, "2022-12-01")]);
+}
+
+#[test]
+fn edit_details() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::InputCapacity(40));
+    preview.reduce(Message::InputLocation("garage".into()));
+    preview.reduce(Message::InputTemperature(-20));
+    preview.reduce(Message::InputNotes("defrost in May".into()));
+    let Effect::Update { freezer, .. } = single(preview.reduce(Message::StartUpdate)) else {
+        panic!("update is requested");
+    };
+    assert_eq!(freezer.details.capacity, Some(40));
+    assert_eq!(freezer.details.temperature, Some(-20));
+
+    // cleared fields are unset, not empty
+    preview.reduce(Message::InputCapacity(0));
+    preview.reduce(Message::InputLocation(String::new()));
+    preview.reduce(Message::ClearTemperature);
+    preview.reduce(Message::InputNotes(String::new()));
+    assert_eq!(
+        preview.sheet().unwrap().info.as_ref().unwrap().details,
+        Details::default()
+    );
+}
+
+fn history_ticket(effects: Vec<Effect>) -> Ticket {
+    ticket!(effects, Effect::FetchHistory { ticket, .. } => ticket)
+}
+
+fn revision(minute: u32, user: &str, pelmeni: usize) -> Revision {
+    let mut freezer: model::Freezer = freezer("kitchen").into();
+    freezer.products.insert("pelmeni".into(), pelmeni);
+    Revision {
+        at: format!("2022-11-01T12:{minute:02}:00Z").parse().unwrap(),
+        user: Some(user.to_owned()),
+        freezer: Some(freezer),
+    }
+}
+
+#[test]
+fn history_follows_navigation() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    let ticket = history_ticket(preview.reduce(Message::ShowTab(Tab::History)));
+    preview.reduce(Message::FetchHistory(
+        ticket.resolve(Ok(vec![revision(0, "admin", 3), revision(5, "guest", 1)])),
+    ));
+    let history = preview.sheet().unwrap().history.as_ref().unwrap();
+    assert_eq!(history[0].revision.user.as_deref(), Some("guest"));
+    assert_eq!(history[0].diff, ["~ pelmeni: 3 -> 1"]);
+
+    // known history is not fetched again
+    preview.reduce(Message::ShowTab(Tab::Details));
+    assert!(preview.reduce(Message::ShowTab(Tab::History)).is_empty());
+
+    // every open freezer has its own page
+    let effects = preview.reduce(Message::FetchRequest("garage".into()));
+    assert_eq!(effects.len(), 2, "{effects:?}");
+
+    // fetched again with the history
+    let effects = preview.reduce(Message::FetchRequest("kitchen".into()));
+    assert_eq!(effects.len(), 3, "{effects:?}");
+    assert!(preview.sheet().unwrap().history.is_none());
+}
+
+#[test]
+fn revert() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    let ticket = history_ticket(preview.reduce(Message::ShowTab(Tab::History)));
+    preview.reduce(Message::FetchHistory(
+        ticket.resolve(Ok(vec![revision(0, "admin", 5), revision(5, "guest", 3)])),
+    ));
+
+    let Effect::Update { freezer, ticket } = single(preview.reduce(Message::Revert(1))) else {
+        panic!("update is requested");
+    };
+    assert_eq!(freezer.products["pelmeni"], 5);
+    assert_eq!(
+        preview.sheet().unwrap().info.as_ref().unwrap().products[0].1,
+        5
+    );
+
+    // the revert is a new revision
+    let effect = single(preview.reduce(Message::OnUpdate(ticket.resolve(Ok(Some(freezer))))));
+    assert!(matches!(effect, Effect::FetchHistory { id, .. } if id == "kitchen"));
+
+    // unknown entry
+    assert!(preview.reduce(Message::Revert(5)).is_empty());
+}
+
+#[test]
+fn revert_keeps_the_new_name() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::InputName("pantry".into()));
+    let Effect::Rename { ticket, .. } = single(preview.reduce(Message::StartRename)) else {
+        panic!("rename is requested");
+    };
+    preview.reduce(Message::OnRename(
+        "pantry".into(),
+        ticket.resolve(Ok(Rename::Renamed(freezer("pantry").into()))),
+    ));
+
+    let mut renamed = revision(5, "admin", 3);
+    renamed.freezer.as_mut().unwrap().name = "pantry".into();
+    let ticket = history_ticket(preview.reduce(Message::ShowTab(Tab::History)));
+    preview.reduce(Message::FetchHistory(
+        ticket.resolve(Ok(vec![revision(0, "admin", 5), renamed])),
+    ));
+
+    // the entry from before the rename
+    let Effect::Update { freezer, ticket } = single(preview.reduce(Message::Revert(1))) else {
+        panic!("update is requested");
+    };
+    assert_eq!(freezer.name, "pantry");
+    assert_eq!(freezer.products["pelmeni"], 5);
+    assert_eq!(preview.shown(), Some("pantry"));
+    assert_eq!(
+        preview.sheet().unwrap().info.as_ref().unwrap().name,
+        "pantry"
+    );
+
+    let effect = single(preview.reduce(Message::OnUpdate(ticket.resolve(Ok(Some(freezer))))));
+    assert!(matches!(effect, Effect::FetchHistory { id, .. } if id == "pantry"));
+}
+
+#[test]
+fn invalid_edits_are_not_saved() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::InputModel("  ".into()));
+    let next = usize::try_from(today().year()).unwrap() + 1;
+    preview.reduce(Message::InputYear(next));
+    let errors = preview
+        .sheet()
+        .unwrap()
+        .info
+        .as_ref()
+        .unwrap()
+        .validate(today());
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    assert!(errors.contains_key(&Field::Model) && errors.contains_key(&Field::Year));
+
+    let effect = single(preview.reduce(Message::StartUpdate));
+    assert!(matches!(effect, Effect::Error(_)));
+    assert!(!preview.sheet().unwrap().state.is_busy());
+
+    preview.reduce(Message::InputModel("Bosch".into()));
+    preview.reduce(Message::InputYear(next - 1));
+    assert!(
+        preview
+            .sheet()
+            .unwrap()
+            .info
+            .as_ref()
+            .unwrap()
+            .validate(today())
+            .is_empty()
+    );
+}
+
+#[test]
+fn empty_owner_is_unset() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::InputOwner("admin".into()));
+    preview.reduce(Message::InputOwner(String::new()));
+    let Effect::Update { freezer, .. } = single(preview.reduce(Message::StartUpdate)) else {
+        panic!("update is requested");
+    };
+    assert_eq!(freezer.owner, None);
+}
+
+#[test]
+fn rename() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    // nothing to rename
+    assert!(preview.reduce(Message::StartRename).is_empty());
+
+    preview.reduce(Message::InputName("pan/try".into()));
+    let effect = single(preview.reduce(Message::StartRename));
+    assert!(matches!(effect, Effect::Error(_)));
+
+    preview.reduce(Message::InputName("pantry".into()));
+    preview.reduce(Message::InputModel("Bosch".into()));
+    let Effect::Rename { old, new, ticket } = single(preview.reduce(Message::StartRename)) else {
+        panic!("rename is requested");
+    };
+    assert_eq!((old.as_str(), new.as_str()), ("kitchen", "pantry"));
+
+    // the id is changing - nothing to save
+    assert!(preview.reduce(Message::StartUpdate).is_empty());
+
+    let renamed = freezer("pantry").into();
+    let effects = preview.reduce(Message::OnRename(
+        new,
+        ticket.resolve(Ok(Rename::Renamed(renamed))),
+    ));
+    assert!(matches!(
+        &effects[..],
+        [Effect::Renamed { old, new }] if old == "kitchen" && new == "pantry"
+    ));
+    assert_eq!(preview.shown(), Some("pantry"));
+    // unsaved edits are kept
+    assert_eq!(
+        preview.sheet().unwrap().info.as_ref().unwrap().model.name,
+        "Bosch"
+    );
+}
+
+#[test]
+fn rename_to_taken() {
+    let mut preview = preview();
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::InputName("garage".into()));
+    let Effect::Rename { new, ticket, .. } = single(preview.reduce(Message::StartRename)) else {
+        panic!("rename is requested");
+    };
+    // the input is changed while the request is in flight
+    preview.reduce(Message::InputName("pantry".into()));
+    let effect = single(preview.reduce(Message::OnRename(new, ticket.resolve(Ok(Rename::Taken)))));
+    assert!(matches!(effect, Effect::Error(error)
+        if error.to_string() == "`kitchen` is not renamed: `garage` is taken by another freezer"));
+    assert_eq!(preview.shown(), Some("kitchen"));
+}
+
+#[test]
+fn assign_to_me() {
+    let mut preview = preview();
+    preview.set_account(Account::unknown("admin"));
+    preview.add_owners(vec!["guest".into()]);
+    shown(&mut preview, "kitchen");
+
+    preview.reduce(Message::AssignToMe);
+    assert_eq!(
+        preview
+            .sheet()
+            .unwrap()
+            .info
+            .as_ref()
+            .unwrap()
+            .owner
+            .as_deref(),
+        Some("admin")
+    );
+
+    // owners of the shown freezers become candidates
+    let effects = preview.reduce(Message::FetchRequest("garage".into()));
+    let mut garage = freezer("garage");
+    garage.owner = Some("cook".into());
+    preview.reduce(Message::FetchInfo(
+        fetch_ticket(effects).resolve(Ok(garage)),
+    ));
+    assert_eq!(preview.owners(), ["cook", "guest"]);
+}
+
+#[tokio::test]
+async fn image_is_revalidated() {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let client: Arc<dyn FreezerApi> = Arc::new(Client::new(&server.api(), reqwest::Client::new()));
+    let cache = Arc::new(ImageCache::new(1, Some(temp_dir())));
+    let size = |photo: Option<super::Photo>| photo.map(|photo| photo.size);
+    let fetch = || Preview::fetch_image(client.clone(), cache.clone(), "kitchen".to_owned());
+
+    let photo = fetch().await.unwrap();
+    assert_eq!(size(photo), Some(ByteSize::b(PIXEL.len() as u64)));
+    let etag = cache.get("kitchen").await.unwrap().etag;
+    assert!(etag.is_some());
+
+    // the tag still matches: the server answers 304, the cached bytes are shown
+    let cached = Entry {
+        etag,
+        bytes: b"cached".as_slice().into(),
+    };
+    cache.insert("kitchen", cached).await;
+    assert_eq!(size(fetch().await.unwrap()), Some(ByteSize::b(6)));
+
+    // the changed image replaces the cached one
+    server.put_image("kitchen", b"changed image".to_vec());
+    assert_eq!(size(fetch().await.unwrap()), Some(ByteSize::b(13)));
+    let entry = cache.get("kitchen").await.unwrap();
+    assert_eq!(entry.bytes, b"changed image".as_slice());
+
+    // the cached one is kept while the server fails
+    server.inject(Route::Image, Fault::Status(503));
+    assert_eq!(size(fetch().await.unwrap()), Some(ByteSize::b(13)));
+    assert_eq!(server.hits(Route::Image), 4);
+}
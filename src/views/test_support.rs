@@ -0,0 +1,46 @@
+//! Fixtures shared by the tests of the views
+
+use crate::{
+    api::FreezerApi,
+    client::Client,
+    model::{Details, Freezer, Model},
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// Effects are never performed, so the backend is never reached
+pub fn client() -> Arc<dyn FreezerApi> {
+    Arc::new(Client::new("http://test", reqwest::Client::new()))
+}
+
+/// Atlant of 2015 with `products`
+pub fn freezer(name: &str, products: &[(&str, usize)]) -> Freezer {
+    Freezer {
+        name: name.to_owned(),
+        model: Model {
+            name: "Atlant".to_owned(),
+            year: 2015,
+        },
+        owner: None,
+        details: Details::default(),
+        products: products
+            .iter()
+            .map(|(product, amount)| ((*product).to_owned(), *amount))
+            .collect(),
+        batches: HashMap::new(),
+    }
+}
+
+/// Ticket of the first effect matching the pattern,
+/// e.g. `ticket!(effects, Effect::Scan { ticket, .. } => ticket)`
+macro_rules! ticket {
+    ($effects:expr, $pattern:pat => $ticket:expr) => {
+        $effects
+            .into_iter()
+            .find_map(|effect| match effect {
+                $pattern => Some($ticket),
+                _ => None,
+            })
+            .expect(concat!("request is issued: ", stringify!($pattern)))
+    };
+}
+pub(crate) use ticket;
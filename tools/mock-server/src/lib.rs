@@ -0,0 +1,618 @@
+//! In-process mock of the freezers API for integration tests and demos.
+//!
+//! Serves the same routes as the real backend under `/api` with seeded data,
+//! and allows to inject faults per route.
+//!
+//! # Examples
+//! ```no_run
+//! # use mock_server::{Fault, MockServer, Route, Seed};
+//! # #[tokio::main]
+//! # async fn main() -> std::io::Result<()> {
+//! let server = MockServer::start(Seed::demo()).await?;
+//!
+//! // every product lookup fails from now on
+//! server.inject(Route::Product, Fault::Status(500));
+//!
+//! let api = server.api(); // pass it to `Client::new`
+//! # Ok(())
+//! # }
+//! ```
+
+use axum::{
+    Extension, Json, Router,
+    body::Bytes,
+    extract::{Path, Query},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use json::Value;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, hash_map::DefaultHasher},
+    hash::{Hash, Hasher},
+    io,
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+use tokio::{sync::oneshot, task::JoinHandle};
+
+/// Routes of the freezers API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Route {
+    /// `GET auth` - the login with its roles and permissions
+    Auth,
+    /// `GET freezers`
+    Freezers,
+    /// `GET freezers/{id}`
+    Freezer,
+    /// `GET freezers/{id}/image`
+    Image,
+    /// `POST freezers/update`
+    Update,
+    /// `DELETE freezers/{id}`
+    Delete,
+    /// `GET products/{id}`
+    Product,
+    /// `GET summary` - units per product and per freezer
+    Summary,
+    /// `GET freezers/{id}/history` - revisions of the freezer, the oldest first
+    History,
+    /// `POST freezers/{id}/rename` with `{ "name": .. }`
+    Rename,
+    /// `GET users` - logins of the users, sorted
+    Users,
+    /// `POST freezers/transfer` with `{ "from": .., "to": .. }` - both are stored at once
+    Transfer,
+}
+
+/// Fault injected into every request of the route until it's cleared
+#[derive(Debug, Clone, Copy)]
+pub enum Fault {
+    /// Respond with the status code and empty body
+    Status(u16),
+    /// Wait before handling the request as usual
+    Delay(Duration),
+    /// Respond `200 OK` with a body that isn't valid JSON
+    Malformed,
+    /// Ignore `limit` and `offset` of the freezers, as servers without paging do
+    Unpaged,
+}
+
+/// Initial data of the server
+#[derive(Debug, Clone, Default)]
+pub struct Seed {
+    pub users: Vec<String>,
+    /// Roles by login, `auth` of the users without roles answers with the login only
+    /// (as older servers do). Known roles: `admin`, `editor` and `viewer`
+    pub roles: HashMap<String, Vec<String>>,
+    /// Freezers as the backend stores them: `{ "_id": .., "model": .., .. }`
+    pub freezers: Vec<Value>,
+    pub products: Vec<Value>,
+    /// Images by freezer id
+    pub images: Vec<(String, Vec<u8>)>,
+}
+
+impl Seed {
+    /// Small inventory used by tests and the `mock-server` binary
+    pub fn demo() -> Self {
+        Self {
+            users: vec!["admin".into(), "guest".into()],
+            roles: HashMap::from([
+                ("admin".into(), vec!["admin".into()]),
+                ("guest".into(), vec!["viewer".into()]),
+            ]),
+            freezers: vec![
+                json::json!({
+                    "_id": "kitchen",
+                    "model": { "name": "Atlant", "year": 2015 },
+                    "owner": "admin",
+                    "capacity": 40,
+                    "location": "kitchen, by the window",
+                    "temperature": -18,
+                    "products": { "pelmeni": 3, "ice-cream": 10 },
+                    "batches": {
+                        "pelmeni": [
+                            { "quantity": 2, "frozen": "2022-10-01", "best_before": "2023-04-01" },
+                        ],
+                    },
+                }),
+                json::json!({
+                    "_id": "garage",
+                    "model": { "name": "Indesit", "year": 2008 },
+                    "owner": null,
+                    "products": { "pelmeni": 1 },
+                }),
+                json::json!({
+                    "_id": "office",
+                    "model": { "name": "Bosch", "year": 2020 },
+                    "owner": "guest",
+                    "products": {},
+                }),
+            ],
+            products: vec![
+                json::json!({ "_id": "pelmeni", "default": 5 }),
+                json::json!({ "_id": "ice-cream", "default": 4 }),
+                json::json!({ "_id": "berries", "default": 2 }),
+            ],
+            images: vec![("kitchen".into(), PIXEL.to_vec())],
+        }
+    }
+}
+
+/// 1x1 transparent PNG
+pub const PIXEL: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4,
+    0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0x00, 0x01, 0x00, 0x00,
+    0x05, 0x00, 0x01, 0x0d, 0x0a, 0x2d, 0xb4, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae,
+    0x42, 0x60, 0x82,
+];
+
+#[derive(Debug, Default)]
+struct Data {
+    users: HashSet<String>,
+    roles: HashMap<String, Vec<String>>,
+    freezers: BTreeMap<String, Value>,
+    products: BTreeMap<String, Value>,
+    images: HashMap<String, Vec<u8>>,
+    /// Login of the last successful `auth`, the mock serves a single session
+    session: Option<String>,
+    /// Revisions by freezer ids: `{ "at": .., "user": .., "freezer": .. }`
+    history: HashMap<String, Vec<Value>>,
+
+    faults: HashMap<Route, Fault>,
+    hits: HashMap<Route, usize>,
+}
+
+impl From<Seed> for Data {
+    fn from(
+        Seed {
+            users,
+            roles,
+            freezers,
+            products,
+            images,
+        }: Seed,
+    ) -> Self {
+        let by_id = |values: Vec<Value>| {
+            values
+                .into_iter()
+                .filter_map(|value| Some((value.get("_id")?.as_str()?.to_owned(), value)))
+                .collect()
+        };
+        let mut data = Self {
+            users: users.into_iter().collect(),
+            roles,
+            freezers: by_id(freezers),
+            products: by_id(products),
+            images: images.into_iter().collect(),
+            ..Data::default()
+        };
+        let seeded: Vec<_> = data.freezers.clone().into_iter().collect();
+        for (id, freezer) in seeded {
+            data.record(&id, Some(freezer));
+        }
+        data
+    }
+}
+
+impl Data {
+    /// Appends the revision by the session user, `None` if the freezer is deleted
+    fn record(&mut self, id: &str, freezer: Option<Value>) {
+        let revision = json::json!({
+            "at": chrono::Utc::now().to_rfc3339(),
+            "user": self.session,
+            "freezer": freezer,
+        });
+        self.history
+            .entry(id.to_owned())
+            .or_default()
+            .push(revision);
+    }
+}
+
+type Shared = Arc<Mutex<Data>>;
+
+fn lock(data: &Shared) -> MutexGuard<'_, Data> {
+    data.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Counts the hit and applies the injected fault of the route,
+/// `Err` is the response to return instead of handling the request
+async fn enter(data: &Shared, route: Route) -> Result<(), Response> {
+    let fault = {
+        let mut data = lock(data);
+        *data.hits.entry(route).or_default() += 1;
+        data.faults.get(&route).copied()
+    };
+    match fault {
+        None | Some(Fault::Unpaged) => Ok(()),
+        Some(Fault::Delay(delay)) => {
+            tokio::time::sleep(delay).await;
+            Ok(())
+        }
+        Some(Fault::Status(status)) => Err(StatusCode::from_u16(status)
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+            .into_response()),
+        Some(Fault::Malformed) => Err((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            "{ malformed",
+        )
+            .into_response()),
+    }
+}
+
+fn etag(bytes: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    format!("\"{:x}\"", hasher.finish())
+}
+
+#[derive(Debug, Deserialize)]
+struct Login {
+    login: String,
+}
+
+async fn auth(
+    Extension(data): Extension<Shared>,
+    Json(Login { login }): Json<Login>,
+) -> Result<Response, Response> {
+    enter(&data, Route::Auth).await?;
+    let mut data = lock(&data);
+    Ok(if data.users.contains(&login) {
+        data.session = Some(login.clone());
+        let account = match data.roles.get(&login) {
+            Some(roles) => {
+                let permissions: &[&str] = if roles.iter().any(|role| role == "admin") {
+                    &["update", "delete"]
+                } else if roles.iter().any(|role| role == "editor") {
+                    &["update"]
+                } else {
+                    &[]
+                };
+                json::json!({ "login": login, "roles": roles, "permissions": permissions })
+            }
+            None => json::json!({ "login": login }),
+        };
+        Json(account).into_response()
+    } else {
+        StatusCode::UNAUTHORIZED.into_response()
+    })
+}
+
+/// `owner` and `unowned` filter the freezers before paging
+#[derive(Debug, Deserialize)]
+struct Page {
+    limit: Option<usize>,
+    offset: Option<usize>,
+    owner: Option<String>,
+    #[serde(default)]
+    unowned: bool,
+}
+
+async fn freezers(
+    Extension(data): Extension<Shared>,
+    Query(Page {
+        limit,
+        offset,
+        owner,
+        unowned,
+    }): Query<Page>,
+) -> Result<Response, Response> {
+    enter(&data, Route::Freezers).await?;
+    let data = lock(&data);
+    let (limit, offset) = match data.faults.get(&Route::Freezers) {
+        Some(Fault::Unpaged) => (None, None),
+        _ => (limit, offset),
+    };
+    let ids: Vec<_> = data
+        .freezers
+        .iter()
+        .filter(|(_, freezer)| {
+            let of = freezer.get("owner").and_then(Value::as_str);
+            (!unowned || of.is_none()) && owner.as_deref().map_or(true, |owner| of == Some(owner))
+        })
+        .map(|(id, _)| id)
+        .skip(offset.unwrap_or_default())
+        .take(limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect();
+    Ok(Json(ids).into_response())
+}
+
+async fn freezer(
+    Extension(data): Extension<Shared>,
+    Path(id): Path<String>,
+) -> Result<Response, Response> {
+    enter(&data, Route::Freezer).await?;
+    Ok(match lock(&data).freezers.get(&id) {
+        Some(freezer) => Json(freezer.clone()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    })
+}
+
+async fn image(
+    Extension(data): Extension<Shared>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, Response> {
+    enter(&data, Route::Image).await?;
+    let Some(bytes) = lock(&data).images.get(&id).cloned() else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    let etag = etag(&bytes);
+    let is_cached = headers
+        .get(header::IF_NONE_MATCH)
+        .is_some_and(|tag| tag.as_bytes() == etag.as_bytes());
+    Ok(if is_cached {
+        (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response()
+    } else {
+        ([(header::ETAG, etag)], Bytes::from(bytes)).into_response()
+    })
+}
+
+async fn update(
+    Extension(data): Extension<Shared>,
+    Json(freezer): Json<Value>,
+) -> Result<Response, Response> {
+    enter(&data, Route::Update).await?;
+    let Some(id) = freezer.get("_id").and_then(Value::as_str) else {
+        return Ok(StatusCode::BAD_REQUEST.into_response());
+    };
+    let mut data = lock(&data);
+    data.freezers.insert(id.to_owned(), freezer.clone());
+    data.record(id, Some(freezer.clone()));
+    Ok(Json(freezer).into_response())
+}
+
+async fn delete(
+    Extension(data): Extension<Shared>,
+    Path(id): Path<String>,
+) -> Result<Response, Response> {
+    enter(&data, Route::Delete).await?;
+    let mut data = lock(&data);
+    data.images.remove(&id);
+    Ok(match data.freezers.remove(&id) {
+        Some(_) => {
+            data.record(&id, None);
+            StatusCode::OK
+        }
+        None => StatusCode::NOT_FOUND,
+    }
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+struct NewName {
+    name: String,
+}
+
+/// The image and the history follow the freezer,
+/// `409 Conflict` if another freezer has the name
+async fn rename(
+    Extension(data): Extension<Shared>,
+    Path(id): Path<String>,
+    Json(NewName { name }): Json<NewName>,
+) -> Result<Response, Response> {
+    enter(&data, Route::Rename).await?;
+    if name.trim().is_empty() {
+        return Ok(StatusCode::BAD_REQUEST.into_response());
+    }
+    let mut data = lock(&data);
+    if data.freezers.contains_key(&name) {
+        return Ok(StatusCode::CONFLICT.into_response());
+    }
+    let Some(mut freezer) = data.freezers.remove(&id) else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    freezer["_id"] = Value::String(name.clone());
+    data.freezers.insert(name.clone(), freezer.clone());
+    if let Some(image) = data.images.remove(&id) {
+        data.images.insert(name.clone(), image);
+    }
+    if let Some(history) = data.history.remove(&id) {
+        data.history.insert(name.clone(), history);
+    }
+    data.record(&name, Some(freezer.clone()));
+    Ok(Json(freezer).into_response())
+}
+
+#[derive(Debug, Deserialize)]
+struct Pair {
+    from: Value,
+    to: Value,
+}
+
+/// Stores both freezers or none, `422 Unprocessable Entity` if either does not exist
+async fn transfer(
+    Extension(data): Extension<Shared>,
+    Json(Pair { from, to }): Json<Pair>,
+) -> Result<Response, Response> {
+    enter(&data, Route::Transfer).await?;
+    let (Some(from_id), Some(to_id)) = (
+        from.get("_id").and_then(Value::as_str),
+        to.get("_id").and_then(Value::as_str),
+    ) else {
+        return Ok(StatusCode::BAD_REQUEST.into_response());
+    };
+    let mut data = lock(&data);
+    if !data.freezers.contains_key(from_id) || !data.freezers.contains_key(to_id) {
+        return Ok(StatusCode::UNPROCESSABLE_ENTITY.into_response());
+    }
+    for (id, freezer) in [(from_id, &from), (to_id, &to)] {
+        data.freezers.insert(id.to_owned(), freezer.clone());
+        data.record(id, Some(freezer.clone()));
+    }
+    Ok(Json(json::json!({ "from": from, "to": to })).into_response())
+}
+
+async fn product(
+    Extension(data): Extension<Shared>,
+    Path(id): Path<String>,
+) -> Result<Response, Response> {
+    enter(&data, Route::Product).await?;
+    Ok(match lock(&data).products.get(&id) {
+        Some(product) => Json(product.clone()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    })
+}
+
+async fn users(Extension(data): Extension<Shared>) -> Result<Response, Response> {
+    enter(&data, Route::Users).await?;
+    let mut users: Vec<_> = lock(&data).users.iter().cloned().collect();
+    users.sort();
+    Ok(Json(users).into_response())
+}
+
+async fn summary(Extension(data): Extension<Shared>) -> Result<Response, Response> {
+    enter(&data, Route::Summary).await?;
+    let mut products = BTreeMap::<String, u64>::new();
+    let mut freezers = BTreeMap::new();
+    for (id, freezer) in &lock(&data).freezers {
+        let mut units = 0;
+        if let Some(contents) = freezer.get("products").and_then(Value::as_object) {
+            for (product, amount) in contents {
+                let amount = amount.as_u64().unwrap_or_default();
+                *products.entry(product.clone()).or_default() += amount;
+                units += amount;
+            }
+        }
+        freezers.insert(id.clone(), units);
+    }
+    Ok(Json(json::json!({ "products": products, "freezers": freezers })).into_response())
+}
+
+async fn history(
+    Extension(data): Extension<Shared>,
+    Path(id): Path<String>,
+) -> Result<Response, Response> {
+    enter(&data, Route::History).await?;
+    Ok(match lock(&data).history.get(&id) {
+        Some(revisions) => Json(revisions.clone()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    })
+}
+
+fn router(data: Shared) -> Router {
+    Router::new()
+        .route("/api/auth", get(auth))
+        .route("/api/freezers", get(freezers))
+        .route("/api/freezers/update", post(update))
+        .route("/api/freezers/transfer", post(transfer))
+        .route("/api/freezers/:id", get(freezer).delete(delete))
+        .route("/api/freezers/:id/image", get(image))
+        .route("/api/freezers/:id/history", get(history))
+        .route("/api/freezers/:id/rename", post(rename))
+        .route("/api/products/:id", get(product))
+        .route("/api/summary", get(summary))
+        .route("/api/users", get(users))
+        .layer(Extension(data))
+}
+
+/// Running mock server, it's stopped on drop
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    data: Shared,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts the server on a free local port
+    pub async fn start(seed: Seed) -> io::Result<Self> {
+        Self::bind(([127, 0, 0, 1], 0).into(), seed).await
+    }
+
+    pub async fn bind(addr: SocketAddr, seed: Seed) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let data = Arc::new(Mutex::new(Data::from(seed)));
+        let (shutdown, signal) = oneshot::channel();
+
+        let server = axum::Server::from_tcp(listener)
+            .map_err(io::Error::other)?
+            .serve(router(data.clone()).into_make_service())
+            .with_graceful_shutdown(async {
+                let _ = signal.await;
+            });
+        let handle = tokio::spawn(async {
+            let _ = server.await;
+        });
+
+        Ok(Self {
+            addr,
+            data,
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    pub const fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Base url for `Client::new`
+    pub fn api(&self) -> String {
+        format!("http://{}/api", self.addr)
+    }
+
+    pub fn inject(&self, route: Route, fault: Fault) {
+        lock(&self.data).faults.insert(route, fault);
+    }
+
+    pub fn clear(&self, route: Route) {
+        lock(&self.data).faults.remove(&route);
+    }
+
+    /// Count of requests to the route (including the faulty ones)
+    pub fn hits(&self, route: Route) -> usize {
+        lock(&self.data)
+            .hits
+            .get(&route)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn freezer(&self, id: &str) -> Option<Value> {
+        lock(&self.data).freezers.get(id).cloned()
+    }
+
+    /// Changes the freezer behind the client's back (e.g. to make conflicts)
+    pub fn put_freezer(&self, freezer: Value) {
+        if let Some(id) = freezer.get("_id").and_then(Value::as_str) {
+            let mut data = lock(&self.data);
+            data.freezers.insert(id.to_owned(), freezer.clone());
+            data.record(id, Some(freezer.clone()));
+        }
+    }
+
+    pub fn put_image(&self, id: &str, bytes: Vec<u8>) {
+        lock(&self.data).images.insert(id.to_owned(), bytes);
+    }
+
+    /// Stops accepting connections and waits for the in-flight ones
+    pub async fn stop(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.await;
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use mock_server::{MockServer, Seed};
+use std::{env, io};
+
+/// Serves the demo inventory on the default client address (or the one from args)
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:1228".to_owned())
+        .parse()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+
+    let server = MockServer::bind(addr, Seed::demo()).await?;
+    println!("serving demo freezers at {}", server.api());
+
+    tokio::signal::ctrl_c().await?;
+    server.stop().await;
+    Ok(())
+}
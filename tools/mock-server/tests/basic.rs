@@ -0,0 +1,76 @@
+use mock_server::{Fault, MockServer, Route, Seed};
+use std::time::Duration;
+
+#[tokio::test]
+async fn basic() {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let client = reqwest::Client::new();
+    let api = server.api();
+
+    let ids: Vec<String> = client
+        .get(format!("{api}/freezers"))
+        .query(&[("limit", 2), ("offset", 1)])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(ids, ["kitchen", "office"]);
+
+    let res = client
+        .get(format!("{api}/freezers/garage"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let res = client
+        .get(format!("{api}/freezers/none"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 404);
+
+    server.inject(Route::Freezer, Fault::Status(503));
+    let res = client
+        .get(format!("{api}/freezers/garage"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 503);
+    server.clear(Route::Freezer);
+
+    assert_eq!(server.hits(Route::Freezer), 3);
+}
+
+#[tokio::test]
+async fn image_etag() {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    let client = reqwest::Client::new();
+    let url = format!("{}/freezers/kitchen/image", server.api());
+
+    let res = client.get(&url).send().await.unwrap();
+    let etag = res.headers()["etag"].clone();
+    assert_eq!(res.bytes().await.unwrap().as_ref(), mock_server::PIXEL);
+
+    let res = client
+        .get(&url)
+        .header("if-none-match", etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 304);
+}
+
+#[tokio::test]
+async fn delay() {
+    let server = MockServer::start(Seed::demo()).await.unwrap();
+    server.inject(Route::Freezers, Fault::Delay(Duration::from_secs(5)));
+
+    let res = reqwest::Client::new()
+        .get(format!("{}/freezers", server.api()))
+        .timeout(Duration::from_millis(100))
+        .send()
+        .await;
+    assert!(res.unwrap_err().is_timeout());
+}